@@ -9,10 +9,10 @@
 # │     --no-exit      -n          Loop back to theme list after install.   │
 # │     --force        -f          Skip confirmation prompts.               │
 # │     --cycle        -c          Cycle through all themes one by one.     │
-# │     --workspace    -w  <wm>    Bypass workspace picker. <wm>: Sway,     │
-# │                                Hypr, Niri, or None.                     │
-# │     --focused      -fw <wm>    Bypass focused window picker. <wm>:      │
+# │     --workspace    -w  <wm>    Bypass workspace picker. <wm>: Auto,     │
 # │                                Sway, Hypr, Niri, or None.               │
+# │     --focused      -fw <wm>    Bypass focused window picker. <wm>:      │
+# │                                Auto, Sway, Hypr, Niri, or None.         │
 # │     --select      -s  <name>   Directly install theme by name.           │
 # │     --help         -h          Show this help message and exit.          │
 # ╰─────────────────────────────────────────────────────────────────────────╯
@@ -21,8 +21,8 @@
 NO_EXIT=false
 FORCE=false
 CYCLE=false
-BYPASS_WM=""    # Sway | Hypr | Niri | None
-BYPASS_FW=""    # Sway | Hypr | Niri | None
+BYPASS_WM=""    # Auto | Sway | Hypr | Niri | None
+BYPASS_FW=""    # Auto | Sway | Hypr | Niri | None
 SELECT=""       # theme name to install directly
 
 # ── Help ───────────────────────────────────────────────────────────────────
@@ -43,7 +43,7 @@ print_help()
     echo -e "    ${CYAN}-s${RESET}, ${CYAN}--select${RESET}     ${DIM}<name>${RESET}    Directly install a theme by name."
     echo -e "    ${CYAN}-h${RESET}, ${CYAN}--help${RESET}                 Show this help message and exit."
     echo
-    echo -e "  ${BWHITE}<w> and ${BWHITE}<fw> values:${RESET}  ${WHITE}Sway${RESET}  ${WHITE}Hypr${RESET}  ${WHITE}Niri${RESET}  ${WHITE}None${RESET}"
+    echo -e "  ${BWHITE}<w> and ${BWHITE}<fw> values:${RESET}  ${WHITE}Auto${RESET}  ${WHITE}Sway${RESET}  ${WHITE}Hypr${RESET}  ${WHITE}Niri${RESET}  ${WHITE}None${RESET}"
     echo
     echo -e "  ${BWHITE}Examples:${RESET}"
     echo -e "    ${DIM}./icebar-theme-switcher.sh${RESET}"
@@ -60,12 +60,13 @@ resolve_wm_arg()
     local val="${1,,}"   # lowercase
     local type="$2"
     case "$val" in
+        auto)  echo "Auto" ;;
         sway)  echo "Sway" ;;
         hypr)  echo "Hypr" ;;
         niri)  echo "Niri" ;;
         none)  echo "None" ;;
         *)
-            echo -e "  ${RED}${BOLD}✗${RESET}  Unknown ${type} compositor '${1}'. Valid values: Sway, Hypr, Niri, None." >&2
+            echo -e "  ${RED}${BOLD}✗${RESET}  Unknown ${type} compositor '${1}'. Valid values: Auto, Sway, Hypr, Niri, None." >&2
             exit 1
             ;;
     esac
@@ -149,6 +150,7 @@ pick_module() {
             *Sway*)  wm="Sway"     ;;
             *Hypr*)  wm="Hyprland" ;;
             *Niri*)  wm="Niri"     ;;
+            Workspaces|FocusedWindow) wm="Auto" ;;
             *)       wm=""         ;;
         esac
         labels+=("$wm")
@@ -166,7 +168,8 @@ pick_module() {
     echo
     print_warn "This theme uses the ${BOLD}${found_module}${RESET} module."
     print_info "These modules are window-manager specific and only work"
-    print_info "in their respective compositors."
+    print_info "in their respective compositors, the Auto one follows"
+    print_info "whichever compositor is running."
     echo
     echo -e "  ${BWHITE}Which module would you like to use?${RESET}"
     echo
@@ -195,7 +198,7 @@ pick_module() {
     elif [[ -n "$_PICK_BYPASS" ]]; then
         # Find the module whose name contains the bypass fragment
         for i in "${!modules[@]}"; do
-            if [[ "${modules[$i]}" == *"${_PICK_BYPASS}"* ]]; then
+            if [[ "${modules[$i]}" == *"${_PICK_BYPASS}"* || "${labels[$i]}" == "${_PICK_BYPASS}" ]]; then
                 bypass="${modules[$i]}"
                 CHOSEN_MODULE="$bypass"
                 CHOSEN_MODULE_LABEL="${labels[$i]}"
@@ -359,11 +362,11 @@ fi  # end of select mode
 fi  # end of cycle/manual selection
 
 # ── Workspace module detection ─────────────────────────────────────────────
-WM_MODULES=("SwayWorkspaces" "HyprWorkspaces" "NiriWorkspaces")
+WM_MODULES=("Workspaces" "SwayWorkspaces" "HyprWorkspaces" "NiriWorkspaces")
 
 FOUND_WM_MODULE=""
 for module in "${WM_MODULES[@]}"; do
-    if grep -v "^[[:space:]]*//" "$CHOSEN_CONFIG" | grep -q "modules:.*$module"; then
+    if grep -v "^[[:space:]]*//" "$CHOSEN_CONFIG" | grep -q "modules:.*\b$module\b"; then
         FOUND_WM_MODULE="$module"
         break
     fi
@@ -383,11 +386,11 @@ if [[ -n "$FOUND_WM_MODULE" ]]; then
 fi
 
 # ── FocusedWindow module detection ─────────────────────────────────────────
-FW_MODULES=("FocusedWindowSway" "FocusedWindowHypr" "FocusedWindowNiri")
+FW_MODULES=("FocusedWindow" "FocusedWindowSway" "FocusedWindowHypr" "FocusedWindowNiri")
 
 FOUND_FW_MODULE=""
 for module in "${FW_MODULES[@]}"; do
-    if grep -v "^[[:space:]]*//" "$CHOSEN_CONFIG" | grep -q "modules:.*$module"; then
+    if grep -v "^[[:space:]]*//" "$CHOSEN_CONFIG" | grep -q "modules:.*\b$module\b"; then
         FOUND_FW_MODULE="$module"
         break
     fi
@@ -493,10 +496,10 @@ if cp "$CHOSEN_CONFIG" "$ICEBAR_CONFIG"; then
     if [[ -n "$FOUND_WM_MODULE" ]]; then
         if [[ -z "$CHOSEN_WM_MODULE" ]]; then
             # Strip only the token from the modules line, preserving everything else
-            sed -i "/modules:.*${FOUND_WM_MODULE}/{s/,[ ]*${FOUND_WM_MODULE}//g;s/${FOUND_WM_MODULE}[ ]*,[ ]*//g;s/${FOUND_WM_MODULE}//g}" "$ICEBAR_CONFIG"
+            sed -i "/modules:.*\\b${FOUND_WM_MODULE}\\b/{s/,[ ]*\\b${FOUND_WM_MODULE}\\b//g;s/\\b${FOUND_WM_MODULE}\\b[ ]*,[ ]*//g;s/\\b${FOUND_WM_MODULE}\\b//g}" "$ICEBAR_CONFIG"
             print_info "Workspace module removed: ${DIM}${FOUND_WM_MODULE}${RESET}"
         elif [[ "$CHOSEN_WM_MODULE" != "$FOUND_WM_MODULE" ]]; then
-            sed -i "/modules:/s/${FOUND_WM_MODULE}/${CHOSEN_WM_MODULE}/g" "$ICEBAR_CONFIG"
+            sed -i "/modules:/s/\\b${FOUND_WM_MODULE}\\b/${CHOSEN_WM_MODULE}/g" "$ICEBAR_CONFIG"
             print_info "Workspace module replaced: ${DIM}${FOUND_WM_MODULE}${RESET} → ${CYAN}${BOLD}${CHOSEN_WM_MODULE}${RESET}"
         fi
     fi
@@ -504,10 +507,10 @@ if cp "$CHOSEN_CONFIG" "$ICEBAR_CONFIG"; then
     # ── Patch FocusedWindow module ─────────────────────────────────────────
     if [[ -n "$FOUND_FW_MODULE" ]]; then
         if [[ -z "$CHOSEN_FW_MODULE" ]]; then
-            sed -i "/modules:.*${FOUND_FW_MODULE}/{s/,[ ]*${FOUND_FW_MODULE}//g;s/${FOUND_FW_MODULE}[ ]*,[ ]*//g;s/${FOUND_FW_MODULE}//g}" "$ICEBAR_CONFIG"
+            sed -i "/modules:.*\\b${FOUND_FW_MODULE}\\b/{s/,[ ]*\\b${FOUND_FW_MODULE}\\b//g;s/\\b${FOUND_FW_MODULE}\\b[ ]*,[ ]*//g;s/\\b${FOUND_FW_MODULE}\\b//g}" "$ICEBAR_CONFIG"
             print_info "FocusedWindow module removed: ${DIM}${FOUND_FW_MODULE}${RESET}"
         elif [[ "$CHOSEN_FW_MODULE" != "$FOUND_FW_MODULE" ]]; then
-            sed -i "/modules:/s/${FOUND_FW_MODULE}/${CHOSEN_FW_MODULE}/g" "$ICEBAR_CONFIG"
+            sed -i "/modules:/s/\\b${FOUND_FW_MODULE}\\b/${CHOSEN_FW_MODULE}/g" "$ICEBAR_CONFIG"
             print_info "FocusedWindow module replaced: ${DIM}${FOUND_FW_MODULE}${RESET} → ${CYAN}${BOLD}${CHOSEN_FW_MODULE}${RESET}"
         fi
     fi
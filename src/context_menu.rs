@@ -1,13 +1,14 @@
 // ============ IMPORTS ============
-use iced::{Alignment, Element, Font, Length, Task, Theme, border::Radius, widget::{button, column, container, row, text}};
-use iced_layershell::reexport::{Anchor, Layer, NewLayerShellSettings};
+use iced::{Alignment, Background, Element, Font, Length, Rectangle, Task, Theme, advanced::widget::{Id, Operation, operate, operation::Outcome}, border::Radius, keyboard::{Key, key::Named}, widget::{Space, button, column, container, image, rich_text, row, span, text}};
+use iced_layershell::reexport::{Anchor, KeyboardInteractivity, Layer, NewLayerShellSettings};
 use serde::{Serialize, Deserialize};
+use std::time::{Duration, Instant};
 
 
 
 
 // ============ CRATES ============
-use crate::helpers::{color::ColorType, style::{TextOrientation, orient_text, set_style}, };
+use crate::helpers::{color::ColorType, icons::{load_icon_cached, try_load_icon_sized}, style::{TextOrientation, orient_text, set_style}, };
 use crate::ron::{BarConfig, BarPosition};
 use crate::modules::tray::MenuItem;
 use crate::{AppData, WindowInfo};
@@ -39,6 +40,9 @@ pub struct ContextMenuConfig
     pub context_menu_border_color:               ColorType,
     pub context_menu_border_size:                f32,
     pub context_menu_border_radius:              [f32; 4],
+    pub context_menu_separator_size:             u32,
+    pub context_menu_separator_color:            ColorType,
+    pub context_menu_icon_size:                  u32,
 }
 
 
@@ -67,19 +71,131 @@ impl Default for ContextMenuConfig
             context_menu_border_color:               ColorType::RGB([130, 90, 140]),
             context_menu_border_size:                1.0,
             context_menu_border_radius:              [3.0, 3.0, 3.0, 3.0],
+            context_menu_separator_size:             9,
+            context_menu_separator_color:            ColorType::RGB([90, 80, 100]),
+            context_menu_icon_size:                  16,
         }
     }
 }
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UserMenuItem
+{
+    pub label:      String,
+    pub icon:       Option<String>,
+    pub command:    Vec<String>,
+    pub children:   Vec<UserMenuItem>,
+    pub separator:  bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum MenuIcon
+{
+    Glyph(String),
+    Image(image::Handle),
+}
+
+#[derive(Default, Clone, Debug, PartialEq)]
+pub enum MenuSource
+{
+    #[default] Tray,
+    User(String),
+}
+
 #[derive(Default, Clone)]
 pub struct ContextMenuData 
 {
     pub context_menu_is_open: bool,
     pub cursor_is_inside_menu: bool,
     pub mouse_position: (i32, i32),
+    pub bar_mouse_position: (i32, i32),
     pub items: Vec<MenuItem>,
     pub default_font: Font,
     pub service: String,
-    pub path: String
+    pub path: String,
+    pub focused_index: Option<usize>,
+    pub submenu_path: Vec<usize>,
+    pub type_ahead: String,
+    pub type_ahead_last_key: Option<Instant>,
+    pub source: MenuSource,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PopupPlacement
+{
+    pub anchor: Anchor,
+    pub margin: (i32, i32, i32, i32),
+}
+
+/// Collects the layout bounds of the module containers tagged with `module_anchor_id`.
+struct ModuleBoundsOperation
+{
+    candidates: Vec<Id>,
+    found: Vec<Rectangle>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuKeyOutcome
+{
+    Nothing,
+    Resize,
+    Activate(usize),
+}
+
+
+
+impl Operation<Vec<Rectangle>> for ModuleBoundsOperation
+{
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation<Vec<Rectangle>>))
+    {
+        operate(self);
+    }
+
+    fn container(&mut self, id: Option<&Id>, bounds: Rectangle)
+    {
+        if let Some(id) = id && self.candidates.contains(id) { self.found.push(bounds); }
+    }
+
+    fn finish(&self) -> Outcome<Vec<Rectangle>>
+    {
+        Outcome::Some(self.found.clone())
+    }
+}
+
+
+
+impl ContextMenuData
+{
+    /// Items of the submenu currently shown, or the top level when no submenu is open.
+    pub fn current_items(&self) -> &[MenuItem]
+    {
+        let mut items = self.items.as_slice();
+        for index in &self.submenu_path
+        {
+            match items.get(*index)
+            {
+                Some(item) => items = item.children.as_slice(),
+                None => break,
+            }
+        }
+        items
+    }
+
+    pub fn open_submenu(&mut self, index: usize) -> bool
+    {
+        if self.current_items().get(index).is_none_or(|item| item.children.is_empty()) { return false; }
+        self.submenu_path.push(index);
+        self.focused_index = step_focus(self.current_items(), None, true);
+        true
+    }
+
+    pub fn close_submenu(&mut self) -> bool
+    {
+        let Some(parent_index) = self.submenu_path.pop() else { return false; };
+        self.focused_index = Some(parent_index);
+        true
+    }
 }
 
 
@@ -87,7 +203,81 @@ pub struct ContextMenuData
 // ============ FUNCTIONS ============
 pub fn create_context_menu(app: &mut AppData) -> Task<Message>
 {
-    let anchor_position = match app.ron_config.general.bar_position
+    let id = iced::window::Id::unique();
+    app.ids.insert(id, WindowInfo::ContextMenu);
+    if app.ron_config.general.force_static_position_context_menu.is_some()
+    {
+        let size = get_context_menu_size(&app.context_menu_data, &app.ron_config);
+        return popup_layer_shell(id, size, cursor_popup_placement(app, size), KeyboardInteractivity::Exclusive);
+    }
+    resolve_popup_anchor(app, id)
+}
+
+
+
+/// The window id is reserved right away, the surface itself is only created once the bounds of the clicked module are known.
+pub fn resolve_popup_anchor(app: &AppData, id: iced::window::Id) -> Task<Message>
+{
+    let general = &app.ron_config.general;
+    let candidates = [("left", general.left_modules.len()), ("center", general.center_modules.len()), ("right", general.right_modules.len())]
+        .into_iter()
+        .flat_map(|(side, count)| (0..count).map(move |index| Id::from(module_anchor_id(side, index))))
+        .collect();
+    operate(ModuleBoundsOperation { candidates, found: Vec::new() }).map(move |bounds| Message::PopupAnchorResolved(id, bounds))
+}
+
+
+
+/// Bounds of a single module container, for popups that already know which module they belong to.
+pub fn module_bounds(anchor: &str) -> Task<Vec<Rectangle>>
+{
+    operate(ModuleBoundsOperation { candidates: vec![Id::from(anchor.to_string())], found: Vec::new() })
+}
+
+
+
+pub fn module_anchor_id(side: &str, index: usize) -> String
+{
+    format!("icebar-module-{side}-{index}")
+}
+
+
+
+/// Smallest module rectangle containing the cursor, so a module nested inside another one wins.
+pub fn pick_anchor_bounds(bounds: &[Rectangle], cursor: (i32, i32)) -> Option<Rectangle>
+{
+    let point = iced::Point::new(cursor.0 as f32, cursor.1 as f32);
+    bounds.iter().filter(|rect| rect.contains(point)).min_by(|a, b| a.area().total_cmp(&b.area())).copied()
+}
+
+
+
+/// Places the popup next to the bar, aligned with the module along the bar and flipped to end at the module's far edge when it would overflow the output.
+/// The bar spans its whole edge, so the module bounds along it are already output coordinates.
+pub fn anchored_popup_placement(bar_position: &BarPosition, widget: Rectangle, popup_size: (u32, u32), screen_size: (u32, u32), gap: i32) -> PopupPlacement
+{
+    let along = |start: f32, length: f32, popup: u32, screen: u32| -> i32
+    {
+        let (start, end, popup, screen) = (start.round() as i32, (start + length).round() as i32, popup as i32, screen as i32);
+        let position = if start + popup <= screen { start } else { end - popup };
+        position.clamp(0, (screen - popup).max(0))
+    };
+    let gap = gap.max(0);
+    match bar_position
+    {
+        BarPosition::Up => PopupPlacement { anchor: Anchor::Top | Anchor::Left, margin: (gap, 0, 0, along(widget.x, widget.width, popup_size.0, screen_size.0)) },
+        BarPosition::Down => PopupPlacement { anchor: Anchor::Bottom | Anchor::Left, margin: (0, 0, gap, along(widget.x, widget.width, popup_size.0, screen_size.0)) },
+        BarPosition::Left => PopupPlacement { anchor: Anchor::Left | Anchor::Top, margin: (along(widget.y, widget.height, popup_size.1, screen_size.1), 0, 0, gap) },
+        BarPosition::Right => PopupPlacement { anchor: Anchor::Right | Anchor::Top, margin: (along(widget.y, widget.height, popup_size.1, screen_size.1), gap, 0, 0) },
+    }
+}
+
+
+
+/// Fallback used when the clicked module can't be found (or the position is forced in the config).
+pub fn cursor_popup_placement(app: &AppData, popup_size: (u32, u32)) -> PopupPlacement
+{
+    let anchor = match app.ron_config.general.bar_position
     {
         BarPosition::Down => Anchor::Bottom | Anchor::Left,
         BarPosition::Up => Anchor::Top | Anchor::Left,
@@ -95,30 +285,31 @@ pub fn create_context_menu(app: &mut AppData) -> Task<Message>
         BarPosition::Right => Anchor::Right | Anchor::Top, 
     };
 
-    let context_menu_size = get_context_menu_size(&app.context_menu_data, &app.ron_config);
-
-    let (context_menu_pos_x, context_menu_pos_y) = if let Some(forced_values) = app.ron_config.general.force_static_position_context_menu
+    let (pos_x, pos_y) = if let Some(forced_values) = app.ron_config.general.force_static_position_context_menu
     {
         forced_values
     }
     else 
     {
-        smart_popup_position(app.context_menu_data.mouse_position.0, app.context_menu_data.mouse_position.1, app.monitor_size.0 as i32, app.monitor_size.1 as i32, context_menu_size.0 as i32, context_menu_size.1 as i32)
+        smart_popup_position(app.context_menu_data.mouse_position.0, app.context_menu_data.mouse_position.1, app.monitor_size.0 as i32, app.monitor_size.1 as i32, popup_size.0 as i32, popup_size.1 as i32)
     };
+    PopupPlacement { anchor, margin: (pos_y, 0, 0, pos_x) }
+}
 
 
-    let id = iced::window::Id::unique();
-    app.ids.insert(id, WindowInfo::ContextMenu);
+
+pub fn popup_layer_shell(id: iced::window::Id, size: (u32, u32), placement: PopupPlacement, keyboard_interactivity: KeyboardInteractivity) -> Task<Message>
+{
     Task::done(Message::NewLayerShell 
     {
         settings: NewLayerShellSettings 
         {
             layer: Layer::Overlay,
-            size: Some((context_menu_size.0, context_menu_size.1)),
+            size: Some(size),
             exclusive_zone: Some(0),
-            keyboard_interactivity: iced_layershell::reexport::KeyboardInteractivity::Exclusive,
-            anchor: anchor_position,
-            margin: Some((context_menu_pos_y, 0, 0, context_menu_pos_x)),
+            keyboard_interactivity,
+            anchor: placement.anchor,
+            margin: Some(placement.margin),
             ..Default::default()
         },
         id,
@@ -126,38 +317,57 @@ pub fn create_context_menu(app: &mut AppData) -> Task<Message>
 }
 
 
+
+pub fn resize_context_menu(app: &AppData) -> Task<Message>
+{
+    let size = get_context_menu_size(&app.context_menu_data, &app.ron_config);
+    Task::batch(app.ids.iter().filter(|(_, info)| **info == WindowInfo::ContextMenu).map(|(id, _)| Task::done(Message::SizeChange { id: *id, size })))
+}
+
+
 pub fn context_menu_view<'a>(data: &'a ContextMenuData, ron_config: &'a BarConfig) -> Element<'a, Message> 
 {
-    let button_vec: Vec<Element<'_, Message>> = data.items.iter().map(|item| 
+    let (width, heigth) = match ron_config.context_menu.context_menu_orientation
+    {
+        TextOrientation::Horizontal => (Length::Fixed(ron_config.context_menu.context_menu_item_size as f32), Length::Fill),
+        TextOrientation::Vertical => (Length::Fill, Length::Fixed(ron_config.context_menu.context_menu_item_size as f32)),
+    };
+    let text_color = ron_config.context_menu.context_menu_text_color.to_iced_color();
+
+    let mut button_vec: Vec<Element<'_, Message>> = Vec::new();
+    if !data.submenu_path.is_empty()
     {
-        let (text_to_send, (width, heigth)) = match ron_config.context_menu.context_menu_orientation
+        let back_text = orient_menu_label("‹", &ron_config.context_menu.context_menu_orientation);
+        button_vec.push(button(text(back_text).color(text_color).font(data.default_font).size(ron_config.context_menu.context_menu_text_size).width(Length::Fill).height(Length::Fill).center()).width(width).height(heigth).on_press(Message::ContextMenuCloseSubmenu).style(move |_: &Theme, status: button::Status| context_menu_button_style(ron_config, status, false)).into());
+    }
+
+    button_vec.extend(data.current_items().iter().enumerate().map(|(index, item)| 
+    {
+        if item.is_separator { return menu_separator_view(ron_config); }
+        let (display_label, mnemonic_index) = parse_mnemonic(&item.label);
+        let display_label = if item.children.is_empty() { display_label } else { format!("{display_label}  ›") };
+        let orientation = &ron_config.context_menu.context_menu_orientation;
+        let label: Element<'_, Message> = match mnemonic_index.and_then(|mnemonic_index| split_mnemonic_label(&display_label, mnemonic_index, orientation))
         {
-            TextOrientation::Horizontal =>
+            Some((before, mnemonic, after)) =>
             {
-                (orient_text(&item.label, &TextOrientation::Vertical), (Length::Fixed(ron_config.context_menu.context_menu_item_size as f32), Length::Fill))
-            }
-            TextOrientation::Vertical => 
-            {
-                (item.label.clone(), (Length::Fill, Length::Fixed(ron_config.context_menu.context_menu_item_size as f32)))
+                let spans: [iced::widget::text::Span<'_, (), Font>; 3] = [span(before), span(mnemonic).underline(true), span(after)];
+                rich_text(spans).color(text_color).font(data.default_font).size(ron_config.context_menu.context_menu_text_size).width(Length::Fill).height(Length::Fill).center().into()
             }
+            None => text(orient_menu_label(&display_label, orientation)).color(text_color).font(data.default_font).size(ron_config.context_menu.context_menu_text_size).width(Length::Fill).height(Length::Fill).center().into(),
         };
-
-        let _ctx_text_color = ron_config.context_menu.context_menu_text_color.to_iced_color();
-        let color_to_send = _ctx_text_color;
-        button(text(text_to_send).color(color_to_send).align_y(Alignment::Center).align_y(Alignment::Center).font(data.default_font).size(ron_config.context_menu.context_menu_text_size).width(Length::Fill).height(Length::Fill).center()).width(width).height(heigth).on_press(Message::TrayAction(data.service.to_string(), data.path.to_string(), item.id, item.label.to_string())).style(|_: &Theme, status: button::Status| 
+        let icon_size = ron_config.context_menu.context_menu_icon_size;
+        let label: Element<'_, Message> = match (&item.icon, &ron_config.context_menu.context_menu_orientation)
         {
-            let hovered =           ron_config.context_menu.context_menu_button_hovered_color;
-            let hovered_text =      ron_config.context_menu.context_menu_button_hovered_text_color;
-            let pressed_text =      ron_config.context_menu.context_menu_button_pressed_text_color;
-            let pressed =           ron_config.context_menu.context_menu_button_pressed_color;
-            let normal =            ron_config.context_menu.context_menu_button_color;
-            let normal_text =       ron_config.context_menu.context_menu_text_color;
-            let border_color =  ron_config.context_menu.context_menu_border_color;
-            let border_size =       ron_config.context_menu.context_menu_border_size;
-            let border_radius =     ron_config.context_menu.context_menu_border_radius;
-            set_style(crate::UserStyle { status, hovered, hovered_text, pressed_text, pressed, normal, normal_text, border_color, border_size, border_radius, normal_gradient: None, hovered_gradient: None, pressed_gradient: None, shadow_color: None, shadow_blur: 0., shadow_x: 0., shadow_y: 0. })
-        }).into()}
-    ).collect();
+            (Some(MenuIcon::Glyph(glyph)), TextOrientation::Vertical) => row![text(glyph.clone()).color(text_color).font(data.default_font).size(icon_size), label].align_y(Alignment::Center).spacing(6).into(),
+            (Some(MenuIcon::Image(handle)), TextOrientation::Vertical) => row![image(handle.clone()).width(icon_size as f32).height(icon_size as f32), label].align_y(Alignment::Center).spacing(6).into(),
+            _ => label,
+        };
+
+        let message = if item.children.is_empty() { Message::ContextMenuActivate(index) } else { Message::ContextMenuOpenSubmenu(index) };
+        let is_focused = data.focused_index == Some(index);
+        button(label).width(width).height(heigth).on_press(message).style(move |_: &Theme, status: button::Status| context_menu_button_style(ron_config, status, is_focused)).into()
+    }));
     
 
     let row_or_column: Element<Message> = match &ron_config.context_menu.context_menu_orientation
@@ -175,7 +385,235 @@ pub fn context_menu_view<'a>(data: &'a ContextMenuData, ron_config: &'a BarConfi
 
 
 
-fn context_menu_background_button_style(ron_config: &BarConfig) -> iced::widget::container::Style
+fn menu_separator_view(ron_config: &BarConfig) -> Element<'_, Message>
+{
+    let separator_size = ron_config.context_menu.context_menu_separator_size as f32;
+    let line_style = move |_: &Theme| container::Style { background: Some(Background::Color(ron_config.context_menu.context_menu_separator_color.to_iced_color())), ..Default::default() };
+    match ron_config.context_menu.context_menu_orientation
+    {
+        TextOrientation::Vertical => container(container(Space::new()).width(Length::Fill).height(Length::Fixed(1.)).style(line_style)).width(Length::Fill).height(Length::Fixed(separator_size)).align_y(Alignment::Center).into(),
+        TextOrientation::Horizontal => container(container(Space::new()).width(Length::Fixed(1.)).height(Length::Fill).style(line_style)).width(Length::Fixed(separator_size)).height(Length::Fill).align_x(Alignment::Center).into(),
+    }
+}
+
+
+
+/// Turns the configured menu tree into `MenuItem`s, handing out ids in depth-first order.
+pub fn user_menu_to_items(items: &[UserMenuItem], icon_size: u32, next_id: &mut i32) -> Vec<MenuItem>
+{
+    items.iter().map(|user_item|
+    {
+        *next_id += 1;
+        let id = *next_id;
+        MenuItem
+        {
+            _visible: true,
+            label: user_item.label.clone(),
+            id,
+            children: user_menu_to_items(&user_item.children, icon_size, next_id),
+            icon: user_item.icon.as_deref().and_then(|icon| resolve_menu_icon(icon, icon_size)),
+            command: user_item.command.clone(),
+            is_separator: user_item.separator,
+        }
+    }).collect()
+}
+
+
+
+/// Paths are loaded as images, plain names go through the icon theme, anything else (nerd font glyphs, emoji) is drawn as text.
+pub fn resolve_menu_icon(icon: &str, size: u32) -> Option<MenuIcon>
+{
+    let to_handle = |(data, width, height): (Vec<u8>, u32, u32)| MenuIcon::Image(image::Handle::from_rgba(width, height, data));
+    if icon.is_empty() { return None; }
+    if icon.starts_with('/') || icon.starts_with('~')
+    {
+        let path = match icon.strip_prefix("~/") { Some(rest) => home::home_dir()?.join(rest), None => std::path::PathBuf::from(icon) };
+        return try_load_icon_sized(&path, Some(size)).map(to_handle);
+    }
+    if icon.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+    {
+        return load_icon_cached(icon, None, size).map(to_handle);
+    }
+    Some(MenuIcon::Glyph(icon.to_string()))
+}
+
+
+
+/// Next focusable (non separator) item in the given direction, wrapping around.
+pub fn step_focus(items: &[MenuItem], from: Option<usize>, forward: bool) -> Option<usize>
+{
+    let count = items.len();
+    if count == 0 { return None; }
+    let mut index = match from { Some(i) => i.min(count - 1), None => if forward { count - 1 } else { 0 } };
+    for _ in 0..count
+    {
+        index = if forward { (index + 1) % count } else { (index + count - 1) % count };
+        if !items[index].is_separator { return Some(index); }
+    }
+    from
+}
+
+
+
+fn orient_menu_label(label: &str, orientation: &TextOrientation) -> String
+{
+    match orientation
+    {
+        TextOrientation::Horizontal => orient_text(label, &TextOrientation::Vertical),
+        TextOrientation::Vertical => label.to_string(),
+    }
+}
+
+
+
+/// The label laid out like `orient_menu_label`, split around its mnemonic so it can be underlined in either orientation.
+fn split_mnemonic_label(label: &str, mnemonic_index: usize, orientation: &TextOrientation) -> Option<(String, String, String)>
+{
+    let chars: Vec<char> = label.chars().collect();
+    let mnemonic = chars.get(mnemonic_index)?.to_string();
+    match orientation
+    {
+        TextOrientation::Vertical => Some((chars[..mnemonic_index].iter().collect(), mnemonic, chars[mnemonic_index + 1..].iter().collect())),
+        // Stacked one character per line, the same as orient_text
+        TextOrientation::Horizontal =>
+        {
+            let before: String = chars[..mnemonic_index].iter().map(|c| format!("{c}\n")).collect();
+            let after: String = chars[mnemonic_index + 1..].iter().map(|c| format!("\n{c}")).collect();
+            Some((before, mnemonic, after.trim_end().to_string()))
+        }
+    }
+}
+
+
+
+fn context_menu_button_style(ron_config: &BarConfig, status: button::Status, is_focused: bool) -> button::Style
+{
+    // The keyboard focused item is drawn like a hovered one
+    let status = if is_focused && status == button::Status::Active { button::Status::Hovered } else { status };
+    let hovered =           ron_config.context_menu.context_menu_button_hovered_color;
+    let hovered_text =      ron_config.context_menu.context_menu_button_hovered_text_color;
+    let pressed_text =      ron_config.context_menu.context_menu_button_pressed_text_color;
+    let pressed =           ron_config.context_menu.context_menu_button_pressed_color;
+    let normal =            ron_config.context_menu.context_menu_button_color;
+    let normal_text =       ron_config.context_menu.context_menu_text_color;
+    let border_color =  ron_config.context_menu.context_menu_border_color;
+    let border_size =       ron_config.context_menu.context_menu_border_size;
+    let border_radius =     ron_config.context_menu.context_menu_border_radius;
+    set_style(crate::UserStyle { status, hovered, hovered_text, pressed_text, pressed, normal, normal_text, border_color, border_size, border_radius, normal_gradient: None, hovered_gradient: None, pressed_gradient: None, shadow_color: None, shadow_blur: 0., shadow_x: 0., shadow_y: 0. })
+}
+
+
+
+/// Strips dbusmenu mnemonic markers: "_File" -> ("File", Some(0)), "__" is a literal underscore.
+pub fn parse_mnemonic(label: &str) -> (String, Option<usize>)
+{
+    let mut display = String::with_capacity(label.len());
+    let mut mnemonic_index = None;
+    let mut chars = label.chars().peekable();
+    let mut char_count = 0;
+    while let Some(c) = chars.next()
+    {
+        if c == '_'
+        {
+            match chars.next()
+            {
+                Some('_') => display.push('_'),
+                Some(next) =>
+                {
+                    if mnemonic_index.is_none() { mnemonic_index = Some(char_count); }
+                    display.push(next);
+                }
+                None => break,
+            }
+        }
+        else
+        {
+            display.push(c);
+        }
+        char_count += 1;
+    }
+    (display, mnemonic_index)
+}
+
+
+
+pub fn mnemonic_char(label: &str) -> Option<char>
+{
+    let (display, mnemonic_index) = parse_mnemonic(label);
+    display.chars().nth(mnemonic_index?).map(|c| c.to_ascii_lowercase())
+}
+
+
+
+/// Applies a key press to the open menu. Typed characters first try the mnemonics, then fall back to type-ahead search by label.
+pub fn handle_context_menu_key(data: &mut ContextMenuData, key: &Key, now: Instant) -> MenuKeyOutcome
+{
+    if data.current_items().is_empty()
+    {
+        if matches!(key, Key::Named(Named::ArrowLeft)) && data.close_submenu() { return MenuKeyOutcome::Resize; }
+        return MenuKeyOutcome::Nothing;
+    }
+
+    let activate_or_open = |data: &mut ContextMenuData, index: usize| -> MenuKeyOutcome
+    {
+        if data.open_submenu(index) { MenuKeyOutcome::Resize } else { MenuKeyOutcome::Activate(index) }
+    };
+
+    match key
+    {
+        Key::Named(Named::ArrowDown) => { data.focused_index = step_focus(data.current_items(), data.focused_index, true); }
+        Key::Named(Named::ArrowUp) => { data.focused_index = step_focus(data.current_items(), data.focused_index, false); }
+        Key::Named(Named::Home) => { data.focused_index = step_focus(data.current_items(), None, true); }
+        Key::Named(Named::End) => { data.focused_index = step_focus(data.current_items(), None, false); }
+        Key::Named(Named::Enter) | Key::Named(Named::Space) =>
+        {
+            if let Some(index) = data.focused_index { return activate_or_open(data, index); }
+        }
+        Key::Named(Named::ArrowRight) =>
+        {
+            if let Some(index) = data.focused_index && data.open_submenu(index) { return MenuKeyOutcome::Resize; }
+        }
+        Key::Named(Named::ArrowLeft) =>
+        {
+            if data.close_submenu() { return MenuKeyOutcome::Resize; }
+        }
+        Key::Character(typed) =>
+        {
+            let typed = typed.to_lowercase();
+            let mut typed_chars = typed.chars();
+            if let (Some(c), None) = (typed_chars.next(), typed_chars.next())
+            {
+                let matches: Vec<usize> = data.current_items().iter().enumerate().filter(|(_, item)| mnemonic_char(&item.label) == Some(c)).map(|(i, _)| i).collect();
+                match matches.as_slice()
+                {
+                    [only] => return activate_or_open(data, *only),
+                    [first, ..] =>
+                    {
+                        let next = matches.iter().find(|i| Some(**i) > data.focused_index).unwrap_or(first);
+                        data.focused_index = Some(*next);
+                        return MenuKeyOutcome::Nothing;
+                    }
+                    [] => {}
+                }
+            }
+
+            let is_continuation = data.type_ahead_last_key.is_some_and(|last| now.duration_since(last) < Duration::from_millis(1000));
+            if !is_continuation { data.type_ahead.clear(); }
+            data.type_ahead.push_str(&typed);
+            data.type_ahead_last_key = Some(now);
+            if let Some(index) = data.current_items().iter().position(|item| parse_mnemonic(&item.label).0.to_lowercase().starts_with(&data.type_ahead))
+            {
+                data.focused_index = Some(index);
+            }
+        }
+        _ => {}
+    }
+    MenuKeyOutcome::Nothing
+}
+
+
+
+pub fn context_menu_background_button_style(ron_config: &BarConfig) -> iced::widget::container::Style
 {
     let mut background_style = container::Style::default();
     let bgc = ron_config.context_menu.context_menu_background_color.to_iced_color();
@@ -204,21 +642,23 @@ pub fn smart_popup_position(cursor_x: i32, cursor_y: i32, screen_w: i32, screen_
 
 pub fn get_context_menu_size(data: &ContextMenuData, ron_config: &BarConfig) -> (u32, u32)
 {
-    let item_count = data.items.len() as u32;
     let menu_item_size = ron_config.context_menu.context_menu_item_size;
+    let separator_size = ron_config.context_menu.context_menu_separator_size;
+    let back_row_size = if data.submenu_path.is_empty() { 0 } else { menu_item_size };
+    let items_size: u32 = data.current_items().iter().map(|item| if item.is_separator { separator_size } else { menu_item_size }).sum::<u32>() + back_row_size;
     let context_size = ron_config.context_menu.context_menu_size;
     let context_background_size = ron_config.context_menu.context_menu_background_size as u32;
     match ron_config.context_menu.context_menu_orientation
     {
         TextOrientation::Horizontal => 
         (
-            (item_count * menu_item_size) + context_background_size * 2,
+            items_size + context_background_size * 2,
             context_size + context_background_size * 2
         ),
         TextOrientation::Vertical => 
         (
             context_size + context_background_size * 2,
-            (item_count * menu_item_size) + context_background_size * 2
+            items_size + context_background_size * 2
         ) 
     }
 }
@@ -347,7 +787,7 @@ mod tests
     
     fn make_items(count: usize) -> Vec<MenuItem>
     {
-        (0..count).map(|i| MenuItem { id: i as i32, label: format!("Item {i}"), _visible: true }).collect()
+        (0..count).map(|i| MenuItem { id: i as i32, label: format!("Item {i}"), _visible: true, ..Default::default() }).collect()
     }
     
     #[test]
@@ -430,5 +870,305 @@ mod tests
         assert_eq!(hv, wh);
     }
 
+    // ---- parse_mnemonic -----------------------------------------------------
+
+    #[test]
+    fn parse_mnemonic_strips_marker_and_reports_index()
+    {
+        assert_eq!(parse_mnemonic("_File"), ("File".to_string(), Some(0)));
+        assert_eq!(parse_mnemonic("E_xit"), ("Exit".to_string(), Some(1)));
+    }
+
+    #[test]
+    fn parse_mnemonic_double_underscore_is_literal()
+    {
+        assert_eq!(parse_mnemonic("snake__case"), ("snake_case".to_string(), None));
+    }
+
+    #[test]
+    fn parse_mnemonic_without_marker()
+    {
+        assert_eq!(parse_mnemonic("Quit"), ("Quit".to_string(), None));
+    }
+
+    #[test]
+    fn split_mnemonic_label_matches_both_orientations()
+    {
+        assert_eq!(split_mnemonic_label("Exit", 1, &TextOrientation::Vertical), Some(("E".into(), "x".into(), "it".into())));
+        let (before, mnemonic, after) = split_mnemonic_label("Exit", 1, &TextOrientation::Horizontal).unwrap();
+        assert_eq!(format!("{before}{mnemonic}{after}"), orient_menu_label("Exit", &TextOrientation::Horizontal));
+        assert_eq!(mnemonic, "x");
+        assert_eq!(split_mnemonic_label("Exit", 9, &TextOrientation::Vertical), None);
+    }
+
+    #[test]
+    fn mnemonic_char_is_lowercase()
+    {
+        assert_eq!(mnemonic_char("_Quit"), Some('q'));
+        assert_eq!(mnemonic_char("Quit"), None);
+    }
+
+    // ---- handle_context_menu_key --------------------------------------------
+
+    fn item(id: i32, label: &str, children: Vec<MenuItem>) -> MenuItem
+    {
+        MenuItem { id, label: label.to_string(), _visible: true, children, ..Default::default() }
+    }
+
+    fn make_menu() -> ContextMenuData
+    {
+        ContextMenuData
+        {
+            items: vec![item(1, "_Open", vec![]), item(2, "_Recent", vec![item(4, "a.txt", vec![]), item(5, "b.txt", vec![])]), item(3, "_Quit", vec![])],
+            ..Default::default()
+        }
+    }
+
+    fn press(data: &mut ContextMenuData, key: Key) -> MenuKeyOutcome { handle_context_menu_key(data, &key, Instant::now()) }
+    fn character(c: &str) -> Key { Key::Character(c.into()) }
+
+    #[test]
+    fn arrow_down_wraps_around()
+    {
+        let mut data = make_menu();
+        for _ in 0..4 { press(&mut data, Key::Named(Named::ArrowDown)); }
+        assert_eq!(data.focused_index, Some(0));
+    }
+
+    #[test]
+    fn arrow_up_from_nothing_focuses_last()
+    {
+        let mut data = make_menu();
+        press(&mut data, Key::Named(Named::ArrowUp));
+        assert_eq!(data.focused_index, Some(2));
+    }
+
+    #[test]
+    fn home_and_end_jump_to_edges()
+    {
+        let mut data = make_menu();
+        press(&mut data, Key::Named(Named::End));
+        assert_eq!(data.focused_index, Some(2));
+        press(&mut data, Key::Named(Named::Home));
+        assert_eq!(data.focused_index, Some(0));
+    }
+
+    #[test]
+    fn enter_activates_focused_item()
+    {
+        let mut data = make_menu();
+        data.focused_index = Some(2);
+        assert_eq!(press(&mut data, Key::Named(Named::Enter)), MenuKeyOutcome::Activate(2));
+    }
+
+    #[test]
+    fn enter_without_focus_does_nothing()
+    {
+        let mut data = make_menu();
+        assert_eq!(press(&mut data, Key::Named(Named::Enter)), MenuKeyOutcome::Nothing);
+    }
+
+    #[test]
+    fn right_opens_submenu_and_left_closes_it()
+    {
+        let mut data = make_menu();
+        data.focused_index = Some(1);
+        assert_eq!(press(&mut data, Key::Named(Named::ArrowRight)), MenuKeyOutcome::Resize);
+        assert_eq!(data.current_items().len(), 2);
+        assert_eq!(data.focused_index, Some(0));
+        assert_eq!(press(&mut data, Key::Named(Named::ArrowLeft)), MenuKeyOutcome::Resize);
+        assert_eq!(data.current_items().len(), 3);
+        assert_eq!(data.focused_index, Some(1));
+    }
+
+    #[test]
+    fn right_on_leaf_item_does_nothing()
+    {
+        let mut data = make_menu();
+        data.focused_index = Some(0);
+        assert_eq!(press(&mut data, Key::Named(Named::ArrowRight)), MenuKeyOutcome::Nothing);
+        assert!(data.submenu_path.is_empty());
+    }
+
+    #[test]
+    fn mnemonic_activates_matching_item()
+    {
+        let mut data = make_menu();
+        assert_eq!(press(&mut data, character("q")), MenuKeyOutcome::Activate(2));
+    }
+
+    #[test]
+    fn mnemonic_on_submenu_opens_it()
+    {
+        let mut data = make_menu();
+        assert_eq!(press(&mut data, character("r")), MenuKeyOutcome::Resize);
+        assert_eq!(data.submenu_path, vec![1]);
+    }
+
+    #[test]
+    fn type_ahead_accumulates_characters()
+    {
+        let mut data = make_menu();
+        data.submenu_path = vec![1];
+        let now = Instant::now();
+        handle_context_menu_key(&mut data, &character("b"), now);
+        assert_eq!(data.focused_index, Some(1));
+        handle_context_menu_key(&mut data, &character("."), now);
+        assert_eq!(data.type_ahead, "b.");
+        assert_eq!(data.focused_index, Some(1));
+    }
 
+    #[test]
+    fn type_ahead_resets_after_pause()
+    {
+        let mut data = make_menu();
+        data.submenu_path = vec![1];
+        let start = Instant::now();
+        handle_context_menu_key(&mut data, &character("b"), start);
+        handle_context_menu_key(&mut data, &character("a"), start + Duration::from_secs(2));
+        assert_eq!(data.type_ahead, "a");
+        assert_eq!(data.focused_index, Some(0));
+    }
+
+    #[test]
+    fn context_menu_size_counts_back_row_in_submenu()
+    {
+        let config = make_config(TextOrientation::Vertical, 100, 20, 0);
+        let mut data = make_menu();
+        data.submenu_path = vec![1];
+        let (_, h) = get_context_menu_size(&data, &config);
+        assert_eq!(h, 60); // 2 items + back row
+    }
+
+    // ---- user menus ---------------------------------------------------------
+
+    fn separator() -> MenuItem { MenuItem { is_separator: true, ..Default::default() } }
+
+    #[test]
+    fn user_menu_ids_are_unique_and_children_nested()
+    {
+        let menu = vec!
+        [
+            UserMenuItem { label: "Lock".into(), command: vec!["loginctl".into(), "lock-session".into()], ..Default::default() },
+            UserMenuItem { separator: true, ..Default::default() },
+            UserMenuItem { label: "Power".into(), children: vec![UserMenuItem { label: "Reboot".into(), ..Default::default() }], ..Default::default() },
+        ];
+        let mut next_id = 0;
+        let items = user_menu_to_items(&menu, 16, &mut next_id);
+        assert_eq!(items.iter().map(|i| i.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(items[0].command, vec!["loginctl", "lock-session"]);
+        assert!(items[1].is_separator);
+        assert_eq!(items[2].children[0].id, 4);
+        assert_eq!(next_id, 4);
+    }
+
+    #[test]
+    fn resolve_menu_icon_treats_symbols_as_glyphs()
+    {
+        assert!(matches!(resolve_menu_icon("⏻", 16), Some(MenuIcon::Glyph(g)) if g == "⏻"));
+        assert!(resolve_menu_icon("", 16).is_none());
+        assert!(resolve_menu_icon("/nonexistent/icon.png", 16).is_none());
+    }
+
+    #[test]
+    fn arrows_skip_separators()
+    {
+        let mut data = ContextMenuData { items: vec![item(1, "A", vec![]), separator(), item(2, "B", vec![])], ..Default::default() };
+        press(&mut data, Key::Named(Named::ArrowDown));
+        press(&mut data, Key::Named(Named::ArrowDown));
+        assert_eq!(data.focused_index, Some(2));
+        press(&mut data, Key::Named(Named::ArrowUp));
+        assert_eq!(data.focused_index, Some(0));
+    }
+
+    #[test]
+    fn home_and_end_skip_separators()
+    {
+        let mut data = ContextMenuData { items: vec![separator(), item(1, "A", vec![]), item(2, "B", vec![]), separator()], ..Default::default() };
+        press(&mut data, Key::Named(Named::Home));
+        assert_eq!(data.focused_index, Some(1));
+        press(&mut data, Key::Named(Named::End));
+        assert_eq!(data.focused_index, Some(2));
+    }
+
+    #[test]
+    fn step_focus_all_separators_keeps_focus()
+    {
+        assert_eq!(step_focus(&[separator(), separator()], None, true), None);
+    }
+
+    #[test]
+    fn context_menu_size_uses_separator_size()
+    {
+        let mut config = make_config(TextOrientation::Vertical, 100, 20, 0);
+        config.context_menu.context_menu_separator_size = 7;
+        let data = ContextMenuData { items: vec![item(1, "A", vec![]), separator(), item(2, "B", vec![])], ..Default::default() };
+        let (_, h) = get_context_menu_size(&data, &config);
+        assert_eq!(h, 47);
+    }
+
+    // ---- anchored popups ----------------------------------------------------
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rectangle { Rectangle { x, y, width, height } }
+
+    #[test]
+    fn pick_anchor_bounds_prefers_smallest_match()
+    {
+        let bounds = [rect(0., 0., 500., 30.), rect(100., 0., 40., 30.), rect(300., 0., 40., 30.)];
+        assert_eq!(pick_anchor_bounds(&bounds, (110, 10)), Some(bounds[1]));
+        assert_eq!(pick_anchor_bounds(&bounds, (600, 10)), None);
+    }
+
+    #[test]
+    fn anchored_popup_top_bar_aligns_with_module()
+    {
+        let placement = anchored_popup_placement(&BarPosition::Up, rect(100., 0., 40., 30.), (200, 150), (1920, 1080), 8);
+        assert_eq!(placement.anchor, Anchor::Top | Anchor::Left);
+        assert_eq!(placement.margin, (8, 0, 0, 100));
+    }
+
+    #[test]
+    fn anchored_popup_flips_at_right_edge()
+    {
+        // 1800 + 200 overflows, so the popup ends at the module's right edge (1840)
+        let placement = anchored_popup_placement(&BarPosition::Down, rect(1800., 0., 40., 30.), (200, 150), (1920, 1080), 0);
+        assert_eq!(placement.anchor, Anchor::Bottom | Anchor::Left);
+        assert_eq!(placement.margin, (0, 0, 0, 1640));
+    }
+
+    #[test]
+    fn anchored_popup_vertical_bars_use_y_axis()
+    {
+        let left = anchored_popup_placement(&BarPosition::Left, rect(0., 1000., 30., 30.), (200, 150), (1920, 1080), 5);
+        assert_eq!(left.anchor, Anchor::Left | Anchor::Top);
+        assert_eq!(left.margin, (880, 0, 0, 5));
+        let right = anchored_popup_placement(&BarPosition::Right, rect(0., 20., 30., 30.), (200, 150), (1920, 1080), 5);
+        assert_eq!(right.anchor, Anchor::Right | Anchor::Top);
+        assert_eq!(right.margin, (20, 5, 0, 0));
+    }
+
+    #[test]
+    fn anchored_popup_relies_on_the_bar_spanning_its_edge()
+    {
+        use crate::helpers::misc::define_bar_anchor_position;
+        // Module bounds are only output coordinates along the bar because it has no margin on that axis
+        assert!(define_bar_anchor_position(&BarPosition::Up).contains(Anchor::Left | Anchor::Right));
+        assert!(define_bar_anchor_position(&BarPosition::Down).contains(Anchor::Left | Anchor::Right));
+        assert!(define_bar_anchor_position(&BarPosition::Left).contains(Anchor::Top | Anchor::Bottom));
+        assert!(define_bar_anchor_position(&BarPosition::Right).contains(Anchor::Top | Anchor::Bottom));
+    }
+
+    #[test]
+    fn anchored_popup_bigger_than_screen_clamps_to_zero()
+    {
+        let placement = anchored_popup_placement(&BarPosition::Up, rect(50., 0., 40., 30.), (300, 150), (200, 1080), -4);
+        assert_eq!(placement.margin, (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn module_anchor_ids_are_unique_per_side()
+    {
+        assert_ne!(module_anchor_id("left", 0), module_anchor_id("right", 0));
+        assert_ne!(module_anchor_id("left", 0), module_anchor_id("left", 1));
+    }
 }
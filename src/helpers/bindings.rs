@@ -0,0 +1,566 @@
+// ============ IMPORTS ============
+use iced::{Element, Task, mouse::ScrollDelta, widget::mouse_area};
+use serde::{Deserialize, Serialize};
+use std::{collections::{HashMap, HashSet}, time::{Duration, Instant}};
+
+
+
+
+
+// ============ CRATES ============
+use crate::helpers::command::CommandRequest;
+use crate::modules::{compositor::workspace_backend, data::Modules, media_player::{MediaPlayerAction, media_player_action}, volume, workspaces::{UserWorkspaceAction, scroll_workspace_action}};
+use crate::helpers::misc::validate_bar_data;
+use crate::ron::{ActionOnClick, BarPosition};
+use crate::update::Message;
+use crate::{AppData, MAIN_ID};
+
+
+
+
+
+// ============ CONST ============
+/// Two left clicks on the same module closer than this are a double click.
+pub const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+
+
+
+
+// ============ ENUM/STRUCT, ETC ============
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BindingTrigger
+{
+    LeftClick,
+    MiddleClick,
+    RightClick,
+    DoubleClick,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+    HoverEnter,
+    HoverLeave,
+}
+
+/// Per-module bindings, `None` (or `DefaultAction`) keeps the module's built-in behavior for that trigger.
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ModuleBindings
+{
+    pub on_left_click:   Option<ActionOnClick>,
+    pub on_middle_click: Option<ActionOnClick>,
+    pub on_right_click:  Option<ActionOnClick>,
+    pub on_double_click: Option<ActionOnClick>,
+    pub on_scroll_up:    Option<ActionOnClick>,
+    pub on_scroll_down:  Option<ActionOnClick>,
+    pub on_scroll_left:  Option<ActionOnClick>,
+    pub on_scroll_right: Option<ActionOnClick>,
+    pub on_hover_enter:  Option<ActionOnClick>,
+    pub on_hover_leave:  Option<ActionOnClick>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum AudioDevice
+{
+    Output,
+    Input,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum WorkspaceTarget
+{
+    Next,
+    Prev,
+    Index(i32),
+    Name(String),
+}
+
+/// Actions handled inside the bar, without spawning a process. Used as `Internal(...)` in any click or binding.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum InternalAction
+{
+    /// Only Clock and Network have an alt mode.
+    ToggleAlt(Modules),
+    /// Relative, in percent: SetVolume(5) / SetVolume(-5).
+    SetVolume(i16),
+    SetInputVolume(i16),
+    MuteToggle(AudioDevice),
+    Workspace(WorkspaceTarget),
+    MediaNext,
+    MediaPrev,
+    MediaPlayPause,
+    CycleClockTimezones,
+    ReloadConfig,
+    /// Shrinks the bar to a one pixel strip and releases its exclusive zone, clicking the strip brings it back.
+    ToggleBar,
+    OpenMenu(String),
+    /// Takes the module name as written in the module lists, e.g: "Tray" or "CustomModule(0)".
+    HideModule(String),
+    ShowModule(String),
+    ToggleModule(String),
+    /// Runs one after the other.
+    Sequence(Vec<InternalAction>),
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct BindingData
+{
+    /// Module under the cursor, scroll and double clicks are resolved against it.
+    pub hovered_module: Option<Modules>,
+    pub last_left_click: Option<(Modules, Instant)>,
+    /// Module names hidden by HideModule / ToggleModule, they survive config reloads.
+    pub hidden_modules: HashSet<String>,
+    pub bar_hidden: bool,
+}
+
+
+
+
+
+// ============ FUNCTIONS ============
+impl BindingTrigger
+{
+    /// Exported as ICEBAR_BUTTON.
+    pub fn button_name(self) -> &'static str
+    {
+        match self
+        {
+            BindingTrigger::LeftClick   => "left",
+            BindingTrigger::MiddleClick => "middle",
+            BindingTrigger::RightClick  => "right",
+            BindingTrigger::DoubleClick => "double",
+            BindingTrigger::ScrollUp    => "scroll-up",
+            BindingTrigger::ScrollDown  => "scroll-down",
+            BindingTrigger::ScrollLeft  => "scroll-left",
+            BindingTrigger::ScrollRight => "scroll-right",
+            BindingTrigger::HoverEnter  => "hover-enter",
+            BindingTrigger::HoverLeave  => "hover-leave",
+        }
+    }
+}
+
+
+
+impl ModuleBindings
+{
+    pub fn get(&self, trigger: BindingTrigger) -> Option<&ActionOnClick>
+    {
+        match trigger
+        {
+            BindingTrigger::LeftClick   => self.on_left_click.as_ref(),
+            BindingTrigger::MiddleClick => self.on_middle_click.as_ref(),
+            BindingTrigger::RightClick  => self.on_right_click.as_ref(),
+            BindingTrigger::DoubleClick => self.on_double_click.as_ref(),
+            BindingTrigger::ScrollUp    => self.on_scroll_up.as_ref(),
+            BindingTrigger::ScrollDown  => self.on_scroll_down.as_ref(),
+            BindingTrigger::ScrollLeft  => self.on_scroll_left.as_ref(),
+            BindingTrigger::ScrollRight => self.on_scroll_right.as_ref(),
+            BindingTrigger::HoverEnter  => self.on_hover_enter.as_ref(),
+            BindingTrigger::HoverLeave  => self.on_hover_leave.as_ref(),
+        }
+    }
+}
+
+
+
+/// The name a module is written with in the module lists, and the key of its "bindings" entry, e.g: "Clock" or "CustomModule(0)".
+pub fn module_key(module: &Modules) -> String
+{
+    format!("{module:?}")
+}
+
+
+
+pub fn module_binding<'a>(bindings: &'a HashMap<String, ModuleBindings>, module: &Modules, trigger: BindingTrigger) -> Option<&'a ActionOnClick>
+{
+    bindings.get(&module_key(module))?.get(trigger)
+}
+
+
+
+/// The message a binding sends, `None` when it defers to the module's built-in behavior.
+pub fn binding_message(app: &AppData, module: &Modules, action: &ActionOnClick, trigger: BindingTrigger) -> Option<Message>
+{
+    let name = module_key(module);
+    let user_env = match module
+    {
+        Modules::CustomModule(index) => app.ron_config.custom_module.custom_modules.get(*index).map(|custom| custom.env.clone()).unwrap_or_default(),
+        Modules::Image(index) => app.ron_config.image.images.get(*index).map(|image| image.env.clone()).unwrap_or_default(),
+        _ => HashMap::new(),
+    };
+    let request = |command: Vec<String>, shell: bool| CommandRequest
+    {
+        name: format!("{name} {}", trigger.button_name()),
+        module: name.clone(),
+        is_left_click: trigger == BindingTrigger::LeftClick,
+        button: Some(trigger.button_name().into()),
+        env: user_env.clone(),
+        command,
+        shell,
+        ..Default::default()
+    };
+
+    match action
+    {
+        ActionOnClick::DefaultAction => None,
+        ActionOnClick::Nothing => Some(Message::Nothing),
+        ActionOnClick::CustomAction(command) => Some(Message::CreateCustomModuleCommand(request(command.clone(), false))),
+        ActionOnClick::ShellAction(script) => Some(Message::CreateCustomModuleCommand(request(vec![script.clone()], true))),
+        ActionOnClick::CycleClockTimezones => Some(Message::InternalAction(InternalAction::CycleClockTimezones)),
+        ActionOnClick::ToggleAltClockAndCycleClockTimezones => Some(Message::ToggleAltClockAndCycleClockTimeZones),
+        ActionOnClick::OpenMenu(menu) => Some(Message::InternalAction(InternalAction::OpenMenu(menu.clone()))),
+        ActionOnClick::Internal(action) => Some(Message::InternalAction(action.clone())),
+    }
+}
+
+
+
+/// A click's message, a binding for the trigger wins over the module's own "action_on_*" setting.
+pub fn bound_click(app: &AppData, module: &Modules, trigger: BindingTrigger, default: Message) -> Message
+{
+    module_binding(&app.ron_config.bindings, module, trigger).and_then(|action| binding_message(app, module, action, trigger)).unwrap_or(default)
+}
+
+
+
+/// Built-in behavior of the triggers that are not handled by the module's own buttons.
+pub fn default_binding_action(app: &AppData, module: &Modules, trigger: BindingTrigger) -> Task<Message>
+{
+    let up = match trigger
+    {
+        BindingTrigger::ScrollUp => true,
+        BindingTrigger::ScrollDown => false,
+        _ => return Task::none(),
+    };
+
+    match module
+    {
+        Modules::MediaPlayerMetaData => media_player_action(&app.ron_config.media_player_metadata.player, if up { MediaPlayerAction::VolumeUp } else { MediaPlayerAction::VolumeDown }),
+        Modules::VolumeOutput =>
+        {
+            let step = app.ron_config.volume_output.incremental_steps_output;
+            volume::volume(if up { volume::VolumeAction::IncreaseOutput(step) } else { volume::VolumeAction::DecreaseOutput(step) })
+        }
+        Modules::VolumeInput =>
+        {
+            let step = app.ron_config.volume_input.incremental_steps_input;
+            volume::volume(if up { volume::VolumeAction::IncreaseInput(step) } else { volume::VolumeAction::DecreaseInput(step) })
+        }
+        Modules::Workspaces | Modules::HyprWorkspaces | Modules::SwayWorkspaces | Modules::NiriWorkspaces =>
+        {
+            let Some(backend) = workspace_backend(&app.modules_data.active_modules) else { return Task::none(); };
+            let Some(action) = scroll_workspace_action(if up { 1. } else { -1. }, app.ron_config.workspace.reverse_scroll_on_workspace) else { return Task::none(); };
+            Task::perform(tokio::task::spawn_blocking(move || backend.change_workspace(action)), |_| Message::Nothing)
+        }
+        _ => Task::none(),
+    }
+}
+
+
+
+/// Runs the module's binding for the trigger, or its built-in behavior when it has none.
+pub fn resolve_binding(app: &AppData, module: &Modules, trigger: BindingTrigger) -> Task<Message>
+{
+    match module_binding(&app.ron_config.bindings, module, trigger).and_then(|action| binding_message(app, module, action, trigger))
+    {
+        Some(message) => Task::done(message),
+        None => default_binding_action(app, module, trigger),
+    }
+}
+
+
+
+/// The dominant axis of the wheel movement, positive deltas scroll up and left.
+pub fn scroll_trigger(delta: ScrollDelta) -> Option<BindingTrigger>
+{
+    let (x, y) = match delta
+    {
+        ScrollDelta::Lines { x, y } | ScrollDelta::Pixels { x, y } => (x, y),
+    };
+    if y != 0. && y.abs() >= x.abs() { return Some(if y > 0. { BindingTrigger::ScrollUp } else { BindingTrigger::ScrollDown }); }
+    if x != 0. { return Some(if x > 0. { BindingTrigger::ScrollLeft } else { BindingTrigger::ScrollRight }); }
+    None
+}
+
+
+
+/// Records a left click on the hovered module and returns it when the click completes a double click.
+pub fn register_left_click(data: &mut BindingData, now: Instant) -> Option<Modules>
+{
+    let module = data.hovered_module.clone()?;
+    if let Some((last_module, last_time)) = data.last_left_click.take() && last_module == module && now.duration_since(last_time) <= DOUBLE_CLICK_INTERVAL
+    {
+        return Some(module);
+    }
+    data.last_left_click = Some((module, now));
+    None
+}
+
+
+
+fn volume_step(step: i16, device: AudioDevice) -> Option<volume::VolumeAction>
+{
+    let amount = step.unsigned_abs().min(u8::MAX as u16) as u8;
+    match (device, step.signum())
+    {
+        (_, 0) => None,
+        (AudioDevice::Output, 1) => Some(volume::VolumeAction::IncreaseOutput(amount)),
+        (AudioDevice::Output, _) => Some(volume::VolumeAction::DecreaseOutput(amount)),
+        (AudioDevice::Input, 1) => Some(volume::VolumeAction::IncreaseInput(amount)),
+        (AudioDevice::Input, _) => Some(volume::VolumeAction::DecreaseInput(amount)),
+    }
+}
+
+
+
+/// Resizes the bar for the current `bar_hidden` state, the hidden bar keeps a one pixel strip along its edge.
+fn bar_visibility_task(app: &mut AppData) -> Task<Message>
+{
+    let Some(&id) = MAIN_ID.get() else { return Task::none(); };
+    let bar_data_validated = validate_bar_data(app);
+    let mut bar_size = bar_data_validated.bar_size;
+    if bar_size.0 == 0 { bar_size.0 = app.monitor_size.0; };
+    if bar_size.1 == 0 { bar_size.1 = app.monitor_size.1; };
+    let mut zone_size = bar_data_validated.exclusive_zone;
+    if app.modules_data.binding_data.bar_hidden
+    {
+        match app.ron_config.general.bar_position
+        {
+            BarPosition::Up | BarPosition::Down => bar_size.1 = 1,
+            BarPosition::Left | BarPosition::Right => bar_size.0 = 1,
+        }
+        zone_size = 0;
+    }
+    Task::batch([Task::done(Message::SizeChange { id, size: bar_size }), Task::done(Message::ExclusiveZoneChange { id, zone_size })])
+}
+
+
+
+/// Routes an internal action to the messages that already implement it.
+pub fn run_internal_action(app: &mut AppData, action: InternalAction) -> Task<Message>
+{
+    match action
+    {
+        InternalAction::ToggleAlt(Modules::Clock) => Task::done(Message::ToggleAltClock),
+        InternalAction::ToggleAlt(Modules::Network) => Task::done(Message::ToggleAltNetwork),
+        InternalAction::ToggleAlt(module) => { eprintln!("[icebar] {} has no alt mode to toggle", module_key(&module)); Task::none() }
+        InternalAction::SetVolume(step) => volume_step(step, AudioDevice::Output).map(volume::volume).unwrap_or_else(Task::none),
+        InternalAction::SetInputVolume(step) => volume_step(step, AudioDevice::Input).map(volume::volume).unwrap_or_else(Task::none),
+        InternalAction::MuteToggle(AudioDevice::Output) => Task::done(Message::MuteAudioPressedOutput),
+        InternalAction::MuteToggle(AudioDevice::Input) => Task::done(Message::MuteAudioPressedInput),
+        InternalAction::Workspace(target) =>
+        {
+            let Some(backend) = workspace_backend(&app.modules_data.active_modules) else { eprintln!("[icebar] Workspace(...) needs a workspaces module in the bar"); return Task::none(); };
+            let action = match target
+            {
+                WorkspaceTarget::Next => UserWorkspaceAction::MoveNext,
+                WorkspaceTarget::Prev => UserWorkspaceAction::MovePrev,
+                WorkspaceTarget::Index(index) => UserWorkspaceAction::ChangeWithIndex(index),
+                WorkspaceTarget::Name(name) => UserWorkspaceAction::ChangeWithName(name),
+            };
+            Task::perform(tokio::task::spawn_blocking(move || backend.change_workspace(action)), |_| Message::Nothing)
+        }
+        InternalAction::MediaNext => Task::done(Message::MediaPlayerClickNext),
+        InternalAction::MediaPrev => Task::done(Message::MediaPlayerClickPrev),
+        InternalAction::MediaPlayPause => Task::done(Message::MediaPlayerClickPlayPause),
+        InternalAction::CycleClockTimezones => Task::done(Message::CycleClockTimeZones),
+        InternalAction::ReloadConfig => Task::done(Message::ConfigChanged),
+        InternalAction::OpenMenu(menu) => Task::done(Message::OpenUserMenu(menu)),
+        InternalAction::HideModule(name) => { app.modules_data.binding_data.hidden_modules.insert(name); Task::none() }
+        InternalAction::ShowModule(name) => { app.modules_data.binding_data.hidden_modules.remove(&name); Task::none() }
+        InternalAction::ToggleModule(name) =>
+        {
+            let hidden_modules = &mut app.modules_data.binding_data.hidden_modules;
+            if !hidden_modules.remove(&name) { hidden_modules.insert(name); }
+            Task::none()
+        }
+        InternalAction::ToggleBar =>
+        {
+            app.modules_data.binding_data.bar_hidden = !app.modules_data.binding_data.bar_hidden;
+            bar_visibility_task(app)
+        }
+        InternalAction::Sequence(actions) => actions.into_iter().fold(Task::none(), |task, action| task.chain(Task::done(Message::InternalAction(action)))),
+    }
+}
+
+
+
+/// Tracks the hover of a whole module, and forwards the clicks its own widgets don't already handle.
+pub fn bind_module_events<'a>(app: &AppData, module: &Modules, element: Element<'a, Message>) -> Element<'a, Message>
+{
+    let mut area = mouse_area(element)
+        .on_enter(Message::ModuleHovered(module.clone(), true))
+        .on_exit(Message::ModuleHovered(module.clone(), false));
+    if module_binding(&app.ron_config.bindings, module, BindingTrigger::MiddleClick).is_some()
+    {
+        area = area.on_middle_press(Message::ModuleBindingTriggered(module.clone(), BindingTrigger::MiddleClick));
+    }
+    // Modules with their own right click capture it first, those read the binding through `bound_click`
+    if module_binding(&app.ron_config.bindings, module, BindingTrigger::RightClick).is_some()
+    {
+        area = area.on_right_press(Message::ModuleBindingTriggered(module.clone(), BindingTrigger::RightClick));
+    }
+    area.into()
+}
+
+
+
+
+
+// ============ TESTS ============
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn bindings_for(module: &str, bindings: ModuleBindings) -> HashMap<String, ModuleBindings>
+    {
+        [(module.to_string(), bindings)].into_iter().collect()
+    }
+
+    // ---- module_key ---------------------------------------------------------
+
+    #[test]
+    fn module_key_matches_the_module_list_syntax()
+    {
+        assert_eq!(module_key(&Modules::VolumeOutput), "VolumeOutput");
+        assert_eq!(module_key(&Modules::CustomModule(2)), "CustomModule(2)");
+        assert_eq!(module_key(&Modules::Image(0)), "Image(0)");
+    }
+
+    #[test]
+    fn module_binding_is_looked_up_by_module_and_trigger()
+    {
+        let bindings = bindings_for("Clock", ModuleBindings { on_middle_click: Some(ActionOnClick::OpenMenu("power".into())), ..Default::default() });
+        assert!(matches!(module_binding(&bindings, &Modules::Clock, BindingTrigger::MiddleClick), Some(ActionOnClick::OpenMenu(menu)) if menu == "power"));
+        assert!(module_binding(&bindings, &Modules::Clock, BindingTrigger::RightClick).is_none());
+        assert!(module_binding(&bindings, &Modules::Cpu, BindingTrigger::MiddleClick).is_none());
+    }
+
+    // ---- binding_message ----------------------------------------------------
+
+    #[test]
+    fn default_action_defers_to_the_module()
+    {
+        let app = AppData { ..Default::default() };
+        assert!(binding_message(&app, &Modules::Clock, &ActionOnClick::DefaultAction, BindingTrigger::ScrollUp).is_none());
+        assert!(matches!(binding_message(&app, &Modules::Clock, &ActionOnClick::Nothing, BindingTrigger::ScrollUp), Some(Message::Nothing)));
+    }
+
+    #[test]
+    fn command_bindings_export_the_trigger_as_button()
+    {
+        let app = AppData { ..Default::default() };
+        let Some(Message::CreateCustomModuleCommand(request)) = binding_message(&app, &Modules::CustomModule(0), &ActionOnClick::ShellAction("playerctl next".into()), BindingTrigger::ScrollDown) else { panic!("expected a command") };
+        assert!(request.shell);
+        assert_eq!(request.module, "CustomModule(0)");
+        assert_eq!(request.button.as_deref(), Some("scroll-down"));
+        assert!(!request.is_left_click);
+    }
+
+    #[test]
+    fn bound_click_prefers_the_binding()
+    {
+        let mut app = AppData { ..Default::default() };
+        assert!(matches!(bound_click(&app, &Modules::Clock, BindingTrigger::LeftClick, Message::ToggleAltClock), Message::ToggleAltClock));
+        app.ron_config.bindings = bindings_for("Clock", ModuleBindings { on_left_click: Some(ActionOnClick::CycleClockTimezones), ..Default::default() });
+        assert!(matches!(bound_click(&app, &Modules::Clock, BindingTrigger::LeftClick, Message::ToggleAltClock), Message::InternalAction(InternalAction::CycleClockTimezones)));
+        assert!(matches!(bound_click(&app, &Modules::Clock, BindingTrigger::RightClick, Message::Nothing), Message::Nothing));
+    }
+
+    #[test]
+    fn internal_bindings_send_the_action()
+    {
+        let app = AppData { ..Default::default() };
+        let action = ActionOnClick::Internal(InternalAction::SetVolume(-5));
+        assert!(matches!(binding_message(&app, &Modules::VolumeOutput, &action, BindingTrigger::ScrollDown), Some(Message::InternalAction(InternalAction::SetVolume(-5)))));
+    }
+
+    // ---- internal actions ---------------------------------------------------
+
+    #[test]
+    fn internal_actions_parse_from_ron()
+    {
+        let action: ActionOnClick = ron::from_str(r#"Internal(Sequence([MediaNext, SetVolume(-5), ToggleAlt(Clock), Workspace(Index(3)), HideModule("Tray")]))"#).unwrap();
+        let ActionOnClick::Internal(InternalAction::Sequence(actions)) = action else { panic!("expected a sequence") };
+        assert_eq!(actions, vec!
+        [
+            InternalAction::MediaNext,
+            InternalAction::SetVolume(-5),
+            InternalAction::ToggleAlt(Modules::Clock),
+            InternalAction::Workspace(WorkspaceTarget::Index(3)),
+            InternalAction::HideModule("Tray".into()),
+        ]);
+    }
+
+    #[test]
+    fn volume_step_picks_the_direction_and_device()
+    {
+        assert!(matches!(volume_step(5, AudioDevice::Output), Some(volume::VolumeAction::IncreaseOutput(5))));
+        assert!(matches!(volume_step(-10, AudioDevice::Input), Some(volume::VolumeAction::DecreaseInput(10))));
+        assert!(matches!(volume_step(-1000, AudioDevice::Output), Some(volume::VolumeAction::DecreaseOutput(255))));
+        assert!(volume_step(0, AudioDevice::Output).is_none());
+    }
+
+    #[test]
+    fn module_visibility_actions_update_the_hidden_set()
+    {
+        let mut app = AppData { ..Default::default() };
+        let _ = run_internal_action(&mut app, InternalAction::HideModule("Tray".into()));
+        assert!(app.modules_data.binding_data.hidden_modules.contains("Tray"));
+        let _ = run_internal_action(&mut app, InternalAction::ToggleModule("Tray".into()));
+        assert!(app.modules_data.binding_data.hidden_modules.is_empty());
+        let _ = run_internal_action(&mut app, InternalAction::ToggleModule("Clock".into()));
+        let _ = run_internal_action(&mut app, InternalAction::ShowModule("Clock".into()));
+        assert!(app.modules_data.binding_data.hidden_modules.is_empty());
+    }
+
+    #[test]
+    fn toggle_bar_flips_the_hidden_state()
+    {
+        let mut app = AppData { ..Default::default() };
+        let _ = run_internal_action(&mut app, InternalAction::ToggleBar);
+        assert!(app.modules_data.binding_data.bar_hidden);
+        let _ = run_internal_action(&mut app, InternalAction::ToggleBar);
+        assert!(!app.modules_data.binding_data.bar_hidden);
+    }
+
+    // ---- scroll_trigger -----------------------------------------------------
+
+    #[test]
+    fn scroll_trigger_follows_the_dominant_axis()
+    {
+        assert_eq!(scroll_trigger(ScrollDelta::Pixels { x: 0., y: 3. }), Some(BindingTrigger::ScrollUp));
+        assert_eq!(scroll_trigger(ScrollDelta::Lines { x: 0., y: -1. }), Some(BindingTrigger::ScrollDown));
+        assert_eq!(scroll_trigger(ScrollDelta::Pixels { x: 4., y: 1. }), Some(BindingTrigger::ScrollLeft));
+        assert_eq!(scroll_trigger(ScrollDelta::Pixels { x: -4., y: 0. }), Some(BindingTrigger::ScrollRight));
+        assert_eq!(scroll_trigger(ScrollDelta::Pixels { x: 0., y: 0. }), None);
+    }
+
+    // ---- register_left_click ------------------------------------------------
+
+    #[test]
+    fn two_quick_clicks_on_the_same_module_are_a_double_click()
+    {
+        let mut data = BindingData { hovered_module: Some(Modules::Clock), ..Default::default() };
+        let now = Instant::now();
+        assert_eq!(register_left_click(&mut data, now), None);
+        assert_eq!(register_left_click(&mut data, now + Duration::from_millis(200)), Some(Modules::Clock));
+        // The pair is consumed, a third click starts over
+        assert_eq!(register_left_click(&mut data, now + Duration::from_millis(300)), None);
+    }
+
+    #[test]
+    fn slow_clicks_or_clicks_on_other_modules_are_not_double_clicks()
+    {
+        let mut data = BindingData { hovered_module: Some(Modules::Clock), ..Default::default() };
+        let now = Instant::now();
+        let _ = register_left_click(&mut data, now);
+        assert_eq!(register_left_click(&mut data, now + DOUBLE_CLICK_INTERVAL + Duration::from_millis(1)), None);
+        data.hovered_module = Some(Modules::Cpu);
+        assert_eq!(register_left_click(&mut data, now + DOUBLE_CLICK_INTERVAL + Duration::from_millis(50)), None);
+        data.hovered_module = None;
+        assert_eq!(register_left_click(&mut data, now + DOUBLE_CLICK_INTERVAL + Duration::from_millis(60)), None);
+    }
+}
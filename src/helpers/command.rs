@@ -0,0 +1,381 @@
+// ============ IMPORTS ============
+use std::{collections::HashMap, process::Stdio, sync::{LazyLock, Mutex}, time::Duration};
+use tokio::sync::watch;
+
+
+
+
+
+// ============ CRATES ============
+use crate::AppData;
+
+
+
+
+
+// ============ CONST ============
+/// For the short-lived helpers the bar runs itself (wpctl, playerctl, ...).
+pub const SYSTEM_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+
+
+
+
+// ============ STATICS ============
+/// Keys of the commands currently running, a command is skipped while a previous run with the same key is in flight.
+static IN_FLIGHT: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Bumped on config reload, commands spawned with `cancel_on_reload` are killed when it changes.
+static RELOAD_GENERATION: LazyLock<watch::Sender<u64>> = LazyLock::new(|| watch::channel(0).0);
+
+
+
+
+
+// ============ ENUM/STRUCT, ETC ============
+#[derive(Clone, Debug, Default)]
+pub struct CommandOptions
+{
+    /// Shown in the log lines, e.g: "'Battery' continous_command".
+    pub label: String,
+    pub timeout: Option<Duration>,
+    /// Runs sharing this key never overlap, a new one is skipped while the previous is still running.
+    pub exclusive_key: Option<String>,
+    pub cancel_on_reload: bool,
+    /// Runs the first argv element as a `sh -c` script, so pipes and `$VARS` work. Further elements are passed quoted.
+    pub shell: bool,
+    pub env: Vec<(String, String)>,
+}
+
+/// A click command, along with where it was triggered from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommandRequest
+{
+    /// Custom module the captured output belongs to.
+    pub output_index: Option<usize>,
+    pub command: Vec<String>,
+    /// Shown in the log lines.
+    pub name: String,
+    /// Exported as ICEBAR_MODULE, e.g: "Cpu" or "CustomModule(0)".
+    pub module: String,
+    pub is_left_click: bool,
+    /// Exported as ICEBAR_BUTTON instead of "left" / "right", for bindings like "middle" or "scroll-up".
+    pub button: Option<String>,
+    pub output_as_text: bool,
+    pub shell: bool,
+    /// User variables, applied on top of the automatic ICEBAR_* ones.
+    pub env: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommandResult
+{
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` when the command couldn't start, was killed by a signal, timed out or was cancelled.
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub cancelled: bool,
+}
+
+/// Releases the in-flight key when the run finishes or its future is dropped.
+struct InFlightGuard(Option<String>);
+
+impl Drop for InFlightGuard
+{
+    fn drop(&mut self)
+    {
+        if let Some(key) = self.0.take() && let Ok(mut in_flight) = IN_FLIGHT.lock() { in_flight.retain(|running| *running != key); }
+    }
+}
+
+
+
+
+
+// ============ FUNCTIONS ============
+impl CommandResult
+{
+    /// Stdout, or stderr when stdout is empty and the module asked to display errors.
+    pub fn display_text(&self, display_err_output_if_failed: bool) -> String
+    {
+        if self.stdout.is_empty() && display_err_output_if_failed { self.stderr.clone() } else { self.stdout.clone() }
+    }
+
+    /// False when the run was cut short by its timeout or a config reload, its output is then incomplete.
+    pub fn finished(&self) -> bool
+    {
+        !self.timed_out && !self.cancelled
+    }
+}
+
+
+
+impl CommandRequest
+{
+    /// `CustomAction` of a built-in module.
+    pub fn custom_action(module: &str, command: &[String], is_left_click: bool) -> Self
+    {
+        Self { command: command.to_vec(), name: format!("{module} Custom Action"), module: module.into(), is_left_click, ..Default::default() }
+    }
+
+    /// `ShellAction` of a built-in module.
+    pub fn shell_action(module: &str, script: &str, is_left_click: bool) -> Self
+    {
+        Self { shell: true, ..Self::custom_action(module, &[script.to_string()], is_left_click) }
+    }
+}
+
+
+
+/// The script given to `sh -c` when a command runs in shell mode.
+/// The first element is the script itself, any further element is quoted so it reaches the script as one literal word.
+pub fn shell_script(program: &str, args: &[String]) -> String
+{
+    std::iter::once(program.to_string()).chain(args.iter().map(|arg| shell_quote(arg))).collect::<Vec<_>>().join(" ")
+}
+
+
+
+/// Wraps the argument in single quotes, closing and reopening them around every quote it contains.
+pub fn shell_quote(arg: &str) -> String
+{
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+
+
+pub fn build_command(program: &str, args: &[String], shell: bool, env: &[(String, String)]) -> tokio::process::Command
+{
+    let mut command = if shell
+    {
+        let mut command = tokio::process::Command::new("sh");
+        command.arg("-c").arg(shell_script(program, args));
+        command
+    }
+    else
+    {
+        let mut command = tokio::process::Command::new(program);
+        command.args(args);
+        command
+    };
+    command.envs(env.iter().map(|(key, value)| (key, value)));
+    command
+}
+
+
+
+/// Variables that stay the same for the whole life of the bar, safe for long-running processes.
+pub fn static_context_env(app: &AppData, module: &str) -> Vec<(String, String)>
+{
+    vec!
+    [
+        ("ICEBAR_MODULE".into(), module.into()),
+        ("ICEBAR_OUTPUT".into(), app.ron_config.general.display.clone().unwrap_or_default()),
+    ]
+}
+
+
+
+/// Exported to every spawned command so scripts know what triggered them. `button` is empty for polled commands.
+pub fn context_env(app: &AppData, module: &str, button: &str) -> Vec<(String, String)>
+{
+    let volume = &app.modules_data.volume_data;
+    let mut env = static_context_env(app, module);
+    env.extend(
+    [
+        ("ICEBAR_BUTTON".into(), button.into()),
+        ("ICEBAR_VOLUME".into(), ((volume.volume_output_raw * 100.).round() as i32).to_string()),
+        ("ICEBAR_INPUT_VOLUME".into(), ((volume.volume_input_raw * 100.).round() as i32).to_string()),
+        ("ICEBAR_WORKSPACE".into(), app.modules_data.workspace_data.current_workspace.to_string()),
+    ]);
+    env
+}
+
+
+
+/// User `env` maps are applied after the automatic variables, so they can override them.
+pub fn merge_env(mut env: Vec<(String, String)>, user_env: &HashMap<String, String>) -> Vec<(String, String)>
+{
+    let mut user_env: Vec<(String, String)> = user_env.iter().map(|(key, value)| (key.clone(), value.clone())).collect();
+    user_env.sort();
+    env.retain(|(key, _)| !user_env.iter().any(|(user_key, _)| user_key == key));
+    env.extend(user_env);
+    env
+}
+
+
+
+fn try_acquire(key: Option<String>) -> Option<InFlightGuard>
+{
+    let Some(key) = key else { return Some(InFlightGuard(None)); };
+    let mut in_flight = IN_FLIGHT.lock().ok()?;
+    if in_flight.contains(&key) { return None; }
+    in_flight.push(key.clone());
+    Some(InFlightGuard(Some(key)))
+}
+
+
+
+/// Kills every running command spawned with `cancel_on_reload`.
+pub fn cancel_running_commands()
+{
+    RELOAD_GENERATION.send_modify(|generation| *generation += 1);
+}
+
+
+
+/// Runs the command to completion and captures its output, stderr is always forwarded to the log.
+/// Returns `None` without spawning anything when a run with the same `exclusive_key` is still in flight.
+pub async fn run_command(program: String, args: Vec<String>, options: CommandOptions) -> Option<CommandResult>
+{
+    let _guard = try_acquire(options.exclusive_key.clone())?;
+    let mut reload = RELOAD_GENERATION.subscribe();
+    let mut command = build_command(&program, &args, options.shell, &options.env);
+    let output = command.stdin(Stdio::null()).kill_on_drop(true).output();
+
+    let timeout = async { match options.timeout { Some(timeout) => tokio::time::sleep(timeout).await, None => std::future::pending().await } };
+    let cancelled = async { if !options.cancel_on_reload || reload.changed().await.is_err() { std::future::pending::<()>().await; } };
+
+    let result = tokio::select!
+    {
+        output = output => match output
+        {
+            Ok(output) => CommandResult
+            {
+                stdout: String::from_utf8_lossy(&output.stdout).into(),
+                stderr: String::from_utf8_lossy(&output.stderr).into(),
+                exit_code: output.status.code(),
+                timed_out: false,
+                cancelled: false,
+            },
+            Err(e) => { eprintln!("[icebar] {} couldn't start {program}: {e}", options.label); CommandResult { stderr: e.to_string(), ..Default::default() } }
+        },
+        _ = timeout =>
+        {
+            eprintln!("[icebar] {} timed out after {}ms and was killed", options.label, options.timeout.unwrap_or_default().as_millis());
+            CommandResult { timed_out: true, ..Default::default() }
+        }
+        _ = cancelled =>
+        {
+            eprintln!("[icebar] {} was killed by a config reload", options.label);
+            CommandResult { cancelled: true, ..Default::default() }
+        }
+    };
+
+    if !result.stderr.trim().is_empty() { eprintln!("[icebar] {} stderr: {}", options.label, result.stderr.trim_end()); }
+    if let Some(code) = result.exit_code && code != 0 { eprintln!("[icebar] {} exited with code {code}", options.label); }
+    Some(result)
+}
+
+
+
+/// Fire and forget, for commands whose output nobody reads. Applications launched this way outlive reloads and the bar itself, and have no timeout.
+pub fn spawn_command(program: String, args: Vec<String>, options: CommandOptions)
+{
+    // Own process group, so a Ctrl+C on the bar's terminal doesn't reach the launched app either
+    let spawned = build_command(&program, &args, options.shell, &options.env).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).process_group(0).spawn();
+    match spawned
+    {
+        // Only waited on so it doesn't stay a zombie, dropping the wait doesn't kill it
+        Ok(mut child) => { tokio::spawn(async move { let _ = child.wait().await; }); }
+        Err(e) => eprintln!("[icebar] {} couldn't start {program}: {e}", options.label),
+    }
+}
+
+
+
+
+
+// ============ TESTS ============
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // ---- display_text -------------------------------------------------------
+
+    #[test]
+    fn display_text_falls_back_to_stderr_only_when_asked()
+    {
+        let result = CommandResult { stderr: "boom".into(), exit_code: Some(1), ..Default::default() };
+        assert_eq!(result.display_text(true), "boom");
+        assert_eq!(result.display_text(false), "");
+        let result = CommandResult { stdout: "ok".into(), stderr: "warn".into(), ..Default::default() };
+        assert_eq!(result.display_text(true), "ok");
+    }
+
+    #[test]
+    fn timed_out_and_cancelled_runs_are_not_finished()
+    {
+        assert!(CommandResult { exit_code: Some(1), ..Default::default() }.finished());
+        assert!(!CommandResult { timed_out: true, ..Default::default() }.finished());
+        assert!(!CommandResult { cancelled: true, ..Default::default() }.finished());
+    }
+
+    // ---- try_acquire --------------------------------------------------------
+
+    #[test]
+    fn exclusive_key_blocks_until_the_guard_is_dropped()
+    {
+        let key = || Some("test-exclusive-key".to_string());
+        let guard = try_acquire(key()).expect("first run acquires the key");
+        assert!(try_acquire(key()).is_none());
+        assert!(try_acquire(Some("test-other-key".into())).is_some());
+        drop(guard);
+        assert!(try_acquire(key()).is_some());
+    }
+
+    #[test]
+    fn commands_without_key_never_block()
+    {
+        let _first = try_acquire(None).unwrap();
+        assert!(try_acquire(None).is_some());
+    }
+
+    // ---- shell mode ---------------------------------------------------------
+
+    #[test]
+    fn shell_script_keeps_the_script_and_quotes_the_arguments()
+    {
+        assert_eq!(shell_script("pactl list sinks | grep -c Sink", &[]), "pactl list sinks | grep -c Sink");
+        assert_eq!(shell_script("echo", &["$HOME".into(), "|".into(), "wc".into()]), "echo '$HOME' '|' 'wc'");
+        assert_eq!(shell_script("notify-send", &["it's done; rm -rf ~".into()]), "notify-send 'it'\\''s done; rm -rf ~'");
+    }
+
+    #[test]
+    fn shell_action_runs_the_script_through_the_shell()
+    {
+        let request = CommandRequest::shell_action("Cpu", "htop &", false);
+        assert!(request.shell);
+        assert_eq!(request.command, vec!["htop &".to_string()]);
+        assert_eq!(request.module, "Cpu");
+        assert!(!request.is_left_click);
+    }
+
+    // ---- env ----------------------------------------------------------------
+
+    #[test]
+    fn context_env_exports_module_button_volume_and_workspace()
+    {
+        let mut app = AppData { ..Default::default() };
+        app.ron_config.general.display = Some("DP-1".into());
+        app.modules_data.volume_data.volume_output_raw = 0.456;
+        app.modules_data.workspace_data.current_workspace = 3;
+        let env = context_env(&app, "VolumeOutput", "left");
+        let get = |key: &str| env.iter().find(|(name, _)| name == key).map(|(_, value)| value.as_str());
+        assert_eq!(get("ICEBAR_MODULE"), Some("VolumeOutput"));
+        assert_eq!(get("ICEBAR_BUTTON"), Some("left"));
+        assert_eq!(get("ICEBAR_OUTPUT"), Some("DP-1"));
+        assert_eq!(get("ICEBAR_VOLUME"), Some("46"));
+        assert_eq!(get("ICEBAR_WORKSPACE"), Some("3"));
+    }
+
+    #[test]
+    fn user_env_overrides_automatic_variables()
+    {
+        let user_env = [("ICEBAR_OUTPUT".to_string(), "mine".to_string()), ("FOO".to_string(), "bar".to_string())].into_iter().collect();
+        let env = merge_env(vec![("ICEBAR_MODULE".into(), "Cpu".into()), ("ICEBAR_OUTPUT".into(), "DP-1".into())], &user_env);
+        assert_eq!(env, vec![("ICEBAR_MODULE".to_string(), "Cpu".to_string()), ("FOO".to_string(), "bar".to_string()), ("ICEBAR_OUTPUT".to_string(), "mine".to_string())]);
+    }
+}
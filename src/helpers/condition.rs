@@ -0,0 +1,542 @@
+// ============ IMPORTS ============
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::sync::LazyLock;
+
+
+
+
+
+// ============ CRATES ============
+use crate::modules::{custom_modules::custom_module_json_output, data::Modules};
+use crate::AppData;
+
+
+
+
+
+// ============ CONST ============
+/// Every name a condition can read, checked when the config is parsed so typos fail loudly instead of hiding a module.
+pub const CONDITION_VARIABLES: &[&str] =
+&[
+    "cpu.usage", "cpu_temp.temp",
+    "ram.percent", "ram.used_mb", "ram.total_mb",
+    "disk.percent", "disk.used", "disk.free", "disk.total",
+    "network.rx", "network.tx", "network.id", "network.iface", "network.level", "network.connection_type",
+    "volume.output", "volume.input", "volume.output_muted", "volume.input_muted",
+    "media.status", "media.metadata",
+    "workspace.current", "workspace.count",
+    "window.title", "window.app_id", "window.class", "window.floating", "window.fullscreen",
+    "binding_mode.mode",
+    "tray.count",
+    "clock.alt",
+    "system.has_battery",
+    "custom.output", "custom.exit_code", "custom.text", "custom.tooltip", "custom.class", "custom.percentage", "custom.alt",
+];
+
+
+
+
+
+// ============ STATICS ============
+static HAS_BATTERY: LazyLock<bool> = LazyLock::new(||
+{
+    std::fs::read_dir("/sys/class/power_supply").map(|entries| entries.flatten().any(|entry|
+    {
+        std::fs::read_to_string(entry.path().join("type")).is_ok_and(|kind| kind.trim() == "Battery")
+    })).unwrap_or(false)
+});
+
+
+
+
+
+// ============ ENUM/STRUCT, ETC ============
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value
+{
+    Number(f64),
+    Text(String),
+    Bool(bool),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompareOp
+{
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr
+{
+    Literal(Value),
+    Variable(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token
+{
+    Number(f64),
+    Text(String),
+    Identifier(String),
+    Compare(CompareOp),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+/// A `visible_when` expression, parsed once when the config is read. Written as its source string.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition
+{
+    pub source: String,
+    pub expr: Expr,
+}
+
+struct Parser
+{
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+
+
+
+
+// ============ FUNCTIONS ============
+impl Value
+{
+    fn as_number(&self) -> Option<f64>
+    {
+        match self
+        {
+            Value::Number(number) => Some(*number),
+            Value::Text(text) => text.trim().parse().ok(),
+            Value::Bool(_) => None,
+        }
+    }
+
+    fn as_text(&self) -> String
+    {
+        match self
+        {
+            Value::Number(number) => number.to_string(),
+            Value::Text(text) => text.clone(),
+            Value::Bool(bool) => bool.to_string(),
+        }
+    }
+
+    pub fn is_truthy(&self) -> bool
+    {
+        match self
+        {
+            Value::Number(number) => *number != 0.,
+            Value::Text(text) => !text.is_empty(),
+            Value::Bool(bool) => *bool,
+        }
+    }
+}
+
+
+
+impl Condition
+{
+    pub fn parse(source: &str) -> Result<Self, String>
+    {
+        Ok(Self { source: source.to_string(), expr: parse_expression(source)? })
+    }
+
+    /// Whether `module` should be rendered right now.
+    pub fn is_met(&self, app: &AppData, module: &Modules) -> bool
+    {
+        evaluate(&self.expr, &|name| condition_variable(app, module, name)).is_truthy()
+    }
+}
+
+
+
+impl Serialize for Condition
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+
+
+impl<'de> Deserialize<'de> for Condition
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+    {
+        let source = String::deserialize(deserializer)?;
+        Condition::parse(&source).map_err(|err| serde::de::Error::custom(format!("invalid visible_when '{source}': {err}")))
+    }
+}
+
+
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String>
+{
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len()
+    {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c
+        {
+            _ if c.is_whitespace() => { i += 1; }
+            '(' => { tokens.push(Token::Open); i += 1; }
+            ')' => { tokens.push(Token::Close); i += 1; }
+            '&' if next == Some('&') => { tokens.push(Token::And); i += 2; }
+            '|' if next == Some('|') => { tokens.push(Token::Or); i += 2; }
+            '=' if next == Some('=') => { tokens.push(Token::Compare(CompareOp::Equal)); i += 2; }
+            '!' if next == Some('=') => { tokens.push(Token::Compare(CompareOp::NotEqual)); i += 2; }
+            '!' => { tokens.push(Token::Not); i += 1; }
+            '>' if next == Some('=') => { tokens.push(Token::Compare(CompareOp::GreaterOrEqual)); i += 2; }
+            '<' if next == Some('=') => { tokens.push(Token::Compare(CompareOp::LessOrEqual)); i += 2; }
+            '>' => { tokens.push(Token::Compare(CompareOp::Greater)); i += 1; }
+            '<' => { tokens.push(Token::Compare(CompareOp::Less)); i += 1; }
+            '"' | '\'' =>
+            {
+                let mut text = String::new();
+                i += 1;
+                loop
+                {
+                    match chars.get(i)
+                    {
+                        None => return Err("unterminated string".into()),
+                        Some('\\') if chars.get(i + 1).is_some() => { text.push(chars[i + 1]); i += 2; }
+                        Some(&quote) if quote == c => { i += 1; break; }
+                        Some(&other) => { text.push(other); i += 1; }
+                    }
+                }
+                tokens.push(Token::Text(text));
+            }
+            _ if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) =>
+            {
+                let start = i;
+                i += 1;
+                while chars.get(i).is_some_and(|d| d.is_ascii_digit() || *d == '.') { i += 1; }
+                let number: String = chars[start..i].iter().collect();
+                tokens.push(Token::Number(number.parse().map_err(|_| format!("invalid number '{number}'"))?));
+            }
+            _ if c.is_alphabetic() || c == '_' =>
+            {
+                let start = i;
+                while chars.get(i).is_some_and(|d| d.is_alphanumeric() || *d == '_' || *d == '.') { i += 1; }
+                tokens.push(Token::Identifier(chars[start..i].iter().collect()));
+            }
+            _ => return Err(format!("unexpected '{c}'")),
+        }
+    }
+    Ok(tokens)
+}
+
+
+
+impl Parser
+{
+    fn peek(&self) -> Option<&Token>
+    {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) -> Option<Token>
+    {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Expr, String>
+    {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or)
+        {
+            self.position += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String>
+    {
+        let mut left = self.unary()?;
+        while self.peek() == Some(&Token::And)
+        {
+            self.position += 1;
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String>
+    {
+        if self.peek() == Some(&Token::Not)
+        {
+            self.position += 1;
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        let left = self.operand()?;
+        if let Some(Token::Compare(op)) = self.peek().cloned()
+        {
+            self.position += 1;
+            return Ok(Expr::Compare(Box::new(left), op, Box::new(self.operand()?)));
+        }
+        Ok(left)
+    }
+
+    fn operand(&mut self) -> Result<Expr, String>
+    {
+        match self.advance()
+        {
+            Some(Token::Number(number)) => Ok(Expr::Literal(Value::Number(number))),
+            Some(Token::Text(text)) => Ok(Expr::Literal(Value::Text(text))),
+            Some(Token::Identifier(name)) if name == "true" || name == "false" => Ok(Expr::Literal(Value::Bool(name == "true"))),
+            Some(Token::Identifier(name)) if CONDITION_VARIABLES.contains(&name.as_str()) => Ok(Expr::Variable(name)),
+            Some(Token::Identifier(name)) => Err(format!("unknown variable '{name}'")),
+            Some(Token::Open) =>
+            {
+                let inner = self.or()?;
+                if self.advance() != Some(Token::Close) { return Err("missing ')'".into()); }
+                Ok(inner)
+            }
+            Some(token) => Err(format!("unexpected {token:?}")),
+            None => Err("unexpected end of expression".into()),
+        }
+    }
+}
+
+
+
+pub fn parse_expression(source: &str) -> Result<Expr, String>
+{
+    let mut parser = Parser { tokens: tokenize(source)?, position: 0 };
+    let expr = parser.or()?;
+    if let Some(token) = parser.peek() { return Err(format!("unexpected {token:?}")); }
+    Ok(expr)
+}
+
+
+
+fn compare(left: &Value, op: CompareOp, right: &Value) -> bool
+{
+    if let (Some(left), Some(right)) = (left.as_number(), right.as_number())
+    {
+        return match op
+        {
+            CompareOp::Equal => left == right,
+            CompareOp::NotEqual => left != right,
+            CompareOp::Greater => left > right,
+            CompareOp::GreaterOrEqual => left >= right,
+            CompareOp::Less => left < right,
+            CompareOp::LessOrEqual => left <= right,
+        };
+    }
+    match op
+    {
+        CompareOp::Equal => left.as_text() == right.as_text(),
+        CompareOp::NotEqual => left.as_text() != right.as_text(),
+        // Ordering only makes sense between numbers
+        _ => false,
+    }
+}
+
+
+
+pub fn evaluate(expr: &Expr, variable: &dyn Fn(&str) -> Value) -> Value
+{
+    match expr
+    {
+        Expr::Literal(value) => value.clone(),
+        Expr::Variable(name) => variable(name),
+        Expr::Not(inner) => Value::Bool(!evaluate(inner, variable).is_truthy()),
+        Expr::And(left, right) => Value::Bool(evaluate(left, variable).is_truthy() && evaluate(right, variable).is_truthy()),
+        Expr::Or(left, right) => Value::Bool(evaluate(left, variable).is_truthy() || evaluate(right, variable).is_truthy()),
+        Expr::Compare(left, op, right) => Value::Bool(compare(&evaluate(left, variable), *op, &evaluate(right, variable))),
+    }
+}
+
+
+
+/// Current value of a condition variable, `custom.*` reads the custom module being evaluated.
+pub fn condition_variable(app: &AppData, module: &Modules, name: &str) -> Value
+{
+    let data = &app.modules_data;
+    let number = Value::Number;
+    let text = |value: &str| Value::Text(value.to_string());
+    let custom_index = match module { Modules::CustomModule(index) => Some(*index), _ => None };
+    let custom_json = custom_index.and_then(|index| custom_module_json_output(app, index));
+    match name
+    {
+        "cpu.usage" => number(data.cpu_data.usage_percent as f64),
+        "cpu_temp.temp" => number(data.cpu_temp_data.temp_celsius as f64),
+        "ram.percent" => number(data.ram_data.percent as f64),
+        "ram.used_mb" => number(data.ram_data.used_mb as f64),
+        "ram.total_mb" => number(data.ram_data.total_mb as f64),
+        "disk.percent" => number(data.disk_data.percent as f64),
+        "disk.used" => number(data.disk_data.used as f64),
+        "disk.free" => number(data.disk_data.free as f64),
+        "disk.total" => number(data.disk_data.total as f64),
+        "network.rx" => number(data.network_data.rx_bytes_per_sec as f64),
+        "network.tx" => number(data.network_data.tx_bytes_per_sec as f64),
+        "network.id" => text(&data.network_data.id),
+        "network.iface" => text(&data.network_data.iface),
+        "network.level" => number(data.network_data.network_level as f64),
+        "network.connection_type" => number(data.network_data.connection_type as f64),
+        "volume.output" => number((data.volume_data.volume_output_raw as f64 * 100.).round()),
+        "volume.input" => number((data.volume_data.volume_input_raw as f64 * 100.).round()),
+        "volume.output_muted" => Value::Bool(data.volume_data.volume_output_is_muted),
+        "volume.input_muted" => Value::Bool(data.volume_data.volume_input_is_muted),
+        "media.status" => text(&data.media_player_data.status),
+        "media.metadata" => text(&data.media_player_data.metadata),
+        "workspace.current" => number(data.workspace_data.current_workspace as f64),
+        "workspace.count" => number(data.workspace_data.workspaces.len() as f64),
+        "window.title" => text(&data.focused_window_data.title),
+        "window.app_id" => text(&data.focused_window_data.app_id),
+        "window.class" => text(&data.focused_window_data.class),
+        "window.floating" => Value::Bool(data.focused_window_data.floating),
+        "window.fullscreen" => Value::Bool(data.focused_window_data.fullscreen),
+        "binding_mode.mode" => text(&data.binding_mode_data.mode),
+        "tray.count" => number(data.tray_icons.len() as f64),
+        "clock.alt" => Value::Bool(data.clock_data.is_showing_alt_clock),
+        "system.has_battery" => Value::Bool(*HAS_BATTERY),
+        "custom.output" =>
+        {
+            let outputs = &data.custom_module_data;
+            let output = custom_index.and_then(|index| outputs.cached_continuous_outputs.get(index).filter(|output| !output.is_empty()).or(outputs.cached_command_outputs.get(index)));
+            text(output.map(String::as_str).unwrap_or_default().trim())
+        }
+        "custom.exit_code" => custom_index.and_then(|index| data.custom_module_data.cached_exit_codes.get(index).copied().flatten()).map(|code| number(code as f64)).unwrap_or_else(|| text("")),
+        "custom.text" => text(custom_json.map(|output| output.text.as_str()).unwrap_or_default()),
+        "custom.tooltip" => text(custom_json.map(|output| output.tooltip.as_str()).unwrap_or_default()),
+        "custom.class" => text(&custom_json.map(|output| output.class.join(" ")).unwrap_or_default()),
+        "custom.percentage" => custom_json.and_then(|output| output.percentage).map(|percentage| number(percentage as f64)).unwrap_or_else(|| text("")),
+        "custom.alt" => text(custom_json.map(|output| output.alt.as_str()).unwrap_or_default()),
+        _ => text(""),
+    }
+}
+
+
+
+
+
+// ============ TESTS ============
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn eval(source: &str, variable: &dyn Fn(&str) -> Value) -> bool
+    {
+        evaluate(&parse_expression(source).unwrap(), variable).is_truthy()
+    }
+
+    // ---- parse_expression ---------------------------------------------------
+
+    #[test]
+    fn parses_comparisons_with_precedence()
+    {
+        let expr = parse_expression(r#"cpu.usage > 50 || media.status == "Playing" && !volume.output_muted"#).unwrap();
+        let Expr::Or(_, right) = expr else { panic!("|| binds loosest") };
+        assert!(matches!(*right, Expr::And(_, _)));
+    }
+
+    #[test]
+    fn rejects_unknown_variables_and_bad_syntax()
+    {
+        assert!(parse_expression("cpu.usgae > 50").unwrap_err().contains("cpu.usgae"));
+        assert!(parse_expression("(cpu.usage > 50").is_err());
+        assert!(parse_expression("cpu.usage >").is_err());
+        assert!(parse_expression("media.status == \"Playing").is_err());
+        assert!(parse_expression("cpu.usage 50").is_err());
+    }
+
+    #[test]
+    fn condition_deserializes_from_ron_strings()
+    {
+        let condition: Condition = ron::from_str(r#""tray.count > 0""#).unwrap();
+        assert_eq!(condition.source, "tray.count > 0");
+        assert!(ron::from_str::<Condition>(r#""tray.cout > 0""#).is_err());
+        assert_eq!(ron::to_string(&condition).unwrap(), r#""tray.count > 0""#);
+    }
+
+    // ---- evaluate -----------------------------------------------------------
+
+    #[test]
+    fn compares_numbers_and_text()
+    {
+        let variable = |name: &str| match name
+        {
+            "cpu.usage" => Value::Number(63.),
+            "media.status" => Value::Text("Playing".into()),
+            "custom.output" => Value::Text(" 42 ".into()),
+            _ => Value::Text(String::new()),
+        };
+        assert!(eval("cpu.usage > 50", &variable));
+        assert!(!eval("cpu.usage <= 50", &variable));
+        assert!(eval("media.status == 'Playing'", &variable));
+        assert!(eval("media.status != \"Paused\"", &variable));
+        assert!(eval("custom.output >= 42", &variable));
+        // Text can't be ordered
+        assert!(!eval("media.status > 3", &variable));
+    }
+
+    #[test]
+    fn bare_variables_use_truthiness()
+    {
+        let variable = |name: &str| match name
+        {
+            "window.floating" => Value::Bool(true),
+            "tray.count" => Value::Number(0.),
+            _ => Value::Text(String::new()),
+        };
+        assert!(eval("window.floating", &variable));
+        assert!(!eval("tray.count", &variable));
+        assert!(!eval("media.status", &variable));
+        assert!(eval("!(tray.count || media.status) && window.floating", &variable));
+    }
+
+    #[test]
+    fn negative_numbers_parse()
+    {
+        assert!(eval("custom.exit_code == -1", &|_| Value::Number(-1.)));
+    }
+
+    // ---- condition_variable -------------------------------------------------
+
+    #[test]
+    fn reads_module_data()
+    {
+        let mut app = AppData::default();
+        app.modules_data.cpu_data.usage_percent = 72.5;
+        app.modules_data.volume_data.volume_output_raw = 0.4;
+        app.modules_data.media_player_data.status = "Playing".into();
+        assert_eq!(condition_variable(&app, &Modules::Cpu, "cpu.usage"), Value::Number(72.5));
+        assert_eq!(condition_variable(&app, &Modules::Cpu, "volume.output"), Value::Number(40.));
+        assert!(Condition::parse("media.status == \"Playing\" && cpu.usage > 50").unwrap().is_met(&app, &Modules::MediaPlayerMetaData));
+    }
+
+    #[test]
+    fn custom_variables_read_the_evaluated_module()
+    {
+        let mut app = AppData::default();
+        app.modules_data.custom_module_data.cached_continuous_outputs = vec!["".into(), "5 updates\n".into()];
+        assert_eq!(condition_variable(&app, &Modules::CustomModule(1), "custom.output"), Value::Text("5 updates".into()));
+        assert_eq!(condition_variable(&app, &Modules::CustomModule(0), "custom.output"), Value::Text(String::new()));
+        assert_eq!(condition_variable(&app, &Modules::Clock, "custom.output"), Value::Text(String::new()));
+    }
+}
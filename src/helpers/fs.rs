@@ -48,9 +48,11 @@ pub fn check_if_config_file_exists() -> Option<String>
 // - It is very important to set the "display" variable. Not setting it may cause undefined behaviour.
 // - Missing options are fine and will fall back to their default values, but invalid syntax will crash.
 // - The "continous_command" field may generate high CPU usage depending on how heavy the command is.
-// - The "continous_command" field must not run a loop of any kind — the process will hang forever if it does.
+// - The "continous_command" field must not run a loop of any kind — the process will hang forever if it does,
+//   unless "exec_stream" is true: the command is then started once and every line it prints updates the module.
 // - "bar_size" has the format (width, height). For side bars, a width of 0 is not valid and will crash.
-// - "NiriWorkspaces" does not support "persistent_workspaces". If set, it will be ignored.
+// - Niri and ext-workspace compositors (labwc, Wayfire, ...) do not support "persistent_workspaces". If set, it will be ignored.
+// - On River, workspaces are tags: only focused, occupied or urgent tags are shown, "persistent_workspaces" adds the first N tags.
 // - If the number of workspaces exceeds the number of entries in "workspace_text" or "workspace_selected_text",
 //   the extra workspaces will display their number as text instead.
 //
@@ -59,13 +61,15 @@ pub fn check_if_config_file_exists() -> Option<String>
 //
 //=================================================================[  TIPS  ]===================================================================
 // All possible modules:
+//   "Workspaces", "FocusedWindow" (these follow the running compositor: Hyprland, Sway, Niri,
+//     or any other one implementing ext-workspace-v1 or River's status protocol),
 //   "FocusedWindowSway", "FocusedWindowHypr", "FocusedWindowNiri",
-//   "NiriWorkspaces", "HyprWorkspaces", "SwayWorkspaces",
+//   "NiriWorkspaces", "HyprWorkspaces", "SwayWorkspaces" (these force one compositor),
 //   "MediaPlayerMetaData", "MediaPlayerButtons",
 //   "CustomModule(index)", "Image(index)",
 //   "CpuTemp", "Ram", "Cpu", "Disk",
 //   "VolumeOutput", "VolumeInput",
-//   "Network", "Clock", "Tray"
+//   "Network", "Clock", "Tray", "Taskbar", "BindingMode"
 //
 // =============================================================================================================================================
 // All color fields now use the ColorType system. Three formats are supported:
@@ -81,6 +85,7 @@ pub fn check_if_config_file_exists() -> Option<String>
 //   general: ( display: Some("HDMI-A-1"), ... )
 //   clock: ( clock_timezones: Some(["America/New_York", "Europe/London"]), ... )
 //   workspace: ( persistent_workspaces: Some(5), ... )
+//   tray: ( tray_pinned_items: Some(3), ... )   — only the first 3 icons stay on the bar, the rest go into the expander drawer
 //   image: ( images_spacing: 5, images: [ (...), (...) ] )
 //   custom_module: ( custom_modules_spacing: 10, custom_modules: [ (...) ] )
 //
@@ -92,6 +97,45 @@ pub fn check_if_config_file_exists() -> Option<String>
 // - Setting "bar_check_reload_interval_ms" to "None" disables hot-reload.
 // - Images and Custom modules are assigned an index based on their position (top to bottom): first = 0, second = 1, etc...
 //   Reference them in the module lists as "Image(index)" or "CustomModule(index)".
+// - Images accept PNG, JPEG, GIF and SVG files. An image with "image_command" shows the path printed on the last line of the command output,
+//   re-run every "image_command_interval" ms ("image_path" can stay empty), and "watch_image_file: true" reloads it whenever the file changes,
+//   e.g: image_command: ["sh", "-c", "qrencode -o /tmp/icebar-qr.png \"$(wl-paste)\" && echo /tmp/icebar-qr.png"]
+// - Custom modules with "output_format: Json" read Waybar style output: {"text", "tooltip", "class", "percentage", "alt"}.
+//   "text" fills {output} / {continous_output}, and "all_output_as_text_format" also accepts {tooltip}, {class}, {percentage}, {alt} and {icon}.
+//   {icon} is "alt_icons" entry for "alt" when there is one, otherwise "format_icons" indexed by "percentage".
+//   Every listed class applies its "class_styles" entry, e.g: class_styles: { "critical": ( button_color: Some(RGB((200, 40, 40))) ) }
+// - Commands whose output is shown are killed after "command_timeout_ms" (None disables it) and on config reload,
+//   a new run is skipped while the previous one is still running. {exit_code} holds the exit code of the last run.
+// - Commands are argv lists. Set "shell: true" on a custom module or image (or use ShellAction("...") instead of CustomAction([...]))
+//   to run them through "sh -c", so pipes and $VARS work: ShellAction("notify-send Volume $ICEBAR_VOLUME%")
+//   In shell mode the first element is the script, any further element is appended quoted, as one literal word.
+// - Every command gets ICEBAR_MODULE, ICEBAR_BUTTON, ICEBAR_OUTPUT, ICEBAR_VOLUME, ICEBAR_INPUT_VOLUME and ICEBAR_WORKSPACE in its environment,
+//   plus the "env" map of the custom module or image, e.g: env: { "PLAYER": "spotify" }. "exec_stream" processes only get ICEBAR_MODULE and ICEBAR_OUTPUT.
+// - The "bindings" map gives any module extra triggers, keyed by its name in the module lists (e.g: "Clock" or "CustomModule(0)"):
+//   on_left_click, on_middle_click, on_right_click, on_double_click, on_scroll_up, on_scroll_down, on_scroll_left, on_scroll_right,
+//   on_hover_enter and on_hover_leave. They take the same actions as "action_on_*" fields, and ICEBAR_BUTTON holds the trigger
+//   (e.g: "middle" or "scroll-up"). DefaultAction keeps the module's built-in behavior.
+// - Internal(...) runs an action inside the bar without spawning a process, from any "action_on_*" field or binding:
+//   ToggleAlt(Clock), ToggleAlt(Network), SetVolume(5), SetVolume(-5), SetInputVolume(5), MuteToggle(Output), MuteToggle(Input),
+//   Workspace(Next), Workspace(Prev), Workspace(Index(3)), Workspace(Name("web")), MediaNext, MediaPrev, MediaPlayPause,
+//   CycleClockTimezones, ReloadConfig, ToggleBar, OpenMenu("power"), HideModule("Tray"), ShowModule("Tray"), ToggleModule("Tray"),
+//   and Sequence([...]) to run several in order, e.g: on_double_click: Some(Internal(Sequence([MuteToggle(Output), MuteToggle(Input)])))
+//   ToggleBar shrinks the bar to a one pixel strip, click the strip to bring it back.
+// - The "visible_when" map hides a module while its condition is false, keyed like "bindings". Conditions compare variables with
+//   ==, !=, >, >=, <, <= and combine them with &&, || and !, e.g: "cpu.usage > 50 && !volume.output_muted". A bare variable is true when
+//   it is non-zero / non-empty. Variables: cpu.usage, cpu_temp.temp, ram.percent, ram.used_mb, ram.total_mb, disk.percent, disk.used,
+//   disk.free, disk.total, network.rx, network.tx (bytes per second), network.id, network.iface, network.level, network.connection_type,
+//   volume.output, volume.input, volume.output_muted, volume.input_muted, media.status, media.metadata, workspace.current,
+//   workspace.count, window.title, window.app_id, window.class, window.floating, window.fullscreen, binding_mode.mode, tray.count,
+//   clock.alt, system.has_battery, and for custom modules their own custom.output, custom.exit_code, custom.text, custom.tooltip,
+//   custom.class, custom.percentage and custom.alt. Strings take 'single' quotes too, so they fit inside the RON string.
+// - "cpu_states", "ram_states", "disk_states", "cpu_temp_states", "volume_output_states" and "volume_input_states" restyle the module
+//   once its value (usage %, used %, °C, volume %) reaches a tier's "threshold", the highest reached tier wins. A tier can set "format",
+//   text_color, button_color, button_hovered_color, button_hovered_text_color, button_pressed_text_color, button_pressed_color,
+//   border_color and the three gradients, unset fields keep the module's own. "blink: true" alternates the tier with the module's
+//   normal style every "blink_interval" ms (default 500). Volume tier formats use {percent}, muted volume keeps its muted style.
+// - A custom module with "signal: Some(n)" re-runs its "continous_command" right away when icebar receives SIGRTMIN+n,
+//   e.g: "pkill -RTMIN+8 icebar" from a volume keybind. Modules using "exec_stream" ignore signals.
 //
 // - Report bugs or request features at: https://github.com/HaruNashii/Icebar
 // - Contact directly on Discord: harunashiii
@@ -277,6 +321,8 @@ BarConfig
         volume_output_button_shadow_x:                  0.0,
         volume_output_button_shadow_y:                  1.0,
         volume_output_button_shadow_blur:               3.0,
+        // e.g: "loud":     (threshold: 100.0, format: Some("   {percent}% !"), text_color: Some(RGB((235, 190, 90)))),
+        volume_output_states:                           {},
     ),
 
 
@@ -348,6 +394,7 @@ BarConfig
         volume_input_button_shadow_x:                   0.0,
         volume_input_button_shadow_y:                   1.0,
         volume_input_button_shadow_blur:                3.0,
+        volume_input_states:                            {},
     ),
 
 
@@ -462,7 +509,6 @@ BarConfig
     // ================= WORKSPACE =================
     workspace:
     (
-        niri_workspaces_update_interval:        225,
         reverse_scroll_on_workspace:            false,
         persistent_workspaces:                  Some(5),
         workspace_height:                       22,
@@ -498,6 +544,26 @@ BarConfig
         workspace_button_shadow_x:                  0.0,
         workspace_button_shadow_y:                  0.0,
         workspace_button_shadow_blur:               0.0,
+        // All, CurrentOutput (needs 'display' to be set) or GroupedByOutput
+        workspace_output_mode:                      All,
+        workspace_output_divider_color:             HEX("3d3d3d"),
+        workspace_output_divider_width:             1.,
+        workspace_output_divider_height:            16.,
+        // Hyprland special workspaces and the Sway scratchpad, 'workspace_text' also accepts {name}, {id}, {windows} and {urgent}
+        workspace_show_special:                     false,
+        workspace_special_text:                     "{name} {windows}",
+        // Workspaces holding a window that asked for attention, {urgent} counts those windows
+        workspace_button_urgent_color:              RGB((200, 60, 40)),
+        workspace_urgent_text_color:                RGB((255, 255, 255)),
+        workspace_button_urgent_gradient_color:     None,
+        workspace_urgent_blink:                     false,
+        workspace_urgent_blink_interval:            500,
+        // Icons of the applications open on each workspace, you may need a bigger 'workspace_width' to fit them
+        workspace_show_window_icons:                false,
+        workspace_window_icon_size:                 14,
+        workspace_window_icon_spacing:              2,
+        workspace_max_window_icons:                 4,
+        workspace_dedup_window_icons:               true,
     ),
 
 
@@ -526,6 +592,11 @@ BarConfig
         tray_button_shadow_x:               0.0,
         tray_button_shadow_y:               1.0,
         tray_button_shadow_blur:            3.0,
+        tray_pinned_items:                  None,
+        tray_expander_text:                 "▾",
+        tray_expander_text_size:            14,
+        tray_drawer_columns:                4,
+        tray_drawer_spacing:                4,
     ),
 
     // ================= MEDIA PLAYER METADATA =================
@@ -627,6 +698,9 @@ BarConfig
         cpu_button_shadow_x:                0.0,
         cpu_button_shadow_y:                1.0,
         cpu_button_shadow_blur:             3.0,
+        // e.g: "warning":  (threshold: 70.0, text_color: Some(RGB((235, 190, 90)))),
+        //      "critical": (threshold: 90.0, format: Some("[Color=(240, 90, 90), String=CPU]  {usage}%"), border_color: Some(RGB((240, 90, 90))), blink: true),
+        cpu_states:                         {},
     ),
 
 
@@ -660,6 +734,8 @@ BarConfig
         cpu_temp_button_shadow_x:               0.0,
         cpu_temp_button_shadow_y:               1.0,
         cpu_temp_button_shadow_blur:            3.0,
+        // e.g: "hot":      (threshold: 80.0, text_color: Some(RGB((240, 90, 90))), border_color: Some(RGB((240, 90, 90)))),
+        cpu_temp_states:                        {},
     ),
 
 
@@ -691,6 +767,8 @@ BarConfig
         ram_button_shadow_x:               0.0,
         ram_button_shadow_y:               1.0,
         ram_button_shadow_blur:            3.0,
+        // e.g: "warning":  (threshold: 85.0, text_color: Some(RGB((235, 190, 90)))),
+        ram_states:                        {},
     ),
 
 
@@ -723,6 +801,8 @@ BarConfig
         disk_button_shadow_x:               0.0,
         disk_button_shadow_y:               1.0,
         disk_button_shadow_blur:            3.0,
+        // e.g: "full":     (threshold: 95.0, text_color: Some(RGB((240, 90, 90))), blink: true, blink_interval: 1000),
+        disk_states:                        {},
     ),
 
 
@@ -747,13 +827,62 @@ BarConfig
         context_menu_border_color:              HEX("3d3d3d"),
         context_menu_border_size:               1.0,
         context_menu_border_radius:             (6.0, 6.0, 6.0, 6.0),
+        context_menu_separator_size:            9,
+        context_menu_separator_color:           HEX("3d3d3d"),
+        context_menu_icon_size:                 16,
     ),
 
 
+    // ================= MENUS =================
+    // Open one from any module with: action_on_left_click_clock: OpenMenu("power"),
+    // 'icon' can be a file path, an icon theme name or any glyph.
+    menus:
+    {
+        "power":
+        [
+            (label: "_Lock",      icon: Some("system-lock-screen"), command: ["loginctl", "lock-session"]),
+            (label: "_Suspend",   icon: Some("⏾"),                  command: ["systemctl", "suspend"]),
+            (separator: true),
+            (label: "_Power",     icon: Some("⏻"), children:
+            [
+                (label: "_Reboot",    command: ["systemctl", "reboot"]),
+                (label: "Shut _Down", command: ["systemctl", "poweroff"]),
+            ]),
+        ],
+    },
+
+
+    // ================= BINDINGS =================
+    // Left / right click bindings win over the module's own 'action_on_*' fields.
+    // Without a scroll binding, MediaPlayerMetaData / VolumeOutput / VolumeInput scroll the volume and Workspaces switches workspace, e.g:
+    //   "Clock":            ( on_middle_click: Some(OpenMenu("power")) ),
+    //   "VolumeOutput":     ( on_middle_click: Some(CustomAction(["pavucontrol"])) ),
+    //   "MediaPlayerMetaData": ( on_middle_click: Some(Internal(MediaPlayPause)), on_scroll_left: Some(Internal(MediaPrev)), on_scroll_right: Some(Internal(MediaNext)) ),
+    bindings: {},
+
+
+    // ================= VISIBLE WHEN =================
+    // Modules without an entry are always shown, e.g:
+    //   "MediaPlayerMetaData":  "media.status == 'Playing'",
+    //   "MediaPlayerButtons":   "media.status != ''",
+    //   "Tray":                 "tray.count > 0",
+    visible_when: {},
+
+
     // ================= FOCUSED WINDOW =================
+    // 'focused_window_format' accepts {title}, {app_id}, {class}, {pid}, {workspace}, {floating} and {fullscreen},
+    // the last two show 'focused_window_floating_text' / 'focused_window_fullscreen_text' when the window is floating / fullscreen
+    // 'focused_window_rewrite_rules' rewrite the title with a regex, optionally only for one app id or class:
+    //   [ ( app_id: Some("firefox"), pattern: " — Mozilla Firefox$", replacement: "" ) ]
     focused_window:
     (
         focused_window_format:                      "{title}",
+        focused_window_floating_text:               "floating",
+        focused_window_fullscreen_text:             "fullscreen",
+        focused_window_rewrite_rules:               [],
+        focused_window_show_icon:                   false,
+        focused_window_icon_size:                   16,
+        focused_window_icon_spacing:                5,
         focused_window_update_interval:             500,
         dont_show_focused_window_if_empty:          true,
         text_when_focused_window_is_empty:          "Desktop",
@@ -784,6 +913,83 @@ BarConfig
     ),
 
 
+    // ================= BINDING MODE =================
+    // Shows the Hyprland submap or the Sway/River mode, hidden while in one of 'binding_mode_hidden_modes'
+    binding_mode:
+    (
+        binding_mode_format:                        "{mode}",
+        binding_mode_hidden_modes:                  ["default"],
+        binding_mode_text_limit_len:                20,
+        binding_mode_padding:                       7,
+        binding_mode_text_size:                     13,
+        binding_mode_text_color:                    HEX("1c1c1c"),
+        binding_mode_text_orientation:              Horizontal,
+        binding_mode_button_color:                  HEX("e5a50a"),
+        binding_mode_button_hovered_color:          HEX("f6d32d"),
+        binding_mode_button_hovered_text_color:     HEX("1c1c1c"),
+        binding_mode_button_pressed_text_color:     HEX("1c1c1c"),
+        binding_mode_button_pressed_color:          HEX("c88800"),
+        binding_mode_border_color:                  HEX("2a2a2a"),
+        binding_mode_border_size:                   1.0,
+        binding_mode_border_radius:                 (6.0, 6.0, 6.0, 6.0),
+        binding_mode_side_separator:                None,
+        binding_mode_side_separator_color:          HEX("3d3d3d"),
+        binding_mode_side_separator_width:          1.,
+        binding_mode_side_separator_height:         18.,
+        binding_mode_button_gradient_color:           None,
+        binding_mode_button_hovered_gradient_color:   None,
+        binding_mode_button_pressed_gradient_color:   None,
+        binding_mode_button_shadow_color:             None,
+        binding_mode_button_shadow_x:                 0.0,
+        binding_mode_button_shadow_y:                 0.0,
+        binding_mode_button_shadow_blur:              0.0,
+    ),
+
+
+    // ================= TASKBAR =================
+    // Left click focuses a window, middle click closes it
+    // 'taskbar_filter' can be All, CurrentWorkspace or CurrentOutput, 'taskbar_format' accepts {title} and {app_id}
+    taskbar:
+    (
+        taskbar_format:                             "{title}",
+        taskbar_filter:                             All,
+        taskbar_title_limit_len:                    20,
+        taskbar_show_title:                         true,
+        taskbar_show_icons:                         true,
+        taskbar_icon_size:                          16,
+        taskbar_icon_spacing:                       4,
+        taskbar_spacing:                            4,
+        taskbar_padding:                            4,
+        taskbar_text_size:                          13,
+        taskbar_text_color:                         HEX("c0bfbc"),
+        taskbar_button_color:                       HEX("2a2a2a"),
+        taskbar_button_hovered_color:               HEX("3d3d3d"),
+        taskbar_button_hovered_text_color:          HEX("ffffff"),
+        taskbar_button_pressed_color:               HEX("1c1c1c"),
+        taskbar_button_pressed_text_color:          HEX("ffffff"),
+        taskbar_button_focused_color:               HEX("4a4a5a"),
+        taskbar_focused_text_color:                 HEX("ffffff"),
+        taskbar_button_urgent_color:                HEX("c03c28"),
+        taskbar_urgent_text_color:                  HEX("ffffff"),
+        taskbar_border_color:                       HEX("2a2a2a"),
+        taskbar_border_size:                        1.0,
+        taskbar_border_radius:                      (6.0, 6.0, 6.0, 6.0),
+        taskbar_side_separator:                     None,
+        taskbar_side_separator_color:               HEX("3d3d3d"),
+        taskbar_side_separator_width:               1.,
+        taskbar_side_separator_height:              18.,
+        taskbar_button_gradient_color:              None,
+        taskbar_button_focused_gradient_color:      None,
+        taskbar_button_urgent_gradient_color:       None,
+        taskbar_button_hovered_gradient_color:      None,
+        taskbar_button_pressed_gradient_color:      None,
+        taskbar_button_shadow_color:                None,
+        taskbar_button_shadow_x:                    0.0,
+        taskbar_button_shadow_y:                    0.0,
+        taskbar_button_shadow_blur:                 0.0,
+    ),
+
+
     // ================= IMAGE =================
     //image:
     //(\n    //    images_spacing: 5,
@@ -808,6 +1014,11 @@ BarConfig
     //                border_radius: (3., 3., 3., 3.),
     //                command_to_exec_on_left_click: ["do", "a", "flip"],
     //                command_to_exec_on_right_click: ["kitty", "echo", "Meow"],
+    //                shell: false,
+    //                env: {},
+    //                image_command: [],
+    //                image_command_interval: 5000,
+    //                watch_image_file: false,
     //            ),
     //        ],
     //),
@@ -855,7 +1066,19 @@ BarConfig
                 command_to_exec_on_left_click:      ["wofi", "--show", "drun"],
                 command_to_exec_on_right_click:     ["wofi", "--show", "run"],
                 continous_command_interval:         500,
-                continous_command:                  []
+                continous_command:                  [],
+                command_timeout_ms:                 Some(10000),
+                shell:                              false,
+                env:                                {},
+                exec_stream:                        false,
+                signal:                             None,
+                output_format:                      Text,
+                format_icons:                       [],
+                alt_icons:                          {},
+                class_styles:                       {},
+                show_tooltip:                       true,
+                tooltip_text_size:                  12,
+                tooltip_padding:                    6
             ),
             // === Example of an button that displays the output on click ===
             //(
@@ -898,6 +1121,26 @@ BarConfig
             //      	continous_command_interval: 500,
             //		continous_command: ["playerctl", "--player=spotify", "metadata", "--format", "{{ artist }} - {{ title }}"]
 	    //), 
+            // === Example of an button that follows a long-running process line by line ===
+            //(
+            //		name: "Playerctl Follow",
+            //		use_continous_output_as_text: true,
+            //		all_output_as_text_format: "{continous_output}",
+            //		output_text_limit_len: 50,
+            //		exec_stream: true,
+            //		continous_command: ["playerctl", "--follow", "metadata", "--format", "{{ artist }} - {{ title }}"]
+            //),
+            // === Example of an button driven by a Waybar style JSON script ===
+            //(
+            //		name: "Battery",
+            //		output_format: Json,
+            //		use_continous_output_as_text: true,
+            //		all_output_as_text_format: "{icon} {continous_output}",
+            //		format_icons: ["▁", "▃", "▅", "▇", "█"],
+            //		class_styles: { "critical": ( button_color: Some(RGB((200, 40, 40))) ) },
+            //		continous_command_interval: 5000,
+            //		continous_command: ["sh", "-c", "echo '{\"text\": \"15%\", \"tooltip\": \"Discharging\", \"class\": \"critical\", \"percentage\": 15}'"]
+            //),
 	],
     ),
 )"#;
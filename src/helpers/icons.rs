@@ -1,5 +1,5 @@
 // ============ IMPORTS ============
-use std::{fs, path::{Path, PathBuf}};
+use std::{fs, collections::{HashMap, HashSet}, path::{Path, PathBuf}, sync::{LazyLock, Mutex, atomic::{AtomicU32, Ordering}}};
 use zbus::{Connection, Proxy};
 use tiny_skia::Pixmap;
 
@@ -8,7 +8,54 @@ use tiny_skia::Pixmap;
 
 
 // ============ CRATES ============
-use crate::TrayEvent;
+use crate::{AppData, TrayEvent, update::Message};
+
+
+
+
+
+// ============ STATICS ============
+static ICON_TARGET_SIZE: AtomicU32 = AtomicU32::new(18);
+static ICON_CACHE: LazyLock<Mutex<HashMap<IconCacheKey, (Vec<u8>, u32, u32)>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+
+
+
+
+// ============ ENUM/STRUCT, ETC ============
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct IconCacheKey
+{
+    pub name: String,
+    pub theme: String,
+    pub size: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThemeDirectoryType
+{
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeDirectory
+{
+    pub path: String,
+    pub size: u32,
+    pub min_size: u32,
+    pub max_size: u32,
+    pub threshold: u32,
+    pub kind: ThemeDirectoryType,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexTheme
+{
+    pub inherits: Vec<String>,
+    pub directories: Vec<ThemeDirectory>,
+}
 
 
 
@@ -19,12 +66,13 @@ pub async fn fetch_icon(conn: &Connection, combined: &str) -> zbus::Result<TrayE
 {
     let (service, path) = combined.split_once('|').unwrap_or((combined, "/StatusNotifierItem"));
     let proxy = Proxy::new(conn, service, path, "org.kde.StatusNotifierItem").await?;
-    if let Ok(pixmaps) = proxy.get_property::<Vec<(i32, i32, Vec<u8>)>>("IconPixmap").await && let Some((w, h, data)) = pixmaps.into_iter().max_by_key(|(w, h, _)| w * h)
+    let target_size = icon_target_size();
+    if let Ok(pixmaps) = proxy.get_property::<Vec<(i32, i32, Vec<u8>)>>("IconPixmap").await && let Some((w, h, data)) = pick_pixmap(pixmaps, target_size)
     {
-        return Ok(TrayEvent::Icon {combined: combined.to_string(), data, width: w as u32, height: h as u32});
+        return Ok(TrayEvent::Icon {combined: combined.to_string(), data: argb32_to_rgba(&data), width: w as u32, height: h as u32});
     }
     let theme_path = proxy.get_property::<String>("IconThemePath").await.ok();
-    let try_name = |name: String| {load_icon_with_theme_path(&name, theme_path.as_deref())};
+    let try_name = |name: String| {load_icon_cached(&name, theme_path.as_deref(), target_size)};
     if let Ok(name) = proxy.get_property::<String>("IconName").await && let Some((d, w, h)) = try_name(name) 
     {
         return Ok(TrayEvent::Icon { combined: combined.to_string(), data: d, width: w, height: h });
@@ -43,7 +91,48 @@ pub async fn fetch_icon(conn: &Connection, combined: &str) -> zbus::Result<TrayE
 
 
 
+/// Sets the pixel size tray icons are resolved at, usually `tray_icon_size` times the output scale.
+pub fn set_icon_target_size(size: u32)
+{
+    ICON_TARGET_SIZE.store(size.max(1), Ordering::Relaxed);
+}
+
+
+
+pub fn icon_target_size() -> u32
+{
+    ICON_TARGET_SIZE.load(Ordering::Relaxed)
+}
+
+
+
+/// SNI pixmaps are ARGB32 in network byte order, iced wants RGBA.
+pub fn argb32_to_rgba(data: &[u8]) -> Vec<u8>
+{
+    data.chunks_exact(4).flat_map(|px| [px[1], px[2], px[3], px[0]]).collect()
+}
+
+
+
+/// Picks the pixmap whose size is closest to the target, preferring the bigger one on ties so downscaling wins over upscaling.
+pub fn pick_pixmap(pixmaps: Vec<(i32, i32, Vec<u8>)>, target_size: u32) -> Option<(i32, i32, Vec<u8>)>
+{
+    pixmaps.into_iter()
+        .filter(|(w, h, data)| *w > 0 && *h > 0 && data.len() == (*w as usize) * (*h as usize) * 4)
+        .min_by_key(|(w, h, _)| ((*w.max(h) as i64 - target_size as i64).abs(), -(*w as i64 * *h as i64)))
+}
+
+
+
 pub fn try_load_icon(path: &std::path::Path) -> Option<(Vec<u8>, u32, u32)>
+{
+    try_load_icon_sized(path, None)
+}
+
+
+
+/// Same as `try_load_icon`, but svgs are rasterized straight at `size` instead of their intrinsic size.
+pub fn try_load_icon_sized(path: &std::path::Path, size: Option<u32>) -> Option<(Vec<u8>, u32, u32)>
 {
     let bytes = std::fs::read(path).ok()?;
     match path.extension().and_then(|e| e.to_str())
@@ -52,10 +141,19 @@ pub fn try_load_icon(path: &std::path::Path) -> Option<(Vec<u8>, u32, u32)>
         {
             let opt = usvg::Options::default();
             let tree = usvg::Tree::from_data(&bytes, &opt).ok()?;
-            let size = tree.size().to_int_size();
-            let mut pixmap = Pixmap::new(size.width(), size.height())?;
-            resvg::render(&tree, tiny_skia::Transform::identity(), &mut pixmap.as_mut());
-            Some((pixmap.data().to_vec(), size.width(), size.height()))
+            let intrinsic = tree.size().to_int_size();
+            let (width, height, transform) = match size
+            {
+                Some(size) if intrinsic.width() > 0 && intrinsic.height() > 0 =>
+                {
+                    let scale = size as f32 / intrinsic.width().max(intrinsic.height()) as f32;
+                    (((intrinsic.width() as f32 * scale).round() as u32).max(1), ((intrinsic.height() as f32 * scale).round() as u32).max(1), tiny_skia::Transform::from_scale(scale, scale))
+                }
+                _ => (intrinsic.width(), intrinsic.height(), tiny_skia::Transform::identity()),
+            };
+            let mut pixmap = Pixmap::new(width, height)?;
+            resvg::render(&tree, transform, &mut pixmap.as_mut());
+            Some((pixmap.data().to_vec(), width, height))
         }
         _ =>
         {
@@ -187,7 +285,7 @@ pub fn load_icon_with_theme_path(name: &str, theme_path: Option<&str>) -> Option
         }
     }
 
-    if let Some(icon) = load_icon_from_theme(name)
+    if let Some(icon) = load_icon_from_theme(name, icon_target_size())
     {
         println!("Loaded icon from system/user theme: {name}");
         return Some(icon);
@@ -227,29 +325,227 @@ pub fn load_icon_with_theme_path(name: &str, theme_path: Option<&str>) -> Option
 
 
 
-pub fn load_icon_from_theme(name: &str) -> Option<(Vec<u8>, u32, u32)>
+/// Resolves `name` through the in-memory cache, then the on-disk cache, and only then through the icon themes.
+pub fn load_icon_cached(name: &str, theme_path: Option<&str>, size: u32) -> Option<(Vec<u8>, u32, u32)>
 {
-    let exts = ["png","svg","xpm"];
-    let mut roots = vec!
-    [
-        PathBuf::from("/usr/share/icons"),
-        PathBuf::from("/usr/local/share/icons"),
-        PathBuf::from("/usr/share/pixmaps"),
-        PathBuf::from("/run/host/usr/share/icons"),
-        PathBuf::from("/run/host/usr/share/pixmaps"),
-    ];
+    let theme = match theme_path
+    {
+        Some(theme_path) if !theme_path.is_empty() => theme_path.to_string(),
+        _ => current_icon_theme(),
+    };
+    let key = IconCacheKey { name: name.to_string(), theme, size };
+
+    if let Some(icon) = ICON_CACHE.lock().unwrap_or_else(|p| p.into_inner()).get(&key)
+    {
+        return Some(icon.clone());
+    }
+
+    let disk_path = icon_cache_dir().map(|dir| dir.join(icon_cache_file_name(&key)));
+    if let Some(path) = &disk_path && let Some(icon) = try_load_icon(path)
+    {
+        ICON_CACHE.lock().unwrap_or_else(|p| p.into_inner()).insert(key, icon.clone());
+        return Some(icon);
+    }
+
+    let icon = load_icon_with_theme_path(name, theme_path)?;
+    if let Some(path) = &disk_path
+    {
+        if let Some(parent) = path.parent() { let _ = fs::create_dir_all(parent); }
+        if let Err(e) = image::save_buffer(path, &icon.0, icon.1, icon.2, image::ExtendedColorType::Rgba8)
+        {
+            eprintln!("Failed to write icon cache {:?}: {e}", path);
+        }
+    }
+    ICON_CACHE.lock().unwrap_or_else(|p| p.into_inner()).insert(key, icon.clone());
+    Some(icon)
+}
+
+
 
+pub fn icon_cache_dir() -> Option<PathBuf>
+{
+    let cache_home = std::env::var_os("XDG_CACHE_HOME").map(PathBuf::from).filter(|p| p.is_absolute()).or_else(|| home::home_dir().map(|h| h.join(".cache")))?;
+    Some(cache_home.join("icebar").join("icons"))
+}
+
+
+
+pub fn icon_cache_file_name(key: &IconCacheKey) -> String
+{
+    let sanitize = |s: &str| -> String { s.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' }).collect() };
+    format!("{}@{}@{}.png", sanitize(&key.name), sanitize(&key.theme), key.size)
+}
+
+
+
+/// The icon theme configured for GTK, since that is what most tray apps expect; falls back to hicolor.
+pub fn current_icon_theme() -> String
+{
+    let Some(home) = home::home_dir() else { return "hicolor".to_string(); };
+    for settings in [home.join(".config/gtk-4.0/settings.ini"), home.join(".config/gtk-3.0/settings.ini")]
+    {
+        if let Ok(content) = fs::read_to_string(settings) && let Some(theme) = content.lines().find_map(|l| l.trim().strip_prefix("gtk-icon-theme-name")).and_then(|l| l.trim_start().strip_prefix('=')).map(|t| t.trim().trim_matches('"').to_string()) && !theme.is_empty()
+        {
+            return theme;
+        }
+    }
+    "hicolor".to_string()
+}
+
+
+
+pub fn icon_base_dirs() -> Vec<PathBuf>
+{
+    let mut dirs = Vec::new();
     if let Some(home) = home::home_dir()
     {
-        roots.push(home.join(".local/share/icons"));
+        dirs.push(home.join(".icons"));
+        let data_home = std::env::var_os("XDG_DATA_HOME").map(PathBuf::from).unwrap_or_else(|| home.join(".local/share"));
+        dirs.push(data_home.join("icons"));
+        dirs.push(home.join(".local/share/flatpak/exports/share/icons"));
+    }
+    let data_dirs = std::env::var("XDG_DATA_DIRS").ok().filter(|d| !d.is_empty()).unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    for dir in data_dirs.split(':').filter(|d| !d.is_empty())
+    {
+        dirs.push(PathBuf::from(dir).join("icons"));
+    }
+    dirs.push(PathBuf::from("/var/lib/flatpak/exports/share/icons"));
+    dirs.push(PathBuf::from("/run/host/usr/share/icons"));
+    dirs.dedup();
+    dirs
+}
+
+
+
+pub fn parse_index_theme(content: &str) -> IndexTheme
+{
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut current = String::new();
+    for line in content.lines().map(str::trim)
+    {
+        if line.is_empty() || line.starts_with('#') { continue; }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']'))
+        {
+            current = section.to_string();
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=')
+        {
+            sections.entry(current.clone()).or_default().insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    let list = |value: Option<&String>| -> Vec<String> { value.map(|v| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()).unwrap_or_default() };
+    let Some(header) = sections.get("Icon Theme") else { return IndexTheme::default(); };
+    let inherits = list(header.get("Inherits"));
+    let mut directory_names = list(header.get("Directories"));
+    directory_names.extend(list(header.get("ScaledDirectories")));
+
+    let directories = directory_names.into_iter().filter_map(|path|
+    {
+        let entry = sections.get(&path)?;
+        let number = |key: &str| entry.get(key).and_then(|v| v.parse::<u32>().ok());
+        let size = number("Size")?;
+        let kind = match entry.get("Type").map(String::as_str)
+        {
+            Some("Fixed") => ThemeDirectoryType::Fixed,
+            Some("Scalable") => ThemeDirectoryType::Scalable,
+            _ => ThemeDirectoryType::Threshold,
+        };
+        Some(ThemeDirectory { path, size, min_size: number("MinSize").unwrap_or(size), max_size: number("MaxSize").unwrap_or(size), threshold: number("Threshold").unwrap_or(2), kind })
+    }).collect();
+
+    IndexTheme { inherits, directories }
+}
+
+
+
+pub fn directory_size_distance(dir: &ThemeDirectory, size: u32) -> u32
+{
+    match dir.kind
+    {
+        ThemeDirectoryType::Fixed => dir.size.abs_diff(size),
+        ThemeDirectoryType::Scalable =>
+        {
+            if size < dir.min_size { dir.min_size - size } else if size > dir.max_size { size - dir.max_size } else { 0 }
+        }
+        ThemeDirectoryType::Threshold =>
+        {
+            let low = dir.size.saturating_sub(dir.threshold);
+            let high = dir.size + dir.threshold;
+            if size < low { low - size } else if size > high { size - high } else { 0 }
+        }
+    }
+}
+
+
+
+fn read_index_theme(theme: &str, base_dirs: &[PathBuf]) -> Option<IndexTheme>
+{
+    base_dirs.iter().find_map(|base| fs::read_to_string(base.join(theme).join("index.theme")).ok()).map(|content| parse_index_theme(&content))
+}
+
+
+
+fn lookup_icon_in_theme(name: &str, size: u32, theme: &str, index: &IndexTheme, base_dirs: &[PathBuf]) -> Option<PathBuf>
+{
+    let mut best: Option<(u32, PathBuf)> = None;
+    for dir in &index.directories
+    {
+        let distance = directory_size_distance(dir, size);
+        if best.as_ref().is_some_and(|(best_distance, _)| distance >= *best_distance) { continue; }
+        for base in base_dirs
+        {
+            if let Some(path) = ["png", "svg"].iter().map(|ext| base.join(theme).join(&dir.path).join(format!("{name}.{ext}"))).find(|p| p.is_file())
+            {
+                if distance == 0 { return Some(path); }
+                best = Some((distance, path));
+                break;
+            }
+        }
+    }
+    best.map(|(_, path)| path)
+}
+
+
+
+/// Freedesktop icon lookup: the theme, then everything it `Inherits=` (depth first), then hicolor.
+pub fn find_themed_icon(name: &str, size: u32, theme: &str, base_dirs: &[PathBuf]) -> Option<PathBuf>
+{
+    let mut visited = HashSet::new();
+    let mut pending = vec![theme.to_string()];
+    while let Some(current) = pending.pop()
+    {
+        if !visited.insert(current.clone()) { continue; }
+        let Some(index) = read_index_theme(&current, base_dirs) else { continue; };
+        if let Some(path) = lookup_icon_in_theme(name, size, &current, &index, base_dirs) { return Some(path); }
+        pending.extend(index.inherits.iter().rev().cloned());
+    }
+    if !visited.contains("hicolor") && let Some(index) = read_index_theme("hicolor", base_dirs)
+    {
+        return lookup_icon_in_theme(name, size, "hicolor", &index, base_dirs);
+    }
+    None
+}
+
+
+
+pub fn load_icon_from_theme(name: &str, size: u32) -> Option<(Vec<u8>, u32, u32)>
+{
+    let base_dirs = icon_base_dirs();
+    if let Some(path) = find_themed_icon(name, size, &current_icon_theme(), &base_dirs)
+    {
+        println!("Loaded icon from {:?}", path);
+        return try_load_icon_sized(&path, Some(size));
     }
 
-    for root in roots
+    // Unthemed icons live directly in the pixmaps directories
+    for root in [PathBuf::from("/usr/share/pixmaps"), PathBuf::from("/run/host/usr/share/pixmaps")]
     {
-        if root.exists() && let Some(path) = search_icon_recursive(&root, name, &exts)
+        if root.exists() && let Some(path) = search_icon_recursive(&root, name, &["png", "svg"])
         {
             println!("Loaded icon from {:?}", path);
-            return try_load_icon(&path);
+            return try_load_icon_sized(&path, Some(size));
         }
     }
 
@@ -258,6 +554,54 @@ pub fn load_icon_from_theme(name: &str) -> Option<(Vec<u8>, u32, u32)>
 
 
 
+/// Candidate icon names for a Wayland `app_id` / X11 class, e.g. `org.gnome.Nautilus` -> `org.gnome.nautilus`, `nautilus`.
+pub fn app_icon_candidates(app_id: &str) -> Vec<String>
+{
+    let lower = app_id.to_lowercase();
+    let last_segment = lower.rsplit('.').next().unwrap_or(&lower).to_string();
+    let mut candidates: Vec<String> = Vec::new();
+    for name in [app_id.to_string(), lower.clone(), last_segment]
+    {
+        if !name.is_empty() && !candidates.contains(&name) { candidates.push(name); }
+    }
+    candidates
+}
+
+
+
+/// Resolves an application icon from its `app_id` / class, trying the icon theme first and the `.desktop` files last.
+pub fn load_app_icon(app_id: &str, size: u32) -> Option<(Vec<u8>, u32, u32)>
+{
+    for name in app_icon_candidates(app_id)
+    {
+        if let Some(icon) = load_icon_from_theme(&name, size) { return Some(icon); }
+    }
+    load_icon_from_desktop(app_id)
+}
+
+
+
+/// Resolves the icons of the `app_ids` not looked up yet, in the background.
+/// They're marked as pending right away so a following refresh doesn't search them again.
+pub fn request_app_icons(app: &mut AppData, app_ids: impl IntoIterator<Item = String>, size: u32) -> iced::Task<Message>
+{
+    let mut missing: Vec<String> = Vec::new();
+    for app_id in app_ids
+    {
+        if app_id.is_empty() || app.modules_data.app_icons.contains_key(&app_id) { continue; }
+        app.modules_data.app_icons.insert(app_id.clone(), None);
+        missing.push(app_id);
+    }
+    if missing.is_empty() { return iced::Task::none(); }
+
+    iced::Task::perform(async move 
+    {
+        tokio::task::spawn_blocking(move || missing.into_iter().map(|app_id| { let icon = load_app_icon(&app_id, size); (app_id, icon) }).collect::<Vec<_>>()).await
+    }, |result| Message::AppIconsResolved(result.unwrap_or_default()))
+}
+
+
+
 pub fn search_icon_recursive(dir: &Path, name: &str, exts: &[&str]) -> Option<PathBuf>
 {
     if let Ok(entries) = fs::read_dir(dir)
@@ -376,4 +720,182 @@ mod tests
         );
         assert!(result.is_none());
     }
+
+    // ---- argb32_to_rgba -----------------------------------------------------
+
+    #[test]
+    fn argb32_to_rgba_moves_alpha_to_the_end()
+    {
+        // A=0x80 R=0x11 G=0x22 B=0x33
+        assert_eq!(argb32_to_rgba(&[0x80, 0x11, 0x22, 0x33]), vec![0x11, 0x22, 0x33, 0x80]);
+    }
+
+    #[test]
+    fn argb32_to_rgba_converts_every_pixel()
+    {
+        let data = [255, 1, 2, 3, 0, 4, 5, 6];
+        assert_eq!(argb32_to_rgba(&data), vec![1, 2, 3, 255, 4, 5, 6, 0]);
+    }
+
+    #[test]
+    fn argb32_to_rgba_drops_trailing_partial_pixel()
+    {
+        assert_eq!(argb32_to_rgba(&[255, 1, 2, 3, 9, 9]), vec![1, 2, 3, 255]);
+    }
+
+    // ---- pick_pixmap --------------------------------------------------------
+
+    fn pixmap(size: i32) -> (i32, i32, Vec<u8>) { (size, size, vec![0u8; (size * size * 4) as usize]) }
+
+    #[test]
+    fn pick_pixmap_prefers_closest_size_over_largest()
+    {
+        let picked = pick_pixmap(vec![pixmap(16), pixmap(22), pixmap(64)], 24).unwrap();
+        assert_eq!(picked.0, 22);
+    }
+
+    #[test]
+    fn pick_pixmap_prefers_bigger_on_tie()
+    {
+        let picked = pick_pixmap(vec![pixmap(16), pixmap(32)], 24).unwrap();
+        assert_eq!(picked.0, 32);
+    }
+
+    #[test]
+    fn pick_pixmap_skips_malformed_data()
+    {
+        let picked = pick_pixmap(vec![(24, 24, vec![0u8; 10]), pixmap(48)], 24).unwrap();
+        assert_eq!(picked.0, 48);
+    }
+
+    #[test]
+    fn pick_pixmap_empty_list_returns_none()
+    {
+        assert!(pick_pixmap(vec![], 24).is_none());
+    }
+
+    // ---- icon cache ---------------------------------------------------------
+
+    #[test]
+    fn icon_cache_file_name_includes_theme_and_size()
+    {
+        let key = IconCacheKey { name: "nm-applet".into(), theme: "Papirus".into(), size: 36 };
+        assert_eq!(icon_cache_file_name(&key), "nm-applet@Papirus@36.png");
+    }
+
+    #[test]
+    fn icon_cache_file_name_sanitizes_paths()
+    {
+        let key = IconCacheKey { name: "app".into(), theme: "/opt/app/icons".into(), size: 18 };
+        assert_eq!(icon_cache_file_name(&key), "app@_opt_app_icons@18.png");
+    }
+
+    // ---- parse_index_theme --------------------------------------------------
+
+    const INDEX_THEME: &str = "[Icon Theme]\nName=Test\nInherits=Parent,hicolor\nDirectories=16x16/apps,scalable/apps\n\n[16x16/apps]\nSize=16\nType=Fixed\n\n[scalable/apps]\nSize=48\nMinSize=8\nMaxSize=512\nType=Scalable\n";
+
+    #[test]
+    fn parse_index_theme_reads_inherits()
+    {
+        assert_eq!(parse_index_theme(INDEX_THEME).inherits, vec!["Parent".to_string(), "hicolor".to_string()]);
+    }
+
+    #[test]
+    fn parse_index_theme_reads_directories()
+    {
+        let index = parse_index_theme(INDEX_THEME);
+        assert_eq!(index.directories.len(), 2);
+        assert_eq!(index.directories[0].kind, ThemeDirectoryType::Fixed);
+        assert_eq!(index.directories[1].min_size, 8);
+        assert_eq!(index.directories[1].max_size, 512);
+    }
+
+    #[test]
+    fn parse_index_theme_without_header_is_empty()
+    {
+        assert_eq!(parse_index_theme("[16x16]\nSize=16\n"), IndexTheme::default());
+    }
+
+    #[test]
+    fn directory_size_distance_threshold_defaults_to_two()
+    {
+        let dir = parse_index_theme("[Icon Theme]\nDirectories=22\n[22]\nSize=22\n").directories.remove(0);
+        assert_eq!(directory_size_distance(&dir, 24), 0);
+        assert_eq!(directory_size_distance(&dir, 30), 6);
+    }
+
+    // ---- find_themed_icon ---------------------------------------------------
+
+    fn write_theme(base: &Path, theme: &str, index: &str, icons: &[(&str, &str)])
+    {
+        fs::create_dir_all(base.join(theme)).unwrap();
+        fs::write(base.join(theme).join("index.theme"), index).unwrap();
+        for (dir, name) in icons
+        {
+            fs::create_dir_all(base.join(theme).join(dir)).unwrap();
+            fs::write(base.join(theme).join(dir).join(name), b"fake").unwrap();
+        }
+    }
+
+    #[test]
+    fn find_themed_icon_follows_inherits()
+    {
+        let base = tempfile::tempdir().unwrap();
+        write_theme(base.path(), "Child", "[Icon Theme]\nInherits=Parent\nDirectories=24\n[24]\nSize=24\n", &[]);
+        write_theme(base.path(), "Parent", "[Icon Theme]\nDirectories=24\n[24]\nSize=24\n", &[("24", "app.png")]);
+        let found = find_themed_icon("app", 24, "Child", &[base.path().to_path_buf()]).unwrap();
+        assert!(found.starts_with(base.path().join("Parent")));
+    }
+
+    #[test]
+    fn find_themed_icon_falls_back_to_hicolor()
+    {
+        let base = tempfile::tempdir().unwrap();
+        write_theme(base.path(), "Child", "[Icon Theme]\nDirectories=24\n[24]\nSize=24\n", &[]);
+        write_theme(base.path(), "hicolor", "[Icon Theme]\nDirectories=48\n[48]\nSize=48\n", &[("48", "app.svg")]);
+        let found = find_themed_icon("app", 24, "Child", &[base.path().to_path_buf()]).unwrap();
+        assert!(found.starts_with(base.path().join("hicolor")));
+    }
+
+    #[test]
+    fn find_themed_icon_prefers_closest_directory()
+    {
+        let base = tempfile::tempdir().unwrap();
+        write_theme(base.path(), "T", "[Icon Theme]\nDirectories=16,32,64\n[16]\nSize=16\nType=Fixed\n[32]\nSize=32\nType=Fixed\n[64]\nSize=64\nType=Fixed\n", &[("16", "app.png"), ("32", "app.png"), ("64", "app.png")]);
+        let found = find_themed_icon("app", 36, "T", &[base.path().to_path_buf()]).unwrap();
+        assert_eq!(found, base.path().join("T").join("32").join("app.png"));
+    }
+
+    #[test]
+    fn find_themed_icon_survives_inheritance_cycles()
+    {
+        let base = tempfile::tempdir().unwrap();
+        write_theme(base.path(), "A", "[Icon Theme]\nInherits=B\nDirectories=24\n[24]\nSize=24\n", &[]);
+        write_theme(base.path(), "B", "[Icon Theme]\nInherits=A\nDirectories=24\n[24]\nSize=24\n", &[]);
+        assert!(find_themed_icon("ghost", 24, "A", &[base.path().to_path_buf()]).is_none());
+    }
+
+    // ---- app icons ------------------------------------------------------------
+
+    #[test]
+    fn app_icon_candidates_try_lowercase_and_last_segment()
+    {
+        assert_eq!(app_icon_candidates("org.gnome.Nautilus"), vec!["org.gnome.Nautilus", "org.gnome.nautilus", "nautilus"]);
+    }
+
+    #[test]
+    fn app_icon_candidates_are_deduplicated()
+    {
+        assert_eq!(app_icon_candidates("firefox"), vec!["firefox"]);
+        assert!(app_icon_candidates("").is_empty());
+    }
+
+    #[test]
+    fn request_app_icons_marks_pending_once()
+    {
+        let mut app = AppData { ..Default::default() };
+        let _ = request_app_icons(&mut app, vec!["foot".to_string(), "foot".to_string(), String::new()], 16);
+        assert_eq!(app.modules_data.app_icons.len(), 1);
+        assert!(app.modules_data.app_icons.get("foot").is_some_and(|icon| icon.is_none()));
+    }
 }
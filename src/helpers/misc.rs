@@ -1,6 +1,6 @@
 // ============ IMPORTS ============
 use iced_layershell::reexport::Anchor;
-use iced::widget::{button, container, mouse_area};
+use iced::widget::{button, column, container, mouse_area, row};
 use iced::{Alignment, Element, Theme};
 use std::collections::HashSet;
 
@@ -12,6 +12,7 @@ use std::collections::HashSet;
 use crate::ron::{BarPosition};
 use crate::modules::data::Modules;
 use crate::update::Message;
+use crate::view::Axis;
 use crate::AppData;
 
 
@@ -185,18 +186,31 @@ pub fn validate_bar_data(app: &mut AppData) -> ValidatedBarSizeAndMargin
 pub fn create_button_container_without_hover_message<'a, F>(app: &'a AppData, padding: u16, text_data: (iced::widget::text::Rich<'a, (), Message>, u32), left_click_message: Message, right_click_message: Message, style_func: F) -> Element<'a, Message>
 where F: Fn(&AppData, button::Status) -> button::Style + 'a,
 {
+    create_icon_button_container_without_hover_message(app, padding, None, text_data, left_click_message, right_click_message, style_func)
+}
+
+
+
+/// Same button, with an optional icon laid out before the text along the bar: (icon, spacing, axis).
+pub fn create_icon_button_container_without_hover_message<'a, F>(app: &'a AppData, padding: u16, icon: Option<(Element<'a, Message>, u32, Axis)>, text_data: (iced::widget::text::Rich<'a, (), Message>, u32), left_click_message: Message, right_click_message: Message, style_func: F) -> Element<'a, Message>
+where F: Fn(&AppData, button::Status) -> button::Style + 'a,
+{
+    let label = text_data.0
+        .wrapping(iced::widget::text::Wrapping::Word)
+        .font(app.default_font)
+        .size(text_data.1)
+        .center();
+    let content: Element<'a, Message> = match icon
+    {
+        Some((icon, spacing, Axis::Horizontal)) => row![icon, label].spacing(spacing).align_y(Alignment::Center).into(),
+        Some((icon, spacing, Axis::Vertical)) => column![icon, label].spacing(spacing).align_x(Alignment::Center).into(),
+        None => label.into(),
+    };
     container
     (
         button
         (
-            mouse_area
-            (
-                text_data.0
-                .wrapping(iced::widget::text::Wrapping::Word)
-                .font(app.default_font)
-                .size(text_data.1)
-                .center()
-            )
+            mouse_area(content)
             .on_right_press(right_click_message)
         )
         .on_press(left_click_message)
@@ -210,39 +224,6 @@ where F: Fn(&AppData, button::Status) -> button::Style + 'a,
 
 
 
-pub fn create_button_container<'a, F>(app: &'a AppData, padding: u16, text_data: (iced::widget::text::Rich<'a, (), Message>, u32), hover_message: (Message, Message), left_click_message: Message, right_click_message: Message, style_func: F) -> Element<'a, Message>
-where F: Fn(&AppData, button::Status) -> button::Style + 'a,
-{
-    container
-    (
-        mouse_area
-        (
-            button
-            (
-                mouse_area
-                (
-                    text_data.0
-                    .wrapping(iced::widget::text::Wrapping::Word)
-                    .font(app.default_font)
-                    .size(text_data.1)
-                    .center()
-                )
-                .on_right_press(right_click_message)
-            )
-            .on_press(left_click_message)
-            .style(move |_: &Theme, status: button::Status| 
-            {
-                style_func(app, status)
-            })
-        )
-        .on_enter(hover_message.0)
-        .on_exit(hover_message.1)
-    ).align_x(Alignment::Center).align_y(Alignment::Center).padding(padding)
-    .into()
-}
-
-
-
 
 
 // ============ TESTS ============
@@ -1,4 +1,7 @@
 pub mod config_watcher;
+pub mod command;
+pub mod bindings;
+pub mod condition;
 pub mod ron_general;
 pub mod workspaces;
 pub mod monitor;
@@ -41,3 +41,16 @@ pub fn get_monitor_res(option_display: Option<String>) -> (u32, u32)
 
     (1920, 1080)
 }
+
+
+
+pub fn get_monitor_scale(option_display: Option<String>) -> f32
+{
+    let Ok(display_infos) = DisplayInfo::all() else { return 1.0; };
+    let display_info = match option_display
+    {
+        Some(display) => display_infos.iter().find(|d| d.name == display).or(display_infos.first()),
+        None => display_infos.first(),
+    };
+    display_info.map(|d| d.scale_factor).filter(|scale| *scale > 0.0).unwrap_or(1.0)
+}
@@ -2,11 +2,13 @@
 use iced::{Alignment, Border, Color, Element, Theme, Vector, border::Radius, theme::Style, widget::{Space, button, column, container, row}};
 use iced_layershell::reexport::core::{Degrees, gradient::Linear};
 use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
 
 
 
 // ============ CRATES ============
 use crate::helpers::color::{ColorType, Gradient};
+use crate::modules::data::Modules;
 use crate::AppData;
 
 
@@ -42,6 +44,51 @@ pub struct UserStyle
     pub shadow_blur: f32,
 }
 
+/// A threshold tier of a numeric module (e.g: "warning" from 70), unset fields keep the module's own value.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StateStyle
+{
+    pub threshold: f32,
+    pub format: Option<String>,
+    pub text_color: Option<ColorType>,
+    pub button_color: Option<ColorType>,
+    pub button_hovered_color: Option<ColorType>,
+    pub button_hovered_text_color: Option<ColorType>,
+    pub button_pressed_text_color: Option<ColorType>,
+    pub button_pressed_color: Option<ColorType>,
+    pub border_color: Option<ColorType>,
+    pub button_gradient_color: Option<Gradient>,
+    pub button_hovered_gradient_color: Option<Gradient>,
+    pub button_pressed_gradient_color: Option<Gradient>,
+    pub blink: bool,
+    pub blink_interval: u64,
+}
+
+impl Default for StateStyle
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            threshold: 0.,
+            format: None,
+            text_color: None,
+            button_color: None,
+            button_hovered_color: None,
+            button_hovered_text_color: None,
+            button_pressed_text_color: None,
+            button_pressed_color: None,
+            border_color: None,
+            button_gradient_color: None,
+            button_hovered_gradient_color: None,
+            button_pressed_gradient_color: None,
+            blink: false,
+            blink_interval: 500,
+        }
+    }
+}
+
 #[derive(Default, Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub enum SideOption 
 {
@@ -130,6 +177,72 @@ pub fn set_style(user_style: UserStyle) -> iced::widget::button::Style
 
 
 
+/// The tier with the highest threshold `value` has reached, equal thresholds resolve by name so the pick is stable.
+pub fn active_state(states: &HashMap<String, StateStyle>, value: f32) -> Option<&StateStyle>
+{
+    states.iter()
+        .filter(|(_, state)| value >= state.threshold)
+        .max_by(|(a_name, a), (b_name, b)| a.threshold.total_cmp(&b.threshold).then_with(|| b_name.cmp(a_name)))
+        .map(|(_, state)| state)
+}
+
+
+
+/// Active tier of a threshold module, muted volume keeps its own muted style.
+pub fn module_state<'a>(app: &'a AppData, module: &Modules) -> Option<&'a StateStyle>
+{
+    let config = &app.ron_config;
+    let data = &app.modules_data;
+    match module
+    {
+        Modules::Cpu => active_state(&config.cpu.cpu_states, data.cpu_data.usage_percent),
+        Modules::Ram => active_state(&config.ram.ram_states, data.ram_data.percent),
+        Modules::Disk => active_state(&config.disk.disk_states, data.disk_data.percent as f32),
+        Modules::CpuTemp => active_state(&config.cpu_temp.cpu_temp_states, data.cpu_temp_data.temp_celsius),
+        Modules::VolumeOutput if !data.volume_data.volume_output_is_muted => active_state(&config.volume_output.volume_output_states, data.volume_data.volume_output_raw * 100.),
+        Modules::VolumeInput if !data.volume_data.volume_input_is_muted => active_state(&config.volume_input.volume_input_states, data.volume_data.volume_input_raw * 100.),
+        _ => None,
+    }
+}
+
+
+
+/// Blink interval of the fastest blinking tier currently active, `None` when nothing blinks.
+pub fn state_blink_interval(app: &AppData) -> Option<u64>
+{
+    app.modules_data.active_modules.iter().filter_map(|module| module_state(app, module)).filter(|state| state.blink).map(|state| state.blink_interval.max(50)).min()
+}
+
+
+
+/// The tier's format when it has one, otherwise the module's.
+pub fn state_format<'a>(format: &'a str, state: Option<&'a StateStyle>) -> &'a str
+{
+    state.and_then(|state| state.format.as_deref()).unwrap_or(format)
+}
+
+
+
+/// Applies the tier on top of the module style, a blinking tier drops back to the module style every other tick.
+pub fn apply_state_style(mut user_style: UserStyle, state: Option<&StateStyle>, blink_off: bool) -> UserStyle
+{
+    let Some(state) = state else { return user_style; };
+    if state.blink && blink_off { return user_style; }
+    if let Some(color) = state.text_color { user_style.normal_text = color; }
+    if let Some(color) = state.button_color { user_style.normal = color; }
+    if let Some(color) = state.button_hovered_color { user_style.hovered = color; }
+    if let Some(color) = state.button_hovered_text_color { user_style.hovered_text = color; }
+    if let Some(color) = state.button_pressed_text_color { user_style.pressed_text = color; }
+    if let Some(color) = state.button_pressed_color { user_style.pressed = color; }
+    if let Some(color) = state.border_color { user_style.border_color = color; }
+    if let Some(gradient) = &state.button_gradient_color { user_style.normal_gradient = Some(gradient.clone()); }
+    if let Some(gradient) = &state.button_hovered_gradient_color { user_style.hovered_gradient = Some(gradient.clone()); }
+    if let Some(gradient) = &state.button_pressed_gradient_color { user_style.pressed_gradient = Some(gradient.clone()); }
+    user_style
+}
+
+
+
 pub fn orient_text(input: &str, orientation: &TextOrientation) -> String 
 {
     match orientation 
@@ -496,4 +609,85 @@ mod tests
         assert_eq!(orient_text("", &TextOrientation::Horizontal), "");
         assert_eq!(orient_text("", &TextOrientation::Vertical),   "");
     }
+
+    // ---- state styles ------------------------------------------------------
+
+    fn tiers() -> HashMap<String, StateStyle>
+    {
+        HashMap::from(
+        [
+            ("warning".to_string(), StateStyle { threshold: 70., format: Some("W {usage}%".into()), button_color: Some(ColorType::RGB([200, 120, 0])), ..Default::default() }),
+            ("critical".to_string(), StateStyle { threshold: 90., button_color: Some(ColorType::RGB([200, 0, 0])), blink: true, ..Default::default() }),
+        ])
+    }
+
+    #[test]
+    fn active_state_picks_highest_reached_threshold()
+    {
+        let states = tiers();
+        assert!(active_state(&states, 69.9).is_none());
+        assert_eq!(active_state(&states, 70.).unwrap().threshold, 70.);
+        assert_eq!(active_state(&states, 95.).unwrap().threshold, 90.);
+        assert!(active_state(&HashMap::new(), 100.).is_none());
+    }
+
+    #[test]
+    fn state_format_falls_back_to_module_format()
+    {
+        let states = tiers();
+        assert_eq!(state_format("CPU {usage}%", active_state(&states, 75.)), "W {usage}%");
+        assert_eq!(state_format("CPU {usage}%", active_state(&states, 95.)), "CPU {usage}%");
+        assert_eq!(state_format("CPU {usage}%", None), "CPU {usage}%");
+    }
+
+    #[test]
+    fn apply_state_style_overrides_only_set_fields()
+    {
+        let states = tiers();
+        let styled = apply_state_style(base_user_style(button::Status::Active), active_state(&states, 75.), false);
+        let base = base_user_style(button::Status::Active);
+        assert_eq!(styled.normal, ColorType::RGB([200, 120, 0]));
+        assert_eq!(styled.normal_text, base.normal_text);
+        assert_eq!(styled.border_color, base.border_color);
+    }
+
+    #[test]
+    fn blinking_state_alternates_with_module_style()
+    {
+        let states = tiers();
+        let base = base_user_style(button::Status::Active);
+        assert_eq!(apply_state_style(base_user_style(button::Status::Active), active_state(&states, 95.), false).normal, ColorType::RGB([200, 0, 0]));
+        assert_eq!(apply_state_style(base_user_style(button::Status::Active), active_state(&states, 95.), true).normal, base.normal);
+        // Non-blinking tiers ignore the blink phase
+        assert_eq!(apply_state_style(base_user_style(button::Status::Active), active_state(&states, 75.), true).normal, ColorType::RGB([200, 120, 0]));
+    }
+
+    #[test]
+    fn module_state_reads_module_values()
+    {
+        let mut app = AppData::default();
+        app.ron_config.cpu.cpu_states = tiers();
+        app.ron_config.volume_output.volume_output_states = tiers();
+        app.modules_data.cpu_data.usage_percent = 92.;
+        app.modules_data.volume_data.volume_output_raw = 0.8;
+        assert_eq!(module_state(&app, &Modules::Cpu).unwrap().threshold, 90.);
+        assert_eq!(module_state(&app, &Modules::VolumeOutput).unwrap().threshold, 70.);
+        app.modules_data.volume_data.volume_output_is_muted = true;
+        assert!(module_state(&app, &Modules::VolumeOutput).is_none());
+        assert!(module_state(&app, &Modules::Ram).is_none());
+    }
+
+    #[test]
+    fn state_blink_interval_needs_an_active_blinking_tier()
+    {
+        let mut app = AppData::default();
+        app.ron_config.cpu.cpu_states = tiers();
+        app.modules_data.active_modules.insert(Modules::Cpu);
+        app.modules_data.cpu_data.usage_percent = 75.;
+        assert_eq!(state_blink_interval(&app), None);
+        app.modules_data.cpu_data.usage_percent = 95.;
+        assert_eq!(state_blink_interval(&app), Some(500));
+        app.modules_data.active_modules.clear();
+        assert_eq!(state_blink_interval(&app), None);
+    }
 }
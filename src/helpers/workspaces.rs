@@ -1,3 +1,10 @@
+// ============ CRATES ============
+use crate::modules::workspaces::{WorkspaceInfo, WorkspaceOutputMode};
+
+
+
+
+
 // ============ FUNCTIONS ============
 pub fn build_workspace_list(real: &[i32], persistent: Option<u8>) -> Vec<i32> 
 {
@@ -21,6 +28,73 @@ pub fn build_workspace_list(real: &[i32], persistent: Option<u8>) -> Vec<i32>
 
 
 
+/// Puts the real workspaces in display order and adds placeholders for the missing persistent ones.
+/// Numbered workspaces come first, then the ones without a number (named), then the special ones.
+pub fn merge_persistent_workspaces(real: &[WorkspaceInfo], persistent: Option<u8>) -> Vec<WorkspaceInfo>
+{
+    let by_key = |a: &WorkspaceInfo, b: &WorkspaceInfo| a.id.cmp(&b.id);
+    let numbers: Vec<i32> = real.iter().filter(|workspace| !workspace.is_special && workspace.number > 0).map(|workspace| workspace.number).collect();
+    let mut result: Vec<WorkspaceInfo> = Vec::new();
+    for number in build_workspace_list(&numbers, persistent)
+    {
+        let mut matching: Vec<WorkspaceInfo> = real.iter().filter(|workspace| !workspace.is_special && workspace.number == number).cloned().collect();
+        if matching.is_empty() { matching.push(WorkspaceInfo::placeholder(number)); }
+        matching.sort_by(by_key);
+        result.extend(matching);
+    }
+    let mut named: Vec<WorkspaceInfo> = real.iter().filter(|workspace| !workspace.is_special && workspace.number <= 0).cloned().collect();
+    named.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut special: Vec<WorkspaceInfo> = real.iter().filter(|workspace| workspace.is_special).cloned().collect();
+    special.sort_by(by_key);
+    result.extend(named);
+    result.extend(special);
+    result
+}
+
+
+
+/// Splits the workspaces into the groups the workspace module draws (their ids), one group unless workspaces are grouped by output.
+/// Without a configured `display` the bar's output is unknown, so `CurrentOutput` falls back to every workspace.
+pub fn build_output_workspace_groups(real: &[WorkspaceInfo], persistent: Option<u8>, mode: &WorkspaceOutputMode, bar_output: Option<&str>) -> Vec<Vec<i32>>
+{
+    let ids = |workspaces: Vec<WorkspaceInfo>| -> Vec<i32> { workspaces.iter().map(|workspace| workspace.id).collect() };
+    match (mode, bar_output)
+    {
+        (WorkspaceOutputMode::CurrentOutput, Some(bar_output)) =>
+        {
+            let on_output: Vec<WorkspaceInfo> = real.iter().filter(|workspace| workspace.output.as_deref().is_none_or(|output| output == bar_output)).cloned().collect();
+            vec![ids(merge_persistent_workspaces(&on_output, persistent))]
+        }
+        (WorkspaceOutputMode::GroupedByOutput, _) =>
+        {
+            let mut groups: Vec<(Option<String>, Vec<WorkspaceInfo>)> = Vec::new();
+            for workspace in real
+            {
+                match groups.iter_mut().find(|(output, _)| *output == workspace.output)
+                {
+                    Some((_, workspaces)) => workspaces.push(workspace.clone()),
+                    None => groups.push((workspace.output.clone(), vec![workspace.clone()])),
+                }
+            }
+            if groups.is_empty() { return vec![ids(merge_persistent_workspaces(&[], persistent))]; }
+
+            // Persistent workspaces that don't exist yet go to the bar's own output, or the first one
+            if let Some(max) = persistent
+            {
+                let missing = (1..=max as i32).filter(|number| !real.iter().any(|workspace| !workspace.is_special && workspace.number == *number)).map(WorkspaceInfo::placeholder);
+                let target = groups.iter().position(|(output, _)| output.as_deref() == bar_output).unwrap_or(0);
+                groups[target].1.extend(missing);
+            }
+            let mut groups: Vec<Vec<WorkspaceInfo>> = groups.into_iter().map(|(_, workspaces)| merge_persistent_workspaces(&workspaces, None)).collect();
+            groups.sort_by_key(|workspaces| workspaces.first().map_or((true, i32::MAX), |workspace| (workspace.is_special || workspace.number <= 0, workspace.number)));
+            groups.into_iter().map(ids).collect()
+        }
+        _ => vec![ids(merge_persistent_workspaces(real, persistent))],
+    }
+}
+
+
+
 
 
 // ============ TESTS ============
@@ -124,4 +198,79 @@ mod tests
         let result = build_workspace_list(&[11, 12], Some(10));
         assert_eq!(result, vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
     }
+
+    // ---- build_output_workspace_groups ---------------------------------------
+
+    fn on(id: i32, output: &str) -> WorkspaceInfo { WorkspaceInfo { output: Some(output.to_string()), ..WorkspaceInfo::placeholder(id) } }
+
+    #[test]
+    fn all_mode_flattens_every_output()
+    {
+        let real = [on(3, "DP-1"), on(1, "HDMI-A-1"), on(2, "DP-1")];
+        assert_eq!(build_output_workspace_groups(&real, None, &WorkspaceOutputMode::All, Some("DP-1")), vec![vec![1, 2, 3]]);
+    }
+
+    #[test]
+    fn current_output_mode_keeps_bar_output_only()
+    {
+        let real = [on(3, "DP-1"), on(1, "HDMI-A-1"), on(2, "DP-1")];
+        assert_eq!(build_output_workspace_groups(&real, None, &WorkspaceOutputMode::CurrentOutput, Some("DP-1")), vec![vec![2, 3]]);
+    }
+
+    #[test]
+    fn current_output_mode_without_display_shows_everything()
+    {
+        let real = [on(2, "DP-1"), on(1, "HDMI-A-1")];
+        assert_eq!(build_output_workspace_groups(&real, None, &WorkspaceOutputMode::CurrentOutput, None), vec![vec![1, 2]]);
+    }
+
+    #[test]
+    fn grouped_mode_orders_groups_by_first_workspace()
+    {
+        let real = [on(4, "DP-1"), on(1, "HDMI-A-1"), on(3, "DP-1"), on(2, "HDMI-A-1")];
+        assert_eq!(build_output_workspace_groups(&real, None, &WorkspaceOutputMode::GroupedByOutput, None), vec![vec![1, 2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn grouped_mode_adds_missing_persistent_to_bar_output()
+    {
+        let real = [on(1, "DP-1"), on(4, "HDMI-A-1")];
+        assert_eq!(build_output_workspace_groups(&real, Some(3), &WorkspaceOutputMode::GroupedByOutput, Some("HDMI-A-1")), vec![vec![1], vec![2, 3, 4]]);
+    }
+
+    #[test]
+    fn grouped_mode_with_no_workspaces_still_shows_persistent()
+    {
+        assert_eq!(build_output_workspace_groups(&[], Some(2), &WorkspaceOutputMode::GroupedByOutput, None), vec![vec![1, 2]]);
+    }
+
+    // ---- merge_persistent_workspaces ------------------------------------------
+
+    #[test]
+    fn merge_orders_numbered_then_named_then_special()
+    {
+        let special = WorkspaceInfo { id: -98, number: -98, name: "magic".into(), is_special: true, ..Default::default() };
+        let named = WorkspaceInfo { id: -1337, number: -1, name: "web".into(), ..Default::default() };
+        let merged = merge_persistent_workspaces(&[special, named, WorkspaceInfo::placeholder(3)], Some(2));
+        assert_eq!(merged.iter().map(|w| w.id).collect::<Vec<_>>(), vec![1, 2, 3, -1337, -98]);
+    }
+
+    #[test]
+    fn merge_keeps_same_number_on_different_outputs()
+    {
+        // niri: idx 1 exists on both outputs, ids are the compositor's own
+        let a = WorkspaceInfo { id: 7, number: 1, output: Some("DP-1".into()), ..Default::default() };
+        let b = WorkspaceInfo { id: 4, number: 1, output: Some("HDMI-A-1".into()), ..Default::default() };
+        let merged = merge_persistent_workspaces(&[a, b], None);
+        assert_eq!(merged.iter().map(|w| w.id).collect::<Vec<_>>(), vec![4, 7]);
+    }
+
+    #[test]
+    fn merge_keeps_real_workspace_data_over_placeholder()
+    {
+        let real = WorkspaceInfo { id: 2, number: 2, name: "2:code".into(), windows: 3, ..Default::default() };
+        let merged = merge_persistent_workspaces(std::slice::from_ref(&real), Some(2));
+        assert_eq!(merged[1], real);
+        assert_eq!(merged[0], WorkspaceInfo::placeholder(1));
+    }
 }
@@ -8,8 +8,8 @@ use iced::Font;
 
 
 // ============ CRATES ============
-use crate::helpers::{font::build_font, fs::check_if_config_file_exists, misc::{define_bar_anchor_position, is_active_module, validate_bar_data}, monitor::get_monitor_res, string::{intern_string, weight_from_str}, style::{UserStyle, set_style, style} };
-use crate::modules::{custom_modules::CustomModuleData, network::NetworkData, clock::ClockData, image::{ImageData, preload_image}, data::{Modules, ModulesData}, tray::{self, TrayEvent, start_tray}};
+use crate::helpers::{font::build_font, fs::check_if_config_file_exists, icons::set_icon_target_size, misc::{define_bar_anchor_position, is_active_module, validate_bar_data}, monitor::{get_monitor_res, get_monitor_scale}, string::{intern_string, weight_from_str}, style::{UserStyle, set_style, style} };
+use crate::modules::{custom_modules::CustomModuleData, network::NetworkData, clock::ClockData, image::{ImageData, configured_image_paths, preload_image}, data::{Modules, ModulesData}, tray::{self, TrayEvent, start_tray}};
 use crate::ron::{read_ron_config, BarConfig};
 use crate::context_menu::ContextMenuData;
 use crate::subscription::subscription;
@@ -47,7 +47,9 @@ pub enum WindowInfo
 {
     MainBar,
     Warning,
-    ContextMenu
+    ContextMenu,
+    TrayDrawer,
+    Tooltip
 }
 
 #[derive(Default, Clone)]
@@ -77,6 +79,7 @@ pub async fn main() -> Result<(), iced_layershell::Error>
     let preloaded_images = preload_image(&mut warning_err, &mut config_parsed_failed, &ron_config.image.images);
     let anchor_position = define_bar_anchor_position(&ron_config.general.bar_position);
     let monitor_res = get_monitor_res(ron_config.general.display.clone());
+    set_icon_target_size((ron_config.tray.tray_icon_size as f32 * get_monitor_scale(ron_config.general.display.clone())).round() as u32);
     if is_active_module(&active_modules, Modules::Tray) { start_tray(); }
     let ron_config_clone = ron_config.clone();
     let font_name = ron_config.general.font_family;
@@ -102,7 +105,9 @@ pub async fn main() -> Result<(), iced_layershell::Error>
         },
         image_data: ImageData
         {
-            preloaded_images_handle: preloaded_images
+            preloaded_images_handle: preloaded_images,
+            image_paths: configured_image_paths(&ron_config.image.images),
+            ..Default::default()
         },
         ..Default::default()
     };
@@ -0,0 +1,190 @@
+// ============ IMPORTS ============
+use iced::widget::button;
+
+
+
+
+
+// ============ CRATES ============
+use crate::helpers::style::{UserStyle, orient_text, set_style};
+use crate::AppData;
+
+
+
+
+
+
+
+// ============ CONFIG ============
+use serde::{Deserialize, Serialize};
+use crate::helpers::style::{TextOrientation, SideOption};
+use crate::helpers::color::{ColorType, Gradient};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BindingModeConfig
+{
+    pub binding_mode_format:                        String,
+    pub binding_mode_hidden_modes:                  Vec<String>,
+    pub binding_mode_text_limit_len:                usize,
+    pub binding_mode_padding:                       u16,
+    pub binding_mode_text_size:                     u32,
+    pub binding_mode_text_color:                    ColorType,
+    pub binding_mode_text_orientation:              TextOrientation,
+    pub binding_mode_button_color:                  ColorType,
+    pub binding_mode_button_hovered_color:          ColorType,
+    pub binding_mode_button_hovered_text_color:     ColorType,
+    pub binding_mode_button_pressed_text_color:     ColorType,
+    pub binding_mode_button_pressed_color:          ColorType,
+    pub binding_mode_border_color:                  ColorType,
+    pub binding_mode_border_size:                   f32,
+    pub binding_mode_border_radius:                 [f32; 4],
+    pub binding_mode_side_separator:                Option<SideOption>,
+    pub binding_mode_side_separator_color:          ColorType,
+    pub binding_mode_side_separator_width:          f32,
+    pub binding_mode_side_separator_height:         f32,
+    pub binding_mode_button_gradient_color:         Option<Gradient>,
+    pub binding_mode_button_hovered_gradient_color: Option<Gradient>,
+    pub binding_mode_button_pressed_gradient_color: Option<Gradient>,
+    pub binding_mode_button_shadow_color:           Option<ColorType>,
+    pub binding_mode_button_shadow_x:               f32,
+    pub binding_mode_button_shadow_y:               f32,
+    pub binding_mode_button_shadow_blur:            f32,
+}
+
+impl Default for BindingModeConfig
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            binding_mode_format:                        "{mode}".into(),
+            binding_mode_hidden_modes:                  vec!["default".into()],
+            binding_mode_text_limit_len:                20,
+            binding_mode_padding:                       7,
+            binding_mode_text_size:                     13,
+            binding_mode_text_color:                    ColorType::RGB([28, 28, 28]),
+            binding_mode_text_orientation:              TextOrientation::Horizontal,
+            binding_mode_button_color:                  ColorType::RGB([229, 165, 10]),
+            binding_mode_button_hovered_color:          ColorType::RGB([246, 211, 45]),
+            binding_mode_button_hovered_text_color:     ColorType::RGB([28, 28, 28]),
+            binding_mode_button_pressed_text_color:     ColorType::RGB([28, 28, 28]),
+            binding_mode_button_pressed_color:          ColorType::RGB([200, 136, 0]),
+            binding_mode_border_color:                  ColorType::RGB([42, 42, 42]),
+            binding_mode_border_size:                   1.0,
+            binding_mode_border_radius:                 [6.0, 6.0, 6.0, 6.0],
+            binding_mode_side_separator:                None,
+            binding_mode_side_separator_color:          ColorType::RGB([61, 61, 61]),
+            binding_mode_side_separator_width:          1.,
+            binding_mode_side_separator_height:         18.,
+            binding_mode_button_gradient_color:         None,
+            binding_mode_button_hovered_gradient_color: None,
+            binding_mode_button_pressed_gradient_color: None,
+            binding_mode_button_shadow_color:           None,
+            binding_mode_button_shadow_x:               0.0,
+            binding_mode_button_shadow_y:               0.0,
+            binding_mode_button_shadow_blur:            0.0,
+        }
+    }
+}
+
+// ============ STRUCTS ============
+#[derive(Default, Clone)]
+pub struct BindingModeData
+{
+    /// Hyprland submap, Sway or River mode. Empty when Hyprland resets to the global keymap.
+    pub mode: String,
+}
+
+
+
+
+
+// ============ FUNCTIONS ============
+pub fn is_default_binding_mode(mode: &str, hidden_modes: &[String]) -> bool
+{
+    mode.is_empty() || hidden_modes.iter().any(|hidden| hidden == mode)
+}
+
+
+
+/// Empty while in the default mode, so the module is hidden.
+pub fn define_binding_mode_text(app: &AppData) -> String
+{
+    let mode = &app.modules_data.binding_mode_data.mode;
+    if is_default_binding_mode(mode, &app.ron_config.binding_mode.binding_mode_hidden_modes) { return String::new(); }
+    let text = app.ron_config.binding_mode.binding_mode_format.replace("{mode}", mode);
+    orient_text(&text, &app.ron_config.binding_mode.binding_mode_text_orientation)
+}
+
+
+
+pub fn define_binding_mode_style(app: &AppData, status: button::Status) -> iced::widget::button::Style
+{
+    set_style(UserStyle
+    {
+        status,
+        normal:            app.ron_config.binding_mode.binding_mode_button_color,
+        normal_text:       app.ron_config.binding_mode.binding_mode_text_color,
+        hovered:           app.ron_config.binding_mode.binding_mode_button_hovered_color,
+        hovered_text:      app.ron_config.binding_mode.binding_mode_button_hovered_text_color,
+        pressed_text:      app.ron_config.binding_mode.binding_mode_button_pressed_text_color,
+        pressed:           app.ron_config.binding_mode.binding_mode_button_pressed_color,
+        border_color:      app.ron_config.binding_mode.binding_mode_border_color,
+        border_size:       app.ron_config.binding_mode.binding_mode_border_size,
+        border_radius:     app.ron_config.binding_mode.binding_mode_border_radius,
+        normal_gradient:   app.ron_config.binding_mode.binding_mode_button_gradient_color.clone(),
+        hovered_gradient:  app.ron_config.binding_mode.binding_mode_button_hovered_gradient_color.clone(),
+        pressed_gradient:  app.ron_config.binding_mode.binding_mode_button_pressed_gradient_color.clone(),
+        shadow_color:      app.ron_config.binding_mode.binding_mode_button_shadow_color,
+        shadow_x:          app.ron_config.binding_mode.binding_mode_button_shadow_x,
+        shadow_y:          app.ron_config.binding_mode.binding_mode_button_shadow_y,
+        shadow_blur:       app.ron_config.binding_mode.binding_mode_button_shadow_blur,
+    })
+}
+
+
+
+
+
+// ============ TESTS ============
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::AppData;
+
+    fn make_app(mode: &str) -> AppData
+    {
+        let mut app = AppData { ..Default::default() };
+        app.modules_data.binding_mode_data.mode = mode.into();
+        app.ron_config.binding_mode.binding_mode_format = "[{mode}]".into();
+        app
+    }
+
+    // ---- is_default_binding_mode ----------------------------------------------
+
+    #[test]
+    fn empty_and_hidden_modes_are_default()
+    {
+        let hidden = vec!["default".to_string()];
+        assert!(is_default_binding_mode("", &hidden));
+        assert!(is_default_binding_mode("default", &hidden));
+        assert!(!is_default_binding_mode("resize", &hidden));
+    }
+
+    // ---- define_binding_mode_text ---------------------------------------------
+
+    #[test]
+    fn binding_mode_text_is_empty_in_default_mode()
+    {
+        assert_eq!(define_binding_mode_text(&make_app("default")), "");
+        assert_eq!(define_binding_mode_text(&make_app("")), "");
+    }
+
+    #[test]
+    fn binding_mode_text_fills_format()
+    {
+        assert_eq!(define_binding_mode_text(&make_app("resize")), "[resize]");
+    }
+}
@@ -0,0 +1,247 @@
+// ============ IMPORTS ============
+use std::{collections::HashSet, pin::Pin};
+
+
+
+
+
+// ============ CRATES ============
+use crate::modules::{data::Modules, focused_window::{FocusedWindowData, read_focused_window_hypr, read_focused_window_niri, read_focused_window_sway}, hypr, niri, sway, taskbar::{TaskbarAction, TaskbarSnapshot}, wayland, workspaces::{UserWorkspaceAction, WorkspaceInfo}};
+use crate::update::Message;
+
+
+
+
+
+// ============ ENUM/STRUCT, ETC ============
+pub type EventStream = Pin<Box<dyn futures::Stream<Item = Message> + Send>>;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Compositor
+{
+    Hyprland,
+    Sway,
+    Niri,
+    /// Any other compositor, through the ext-workspace-v1 or River status protocols.
+    Wayland,
+}
+
+/// Everything the bar needs from a compositor, so the modules don't have to care which one is running.
+/// All the calls block on IPC, run them through `spawn_blocking`.
+pub trait CompositorBackend: Send + Sync
+{
+    fn compositor(&self) -> Compositor;
+    /// The focused workspace key and every workspace, see `WorkspaceInfo`.
+    fn workspaces(&self) -> (i32, Vec<WorkspaceInfo>);
+    fn focused_window(&self) -> Option<FocusedWindowData>;
+    fn windows(&self) -> TaskbarSnapshot;
+    fn change_workspace(&self, action: UserWorkspaceAction);
+    fn window_action(&self, id: &str, action: TaskbarAction);
+    /// A plain fn so iced sees the same subscription for every module using this compositor.
+    fn event_stream(&self) -> fn() -> EventStream;
+    fn supports_persistent_workspaces(&self) -> bool { true }
+}
+
+pub struct HyprlandBackend;
+pub struct SwayBackend;
+pub struct NiriBackend;
+pub struct WaylandBackend;
+
+
+
+impl CompositorBackend for HyprlandBackend
+{
+    fn compositor(&self) -> Compositor { Compositor::Hyprland }
+    fn workspaces(&self) -> (i32, Vec<WorkspaceInfo>) { (hypr::current_workspace(), hypr::workspace_count()) }
+    fn focused_window(&self) -> Option<FocusedWindowData> { read_focused_window_hypr() }
+    fn windows(&self) -> TaskbarSnapshot { hypr::taskbar_snapshot() }
+    fn change_workspace(&self, action: UserWorkspaceAction) { hypr::change_workspace_hypr(action) }
+    fn window_action(&self, id: &str, action: TaskbarAction) { hypr::taskbar_action_hypr(id, action) }
+    fn event_stream(&self) -> fn() -> EventStream { hypr::hypr_event_subscription }
+}
+
+impl CompositorBackend for SwayBackend
+{
+    fn compositor(&self) -> Compositor { Compositor::Sway }
+    fn workspaces(&self) -> (i32, Vec<WorkspaceInfo>) { (sway::current_workspace(), sway::workspace_count()) }
+    fn focused_window(&self) -> Option<FocusedWindowData> { read_focused_window_sway() }
+    fn windows(&self) -> TaskbarSnapshot { sway::taskbar_snapshot() }
+    fn change_workspace(&self, action: UserWorkspaceAction) { sway::change_workspace_sway(action) }
+    fn window_action(&self, id: &str, action: TaskbarAction) { sway::taskbar_action_sway(id, action) }
+    fn event_stream(&self) -> fn() -> EventStream { sway::sway_event_subscription }
+}
+
+impl CompositorBackend for NiriBackend
+{
+    fn compositor(&self) -> Compositor { Compositor::Niri }
+    fn workspaces(&self) -> (i32, Vec<WorkspaceInfo>) { (niri::current_workspace(), niri::workspace_count()) }
+    fn focused_window(&self) -> Option<FocusedWindowData> { read_focused_window_niri() }
+    fn windows(&self) -> TaskbarSnapshot { niri::taskbar_snapshot() }
+    fn change_workspace(&self, action: UserWorkspaceAction) { niri::change_workspace_niri(action) }
+    fn window_action(&self, id: &str, action: TaskbarAction) { niri::taskbar_action_niri(id, action) }
+    fn event_stream(&self) -> fn() -> EventStream { niri::niri_event_subscription }
+    fn supports_persistent_workspaces(&self) -> bool { false }
+}
+
+impl CompositorBackend for WaylandBackend
+{
+    fn compositor(&self) -> Compositor { Compositor::Wayland }
+    fn workspaces(&self) -> (i32, Vec<WorkspaceInfo>) { wayland::workspace_snapshot() }
+    fn focused_window(&self) -> Option<FocusedWindowData> { wayland::focused_window_wayland() }
+    // Neither protocol lists windows, the taskbar stays empty
+    fn windows(&self) -> TaskbarSnapshot { TaskbarSnapshot::default() }
+    fn change_workspace(&self, action: UserWorkspaceAction) { wayland::change_workspace_wayland(action) }
+    fn window_action(&self, _id: &str, _action: TaskbarAction) {}
+    fn event_stream(&self) -> fn() -> EventStream { wayland::wayland_event_subscription }
+    fn supports_persistent_workspaces(&self) -> bool { wayland::supports_persistent_workspaces() }
+}
+
+
+
+
+
+// ============ FUNCTIONS ============
+pub fn backend_for(compositor: Compositor) -> &'static dyn CompositorBackend
+{
+    match compositor
+    {
+        Compositor::Hyprland => &HyprlandBackend,
+        Compositor::Sway => &SwayBackend,
+        Compositor::Niri => &NiriBackend,
+        Compositor::Wayland => &WaylandBackend,
+    }
+}
+
+
+
+/// The running compositor, told apart by the IPC socket variable each one exports.
+/// Any other Wayland session falls back to the generic workspace protocols.
+pub fn detect_compositor() -> Option<Compositor>
+{
+    compositor_from_env(|name| std::env::var_os(name).is_some_and(|value| !value.is_empty()))
+}
+
+
+
+pub fn compositor_from_env(is_set: impl Fn(&str) -> bool) -> Option<Compositor>
+{
+    if is_set("HYPRLAND_INSTANCE_SIGNATURE") { return Some(Compositor::Hyprland); }
+    if is_set("NIRI_SOCKET") { return Some(Compositor::Niri); }
+    if is_set("SWAYSOCK") { return Some(Compositor::Sway); }
+    if is_set("WAYLAND_DISPLAY") { return Some(Compositor::Wayland); }
+    None
+}
+
+
+
+/// The old per-compositor variants are aliases pinning their compositor, the generic modules follow the running one.
+pub fn module_compositor(module: &Modules, detected: Option<Compositor>) -> Option<Compositor>
+{
+    match module
+    {
+        Modules::HyprWorkspaces | Modules::FocusedWindowHypr => Some(Compositor::Hyprland),
+        Modules::SwayWorkspaces | Modules::FocusedWindowSway => Some(Compositor::Sway),
+        Modules::NiriWorkspaces | Modules::FocusedWindowNiri => Some(Compositor::Niri),
+        Modules::Workspaces | Modules::FocusedWindow | Modules::Taskbar | Modules::BindingMode => detected,
+        _ => None,
+    }
+}
+
+
+
+fn first_backend(active_modules: &HashSet<Modules>, candidates: &[Modules]) -> Option<&'static dyn CompositorBackend>
+{
+    let module = candidates.iter().find(|module| active_modules.contains(module))?;
+    module_compositor(module, detect_compositor()).map(backend_for)
+}
+
+
+
+pub fn workspace_backend(active_modules: &HashSet<Modules>) -> Option<&'static dyn CompositorBackend>
+{
+    first_backend(active_modules, &[Modules::HyprWorkspaces, Modules::SwayWorkspaces, Modules::NiriWorkspaces, Modules::Workspaces])
+}
+
+
+
+pub fn focused_window_backend(active_modules: &HashSet<Modules>) -> Option<&'static dyn CompositorBackend>
+{
+    first_backend(active_modules, &[Modules::FocusedWindowHypr, Modules::FocusedWindowSway, Modules::FocusedWindowNiri, Modules::FocusedWindow])
+}
+
+
+
+pub fn taskbar_backend() -> Option<&'static dyn CompositorBackend>
+{
+    detect_compositor().map(backend_for)
+}
+
+
+
+/// One event stream per compositor in use, however many modules need it.
+pub fn compositor_subscriptions(active_modules: &HashSet<Modules>) -> Vec<iced::Subscription<Message>>
+{
+    let detected = detect_compositor();
+    let compositors: HashSet<Compositor> = active_modules.iter().filter_map(|module| module_compositor(module, detected)).collect();
+    compositors.into_iter().map(|compositor| iced::Subscription::run(backend_for(compositor).event_stream())).collect()
+}
+
+
+
+
+
+// ============ TESTS ============
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // ---- compositor_from_env --------------------------------------------------
+
+    #[test]
+    fn compositor_detected_from_socket_variables()
+    {
+        assert_eq!(compositor_from_env(|name| name == "SWAYSOCK"), Some(Compositor::Sway));
+        assert_eq!(compositor_from_env(|name| name == "NIRI_SOCKET"), Some(Compositor::Niri));
+        assert_eq!(compositor_from_env(|name| name == "HYPRLAND_INSTANCE_SIGNATURE" || name == "SWAYSOCK"), Some(Compositor::Hyprland));
+        assert_eq!(compositor_from_env(|name| name == "WAYLAND_DISPLAY"), Some(Compositor::Wayland));
+        assert_eq!(compositor_from_env(|name| name == "WAYLAND_DISPLAY" || name == "SWAYSOCK"), Some(Compositor::Sway));
+        assert_eq!(compositor_from_env(|_| false), None);
+    }
+
+    // ---- module_compositor ----------------------------------------------------
+
+    #[test]
+    fn legacy_modules_pin_their_compositor()
+    {
+        assert_eq!(module_compositor(&Modules::SwayWorkspaces, Some(Compositor::Niri)), Some(Compositor::Sway));
+        assert_eq!(module_compositor(&Modules::FocusedWindowHypr, None), Some(Compositor::Hyprland));
+    }
+
+    #[test]
+    fn generic_modules_follow_detected_compositor()
+    {
+        assert_eq!(module_compositor(&Modules::Workspaces, Some(Compositor::Niri)), Some(Compositor::Niri));
+        assert_eq!(module_compositor(&Modules::FocusedWindow, None), None);
+        assert_eq!(module_compositor(&Modules::BindingMode, Some(Compositor::Hyprland)), Some(Compositor::Hyprland));
+        assert_eq!(module_compositor(&Modules::Clock, Some(Compositor::Sway)), None);
+    }
+
+    #[test]
+    fn backends_report_their_compositor()
+    {
+        for compositor in [Compositor::Hyprland, Compositor::Sway, Compositor::Niri, Compositor::Wayland]
+        {
+            assert_eq!(backend_for(compositor).compositor(), compositor);
+        }
+        assert!(!backend_for(Compositor::Niri).supports_persistent_workspaces());
+    }
+
+    #[test]
+    fn legacy_workspace_module_wins_over_generic()
+    {
+        let active: HashSet<Modules> = [Modules::Workspaces, Modules::SwayWorkspaces].into_iter().collect();
+        assert_eq!(workspace_backend(&active).map(|backend| backend.compositor()), Some(Compositor::Sway));
+        assert!(workspace_backend(&HashSet::new()).is_none());
+    }
+}
@@ -1,14 +1,16 @@
 // ============ IMPORTS ============
 use iced::widget::button;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 
 
 
 // ============ CRATES ============
-use crate::helpers::style::{UserStyle, orient_text, set_style, TextOrientation, SideOption};
+use crate::helpers::style::{StateStyle, UserStyle, apply_state_style, module_state, orient_text, set_style, state_format, TextOrientation, SideOption};
 use crate::helpers::color::{ColorType, Gradient};
 use crate::ron::ActionOnClick;
+use crate::modules::data::Modules;
 use crate::AppData;
 
 
@@ -46,6 +48,7 @@ pub struct CpuConfig
     pub cpu_button_shadow_x:               f32,
     pub cpu_button_shadow_y:               f32,
     pub cpu_button_shadow_blur:            f32,
+    pub cpu_states:                        HashMap<String, StateStyle>,
 }
 
 impl Default for CpuConfig
@@ -81,6 +84,7 @@ impl Default for CpuConfig
             cpu_button_shadow_x:               0.0,
             cpu_button_shadow_y:               0.0,
             cpu_button_shadow_blur:            0.0,
+            cpu_states:                        HashMap::new(),
         }
     }
 }
@@ -137,13 +141,13 @@ pub fn compute_cpu_usage(prev: &CpuSnapshot, curr: &CpuSnapshot) -> f32
 pub fn define_cpu_text(app: &AppData) -> String
 {
     let usage = app.modules_data.cpu_data.usage_percent;
-    let text  = app.ron_config.cpu.cpu_format.replace("{usage}", &format!("{:.0}", usage));
+    let text  = state_format(&app.ron_config.cpu.cpu_format, module_state(app, &Modules::Cpu)).replace("{usage}", &format!("{:.0}", usage));
     orient_text(&text, &app.ron_config.cpu.cpu_text_orientation)
 }
 
 pub fn define_cpu_style(app: &AppData, status: button::Status) -> iced::widget::button::Style
 {
-    set_style(UserStyle
+    set_style(apply_state_style(UserStyle
     {
         status,
         normal:            app.ron_config.cpu.cpu_button_color,
@@ -162,7 +166,7 @@ pub fn define_cpu_style(app: &AppData, status: button::Status) -> iced::widget::
         shadow_x: app.ron_config.cpu.cpu_button_shadow_x,
         shadow_y: app.ron_config.cpu.cpu_button_shadow_y,
         shadow_blur: app.ron_config.cpu.cpu_button_shadow_blur,
-    })
+    }, module_state(app, &Modules::Cpu), app.modules_data.state_blink_off))
 }
 
 
@@ -360,4 +364,20 @@ mod tests
     {
         assert_eq!(CpuData::default().usage_percent, 0.0);
     }
+
+    // ---- cpu_states ---------------------------------------------------------
+
+    #[test]
+    fn cpu_states_switch_format_and_style_at_threshold()
+    {
+        let mut app = AppData::default();
+        app.ron_config.cpu.cpu_format = "CPU {usage}%".into();
+        app.ron_config.cpu.cpu_states.insert("critical".into(), StateStyle { threshold: 90., format: Some("!! {usage}%".into()), button_color: Some(ColorType::RGB([200, 0, 0])), ..Default::default() });
+        app.modules_data.cpu_data.usage_percent = 42.;
+        assert_eq!(define_cpu_text(&app), "CPU 42%");
+        app.modules_data.cpu_data.usage_percent = 93.;
+        assert_eq!(define_cpu_text(&app), "!! 93%");
+        let style = define_cpu_style(&app, button::Status::Active);
+        assert_eq!(style.background, Some(iced::Background::Color(ColorType::RGB([200, 0, 0]).to_iced_color())));
+    }
 }
@@ -1,13 +1,15 @@
 // ============ IMPORTS ============
 use iced::widget::button;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 
 
 // ============ CRATES ============
-use crate::helpers::style::{UserStyle, orient_text, set_style, TextOrientation, SideOption};
+use crate::helpers::style::{StateStyle, UserStyle, apply_state_style, module_state, orient_text, set_style, state_format, TextOrientation, SideOption};
 use crate::helpers::color::{ColorType, Gradient};
 use crate::ron::ActionOnClick;
+use crate::modules::data::Modules;
 use crate::AppData;
 
 
@@ -44,6 +46,7 @@ pub struct CpuTempConfig
     pub cpu_temp_button_shadow_x:               f32,
     pub cpu_temp_button_shadow_y:               f32,
     pub cpu_temp_button_shadow_blur:            f32,
+    pub cpu_temp_states:                        HashMap<String, StateStyle>,
 }
 
 impl Default for CpuTempConfig
@@ -79,6 +82,7 @@ impl Default for CpuTempConfig
             cpu_temp_button_shadow_x:               0.0,
             cpu_temp_button_shadow_y:               0.0,
             cpu_temp_button_shadow_blur:            0.0,
+            cpu_temp_states:                        HashMap::new(),
         }
     }
 }
@@ -155,7 +159,7 @@ fn read_temp_file(path: &str) -> Option<f32>
 pub fn define_cpu_temp_text(app: &AppData) -> String
 {
     let temp = app.modules_data.cpu_temp_data.temp_celsius;
-    let text = app.ron_config.cpu_temp.cpu_temp_format.replace("{temp}", &format!("{:.0}", temp));
+    let text = state_format(&app.ron_config.cpu_temp.cpu_temp_format, module_state(app, &Modules::CpuTemp)).replace("{temp}", &format!("{:.0}", temp));
     orient_text(&text, &app.ron_config.cpu_temp.cpu_temp_text_orientation)
 }
 
@@ -163,7 +167,7 @@ pub fn define_cpu_temp_text(app: &AppData) -> String
 
 pub fn define_cpu_temp_style(app: &AppData, status: button::Status) -> iced::widget::button::Style
 {
-    set_style(UserStyle
+    set_style(apply_state_style(UserStyle
     {
         status,
         normal:            app.ron_config.cpu_temp.cpu_temp_button_color,
@@ -182,7 +186,7 @@ pub fn define_cpu_temp_style(app: &AppData, status: button::Status) -> iced::wid
         shadow_x: app.ron_config.cpu_temp.cpu_temp_button_shadow_x,
         shadow_y: app.ron_config.cpu_temp.cpu_temp_button_shadow_y,
         shadow_blur: app.ron_config.cpu_temp.cpu_temp_button_shadow_blur,
-    })
+    }, module_state(app, &Modules::CpuTemp), app.modules_data.state_blink_off))
 }
 
 
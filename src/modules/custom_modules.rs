@@ -1,15 +1,22 @@
 // ============ IMPORTS ============
 use serde::{Deserialize, Serialize};
-use iced::widget::button;
-use std::time::Instant;
+use iced::{Element, Theme, widget::{button, container, text}};
+use std::{collections::HashMap, process::Stdio, time::{Duration, Instant}};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use futures::stream::BoxStream;
+use futures_util::StreamExt;
+use async_stream::stream;
 
 
 
 
 
 // ============ CRATES ============
-use crate::helpers::{color::{Gradient, ColorType}, string::ellipsize, style::{SideOption, TextOrientation, UserStyle, set_style}};
-use crate::AppData;
+use crate::helpers::{color::{Gradient, ColorType}, command::{CommandOptions, CommandRequest, build_command, context_env, merge_env, run_command, static_context_env}, string::ellipsize, style::{SideOption, TextOrientation, UserStyle, set_style}};
+use crate::context_menu::module_bounds;
+use crate::modules::data::Modules;
+use crate::update::Message;
+use crate::{AppData, WindowInfo};
 
 
 
@@ -22,6 +29,67 @@ pub struct CustomModuleData
     pub cached_continuous_outputs: Vec<String>,
     pub custom_module_last_run: Vec<Instant>,
     pub cached_command_outputs: Vec<String>,
+    /// Bumped on every config reload, so the `exec_stream` processes are restarted even when their command didn't change.
+    pub config_generation: u64,
+    /// Last JSON object printed by each `output_format: Json` module, by whichever of its commands ran last.
+    pub cached_json_outputs: Vec<CustomModuleOutput>,
+    /// Exit code of the last captured command of each module, `None` when it didn't exit normally.
+    pub cached_exit_codes: Vec<Option<i32>>,
+    /// Hovered module and its anchor id, its tooltip popup is shown whenever the tooltip isn't empty.
+    pub hovered_tooltip_module: Option<(usize, String)>,
+}
+
+/// Waybar compatible `{"text", "tooltip", "class", "percentage", "alt"}` object.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct CustomModuleOutput
+{
+    pub text: String,
+    pub tooltip: String,
+    pub class: Vec<String>,
+    pub percentage: Option<u8>,
+    pub alt: String,
+}
+
+/// Identity of the listener of one `SIGRTMIN+n` signal.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CustomModuleSignal(pub u8);
+
+/// Identity of an `exec_stream` subscription, iced restarts the process whenever it changes.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CustomModuleStream
+{
+    pub index: usize,
+    pub command: Vec<String>,
+    pub shell: bool,
+    /// Only the variables that never change, a new value would restart the process.
+    pub env: Vec<(String, String)>,
+    pub generation: u64,
+}
+
+
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum CustomModuleOutputFormat
+{
+    #[default] Text,
+    Json,
+}
+
+
+
+/// Overrides applied on top of the module style while the JSON output lists the class, unset fields keep the module's own value.
+#[derive(Default, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CustomModuleClassStyle
+{
+    pub text_color: Option<ColorType>,
+    pub button_color: Option<ColorType>,
+    pub button_hovered_color: Option<ColorType>,
+    pub button_hovered_text_color: Option<ColorType>,
+    pub button_pressed_text_color: Option<ColorType>,
+    pub button_pressed_color: Option<ColorType>,
+    pub border_color: Option<ColorType>,
+    pub button_gradient_color: Option<Gradient>,
 }
 
 
@@ -76,7 +144,19 @@ pub struct CustomModule
     pub command_to_exec_on_left_click: Vec<String>,
     pub command_to_exec_on_right_click: Vec<String>,
     pub continous_command_interval: u64,
-    pub continous_command: Vec<String>
+    pub continous_command: Vec<String>,
+    pub command_timeout_ms: Option<u64>,
+    pub shell: bool,
+    pub env: HashMap<String, String>,
+    pub exec_stream: bool,
+    pub signal: Option<u8>,
+    pub output_format: CustomModuleOutputFormat,
+    pub format_icons: Vec<String>,
+    pub alt_icons: HashMap<String, String>,
+    pub class_styles: HashMap<String, CustomModuleClassStyle>,
+    pub show_tooltip: bool,
+    pub tooltip_text_size: u32,
+    pub tooltip_padding: u16
 }
 
 
@@ -126,13 +206,433 @@ impl Default for CustomModule
             command_to_exec_on_left_click: vec![], 
             command_to_exec_on_right_click: vec![],
             continous_command_interval: 500,
-            continous_command: vec![]
+            continous_command: vec![],
+            command_timeout_ms: Some(10_000),
+            shell: false,
+            env: HashMap::new(),
+            exec_stream: false,
+            signal: None,
+            output_format: CustomModuleOutputFormat::Text,
+            format_icons: vec![],
+            alt_icons: HashMap::new(),
+            class_styles: HashMap::new(),
+            show_tooltip: true,
+            tooltip_text_size: 12,
+            tooltip_padding: 6
+        }
+    }
+}
+
+
+
+/// Modules whose `continous_command` is re-run from the tick loop, `exec_stream` ones run once in their own subscription.
+pub fn is_polled_custom_module(module: &CustomModule) -> bool
+{
+    !module.continous_command.is_empty() && !module.exec_stream
+}
+
+
+
+pub fn custom_module_streams(app: &AppData) -> Vec<CustomModuleStream>
+{
+    let mut streams: Vec<CustomModuleStream> = app.modules_data.active_modules.iter().filter_map(|module| match module
+    {
+        Modules::CustomModule(index) => app.ron_config.custom_module.custom_modules.get(*index).filter(|custom| custom.exec_stream && !custom.continous_command.is_empty()).map(|custom| CustomModuleStream
+        {
+            index: *index,
+            command: custom.continous_command.clone(),
+            shell: custom.shell,
+            env: merge_env(static_context_env(app, &format!("CustomModule({index})")), &custom.env),
+            generation: app.modules_data.custom_module_data.config_generation,
+        }),
+        _ => None,
+    }).collect();
+    streams.sort_by_key(|stream| stream.index);
+    streams
+}
+
+
+
+pub fn run_continuous_command(index: usize, module: &CustomModule, env: Vec<(String, String)>) -> iced::Task<Message>
+{
+    run_custom_module_command(index, module, module.continous_command.clone(), true, format!("'{}' continous_command", module.name), env)
+}
+
+
+
+/// Automatic ICEBAR_* variables merged with the module's own `env`.
+pub fn custom_module_env(app: &AppData, index: usize, button: &str) -> Vec<(String, String)>
+{
+    let env = context_env(app, &format!("CustomModule({index})"), button);
+    match app.ron_config.custom_module.custom_modules.get(index)
+    {
+        Some(module) => merge_env(env, &module.env),
+        None => env,
+    }
+}
+
+
+
+pub fn custom_module_click_request(index: usize, module: &CustomModule, is_left_click: bool) -> CommandRequest
+{
+    CommandRequest
+    {
+        output_index: Some(index),
+        command: if is_left_click { module.command_to_exec_on_left_click.clone() } else { module.command_to_exec_on_right_click.clone() },
+        name: module.name.clone(),
+        module: format!("CustomModule({index})"),
+        is_left_click,
+        output_as_text: module.use_output_as_text,
+        shell: module.shell,
+        env: module.env.clone(),
+    }
+}
+
+
+
+/// Captured run, the output lands in the module's cached output and its exit code in `{exit_code}`.
+/// A run is skipped while the previous one of the same module and kind is still in flight.
+pub fn run_custom_module_command(index: usize, module: &CustomModule, command: Vec<String>, continuous: bool, label: String, env: Vec<(String, String)>) -> iced::Task<Message>
+{
+    let Some((program, args)) = command.split_first() else { return iced::Task::none(); };
+    let options = CommandOptions
+    {
+        label,
+        timeout: module.command_timeout_ms.map(Duration::from_millis),
+        exclusive_key: Some(format!("custom-module-{index}-{}", if continuous { "continuous" } else { "click" })),
+        cancel_on_reload: true,
+        shell: module.shell,
+        env,
+    };
+    let display_err = module.display_err_output_if_failed;
+
+    iced::Task::perform(run_command(program.clone(), args.to_vec(), options), |result| result).then(move |result|
+    {
+        let Some(result) = result else { return iced::Task::none(); };
+        let text = result.display_text(display_err);
+        let output_message = if continuous { Message::ContinuousCommandFinished(index, text) } else { Message::CommandFinished(index, text) };
+        iced::Task::batch([iced::Task::done(Message::CommandExited(index, result.exit_code)), iced::Task::done(output_message)])
+    })
+}
+
+
+
+pub fn receive_custom_module_exit_code(app: &mut AppData, index: usize, exit_code: Option<i32>)
+{
+    let exit_codes = &mut app.modules_data.custom_module_data.cached_exit_codes;
+    if exit_codes.len() <= index { exit_codes.resize(index + 1, None); }
+    exit_codes[index] = exit_code;
+}
+
+
+
+/// Signals used by the active polled modules, one listener is spawned per signal.
+pub fn custom_module_signals(app: &AppData) -> Vec<CustomModuleSignal>
+{
+    let mut signals: Vec<u8> = app.modules_data.active_modules.iter().filter_map(|module| match module
+    {
+        Modules::CustomModule(index) => app.ron_config.custom_module.custom_modules.get(*index).filter(|custom| is_polled_custom_module(custom)).and_then(|custom| custom.signal),
+        _ => None,
+    }).collect();
+    signals.sort_unstable();
+    signals.dedup();
+    signals.into_iter().map(CustomModuleSignal).collect()
+}
+
+
+
+pub fn signaled_custom_modules(app: &AppData, signal: u8) -> Vec<usize>
+{
+    let mut indexes: Vec<usize> = app.modules_data.active_modules.iter().filter_map(|module| match module
+    {
+        Modules::CustomModule(index) => app.ron_config.custom_module.custom_modules.get(*index).filter(|custom| is_polled_custom_module(custom) && custom.signal == Some(signal)).map(|_| *index),
+        _ => None,
+    }).collect();
+    indexes.sort_unstable();
+    indexes
+}
+
+
+
+/// Yields every time `SIGRTMIN+n` is received, e.g: `pkill -RTMIN+8 icebar`.
+pub fn custom_module_signal_stream(signal: &CustomModuleSignal) -> BoxStream<'static, Message>
+{
+    let signal = signal.0;
+    stream!
+    {
+        let signal_number = libc::SIGRTMIN() + signal as i32;
+        if signal_number > libc::SIGRTMAX()
+        {
+            eprintln!("[icebar] signal RTMIN+{signal} is above SIGRTMAX, the maximum is RTMIN+{}", libc::SIGRTMAX() - libc::SIGRTMIN());
+            return;
+        }
+        let mut listener = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::from_raw(signal_number))
+        {
+            Ok(listener) => listener,
+            Err(e) => { eprintln!("[icebar] couldn't listen to signal RTMIN+{signal}: {e}"); return; }
+        };
+        while listener.recv().await.is_some() { yield Message::CustomModuleSignal(signal); }
+    }.boxed()
+}
+
+
+
+const STREAM_BACKOFF_MIN: Duration = Duration::from_secs(1);
+const STREAM_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// Doubles the restart delay after each quick exit, a process that ran for a minute starts over from the minimum.
+pub fn next_stream_backoff(current: Duration, ran_for: Duration) -> Duration
+{
+    if ran_for >= STREAM_BACKOFF_MAX { return STREAM_BACKOFF_MIN; }
+    (current * 2).clamp(STREAM_BACKOFF_MIN, STREAM_BACKOFF_MAX)
+}
+
+
+
+/// Runs the command once and turns every stdout line into the module's continuous output.
+/// The child is killed when iced drops the subscription (module removed, config reloaded or bar closed).
+pub fn custom_module_stream(stream: &CustomModuleStream) -> BoxStream<'static, Message>
+{
+    let CustomModuleStream { index, command, shell, env, .. } = stream.clone();
+    stream!
+    {
+        let Some((program, args)) = command.split_first() else { return; };
+        // Half the minimum, so the first restart waits exactly STREAM_BACKOFF_MIN once doubled
+        let mut backoff = STREAM_BACKOFF_MIN / 2;
+        loop
+        {
+            let started = Instant::now();
+            match build_command(program, args, shell, &env).stdin(Stdio::null()).stdout(Stdio::piped()).kill_on_drop(true).spawn()
+            {
+                Ok(mut child) =>
+                {
+                    if let Some(stdout) = child.stdout.take()
+                    {
+                        let mut lines = BufReader::new(stdout).lines();
+                        while let Ok(Some(line)) = lines.next_line().await { yield Message::ContinuousCommandFinished(index, line); }
+                    }
+                    match child.wait().await
+                    {
+                        Ok(status) => eprintln!("[icebar] exec_stream {command:?} exited with {status}"),
+                        Err(e) => eprintln!("[icebar] exec_stream {command:?} failed: {e}"),
+                    }
+                }
+                Err(e) => eprintln!("[icebar] exec_stream {command:?} couldn't start: {e}"),
+            }
+            backoff = next_stream_backoff(backoff, started.elapsed());
+            eprintln!("[icebar] restarting exec_stream {command:?} in {}s", backoff.as_secs());
+            tokio::time::sleep(backoff).await;
+        }
+    }.boxed()
+}
+
+
+
+/// Reads the object from the whole output, or from its last non-empty line so scripts that print one object per update also work.
+/// Returns `None` when no JSON object can be found.
+pub fn parse_custom_module_output(raw: &str) -> Option<CustomModuleOutput>
+{
+    let parse = |candidate: &str| serde_json::from_str::<serde_json::Value>(candidate.trim()).ok().filter(serde_json::Value::is_object);
+    let value = parse(raw).or_else(|| raw.lines().rev().find(|line| !line.trim().is_empty()).and_then(parse))?;
+    let string_field = |key: &str| match &value[key]
+    {
+        serde_json::Value::String(string) => string.clone(),
+        serde_json::Value::Number(number) => number.to_string(),
+        _ => String::new(),
+    };
+    let class = match &value["class"]
+    {
+        serde_json::Value::String(class) if !class.is_empty() => vec![class.clone()],
+        serde_json::Value::Array(classes) => classes.iter().filter_map(|class| class.as_str()).filter(|class| !class.is_empty()).map(String::from).collect(),
+        _ => vec![],
+    };
+    Some(CustomModuleOutput
+    {
+        text: string_field("text"),
+        tooltip: string_field("tooltip"),
+        class,
+        percentage: value["percentage"].as_f64().map(|percentage| percentage.clamp(0., 100.).round() as u8),
+        alt: string_field("alt"),
+    })
+}
+
+
+
+/// Stores a command output, JSON modules keep the parsed object and expose its `text` through the usual `{output}` / `{continous_output}`.
+pub fn receive_custom_module_output(app: &mut AppData, index: usize, raw: String, continuous: bool)
+{
+    let is_json = app.ron_config.custom_module.custom_modules.get(index).is_some_and(|module| module.output_format == CustomModuleOutputFormat::Json);
+    let text = if is_json
+    {
+        let output = parse_custom_module_output(&raw).unwrap_or_else(||
+        {
+            if !raw.trim().is_empty() { eprintln!("[icebar] custom module {index} didn't print a JSON object, using its raw output as text"); }
+            CustomModuleOutput { text: raw.trim().to_string(), ..Default::default() }
+        });
+        let text = output.text.clone();
+        let json_outputs = &mut app.modules_data.custom_module_data.cached_json_outputs;
+        if json_outputs.len() <= index { json_outputs.resize(index + 1, CustomModuleOutput::default()); }
+        json_outputs[index] = output;
+        text
+    }
+    else
+    {
+        raw
+    };
+
+    let outputs = if continuous { &mut app.modules_data.custom_module_data.cached_continuous_outputs } else { &mut app.modules_data.custom_module_data.cached_command_outputs };
+    if outputs.len() <= index { outputs.resize(index + 1, String::new()); }
+    outputs[index] = text;
+}
+
+
+
+pub fn custom_module_json_output(app: &AppData, index: usize) -> Option<&CustomModuleOutput>
+{
+    let module = app.ron_config.custom_module.custom_modules.get(index)?;
+    if module.output_format != CustomModuleOutputFormat::Json { return None; }
+    app.modules_data.custom_module_data.cached_json_outputs.get(index)
+}
+
+
+
+/// `alt_icons` wins when it has an entry for `alt`, otherwise `format_icons` is indexed by the percentage, like Waybar's `format-icons`.
+pub fn define_custom_module_icon(custom_module: &CustomModule, output: &CustomModuleOutput) -> String
+{
+    if let Some(icon) = custom_module.alt_icons.get(&output.alt) { return icon.clone(); }
+    let (Some(percentage), false) = (output.percentage, custom_module.format_icons.is_empty()) else { return String::new(); };
+    let last = custom_module.format_icons.len() - 1;
+    custom_module.format_icons[(percentage as usize * custom_module.format_icons.len() / 100).min(last)].clone()
+}
+
+
+
+fn fill_custom_module_json_placeholders(text: String, custom_module: &CustomModule, output: Option<&CustomModuleOutput>) -> String
+{
+    let Some(output) = output else { return text; };
+    text.replace("{tooltip}", &output.tooltip)
+        .replace("{class}", &output.class.join(" "))
+        .replace("{percentage}", &output.percentage.map(|percentage| percentage.to_string()).unwrap_or_default())
+        .replace("{alt}", &output.alt)
+        .replace("{icon}", &define_custom_module_icon(custom_module, output))
+}
+
+
+
+/// Copy of the module with the `class_styles` of every listed class applied in order.
+pub fn apply_custom_module_classes(custom_module: &CustomModule, classes: &[String]) -> CustomModule
+{
+    let mut styled = custom_module.clone();
+    for class_style in classes.iter().filter_map(|class| custom_module.class_styles.get(class))
+    {
+        if let Some(color) = class_style.text_color { styled.text_color = color; }
+        if let Some(color) = class_style.button_color { styled.button_color = color; }
+        if let Some(color) = class_style.button_hovered_color { styled.button_hovered_color = color; }
+        if let Some(color) = class_style.button_hovered_text_color { styled.button_hovered_text_color = color; }
+        if let Some(color) = class_style.button_pressed_text_color { styled.button_pressed_text_color = color; }
+        if let Some(color) = class_style.button_pressed_color { styled.button_pressed_color = color; }
+        if let Some(color) = class_style.border_color { styled.border_color = color; }
+        if let Some(gradient) = &class_style.button_gradient_color { styled.button_gradient_color = Some(gradient.clone()); }
+    }
+    styled
+}
+
+
+
+pub fn define_custom_module_tooltip(app: &AppData, index: usize) -> String
+{
+    let Some(module) = app.ron_config.custom_module.custom_modules.get(index) else { return String::new(); };
+    if !module.show_tooltip { return String::new(); }
+    custom_module_json_output(app, index).map(|output| output.tooltip.clone()).unwrap_or_default()
+}
+
+
+
+/// Rough text extent, the popup is a layer surface so it has to be sized before it's drawn.
+pub fn get_custom_module_tooltip_size(app: &AppData) -> (u32, u32)
+{
+    let Some((index, _)) = app.modules_data.custom_module_data.hovered_tooltip_module else { return (1, 1); };
+    let Some(module) = app.ron_config.custom_module.custom_modules.get(index) else { return (1, 1); };
+    let tooltip = define_custom_module_tooltip(app, index);
+    let longest_line = tooltip.lines().map(|line| line.chars().count()).max().unwrap_or(0) as f32;
+    let line_count = tooltip.lines().count().max(1) as f32;
+    let padding = module.tooltip_padding as f32 * 2. + module.border_size * 2.;
+    (
+        (longest_line * module.tooltip_text_size as f32 * 0.6 + padding).ceil() as u32,
+        (line_count * module.tooltip_text_size as f32 * 1.3 + padding).ceil() as u32
+    )
+}
+
+
+
+pub fn show_custom_module_tooltip(app: &mut AppData, index: usize, anchor: String) -> iced::Task<Message>
+{
+    let close_task = close_custom_module_tooltip_windows(app);
+    app.modules_data.custom_module_data.hovered_tooltip_module = Some((index, anchor.clone()));
+    if define_custom_module_tooltip(app, index).is_empty() { return close_task; }
+    let id = iced::window::Id::unique();
+    app.ids.insert(id, WindowInfo::Tooltip);
+    iced::Task::batch([close_task, module_bounds(&anchor).map(move |bounds| Message::PopupAnchorResolved(id, bounds))])
+}
+
+
+
+pub fn hide_custom_module_tooltip(app: &mut AppData) -> iced::Task<Message>
+{
+    app.modules_data.custom_module_data.hovered_tooltip_module = None;
+    close_custom_module_tooltip_windows(app)
+}
+
+
+
+fn close_custom_module_tooltip_windows(app: &mut AppData) -> iced::Task<Message>
+{
+    let window_ids_to_close: Vec<iced::window::Id> = app.ids.iter().filter(|(_, info)| **info == WindowInfo::Tooltip).map(|(id, _)| *id).collect();
+    for id in &window_ids_to_close { app.ids.remove(id); }
+    iced::Task::batch(window_ids_to_close.into_iter().map(|id| iced::Task::done(Message::RemoveWindow(id))))
+}
+
+
+
+/// Keeps the hovered module's popup in sync with new output, opening or closing it as the tooltip appears or empties.
+pub fn refresh_custom_module_tooltip(app: &mut AppData, index: usize) -> iced::Task<Message>
+{
+    let Some((hovered, anchor)) = app.modules_data.custom_module_data.hovered_tooltip_module.clone() else { return iced::Task::none(); };
+    if hovered != index { return iced::Task::none(); }
+    let is_open = app.ids.values().any(|info| *info == WindowInfo::Tooltip);
+    match (is_open, define_custom_module_tooltip(app, index).is_empty())
+    {
+        (true, true) => close_custom_module_tooltip_windows(app),
+        (true, false) =>
+        {
+            let size = get_custom_module_tooltip_size(app);
+            iced::Task::batch(app.ids.iter().filter(|(_, info)| **info == WindowInfo::Tooltip).map(|(id, _)| iced::Task::done(Message::SizeChange { id: *id, size })))
         }
+        (false, false) => show_custom_module_tooltip(app, index, anchor),
+        (false, true) => iced::Task::none(),
     }
 }
 
 
 
+pub fn custom_module_tooltip_view(app: &AppData) -> Element<'_, Message>
+{
+    let Some((index, _)) = app.modules_data.custom_module_data.hovered_tooltip_module else { return text("").into(); };
+    let Some(module) = app.ron_config.custom_module.custom_modules.get(index) else { return text("").into(); };
+    container(text(define_custom_module_tooltip(app, index)).size(module.tooltip_text_size).font(app.default_font))
+        .padding(module.tooltip_padding)
+        .style(move |_: &Theme| container::Style
+        {
+            text_color: Some(module.text_color.to_iced_color()),
+            background: Some(module.button_color.to_iced_color().into()),
+            border: iced::Border { color: module.border_color.to_iced_color(), width: module.border_size, radius: iced::border::Radius { top_left: module.border_radius[0], top_right: module.border_radius[1], bottom_left: module.border_radius[2], bottom_right: module.border_radius[3] } },
+            ..Default::default()
+        })
+        .into()
+}
+
+
+
 pub fn define_custom_module_style(custom_module: &CustomModule, status: button::Status) -> iced::widget::button::Style
 {
     let hovered = custom_module.button_hovered_color; 
@@ -151,20 +651,23 @@ pub fn define_custom_module_style(custom_module: &CustomModule, status: button::
 
 pub fn define_custom_module_text(index: usize, custom_module: &CustomModule, app: &AppData) -> String
 {
+    let exit_code = app.modules_data.custom_module_data.cached_exit_codes.get(index).copied().flatten().map(|code| code.to_string()).unwrap_or_default();
     // COMMAND_OUTPUT
     if custom_module.use_output_as_text && !custom_module.all_output_as_text_format.is_empty()
     {
         let output_text = app.modules_data.custom_module_data.cached_command_outputs.get(index).map(String::as_str).unwrap_or("");
         let output_text = ellipsize(&app.ron_config.general.ellipsis_text, output_text, custom_module.output_text_limit_len);
         if custom_module.dont_show_if_any_output_is_empty && output_text.is_empty() { return String::new() };
-        custom_module.all_output_as_text_format.replace("{text}", &custom_module.text).replace("{output}", &output_text).replace('\n', "")
+        let text = custom_module.all_output_as_text_format.replace("{text}", &custom_module.text).replace("{output}", &output_text).replace("{exit_code}", &exit_code).replace('\n', "");
+        fill_custom_module_json_placeholders(text, custom_module, custom_module_json_output(app, index))
     }
     // CONTINOUS_OUTPUT
     else if custom_module.use_continous_output_as_text && !custom_module.all_output_as_text_format.is_empty() && !&app.modules_data.custom_module_data.cached_continuous_outputs.is_empty() && (app.modules_data.custom_module_data.cached_continuous_outputs.len() - 1) >= index
     {
         let output_text = ellipsize(&app.ron_config.general.ellipsis_text, &app.modules_data.custom_module_data.cached_continuous_outputs[index], custom_module.output_text_limit_len);
         if custom_module.dont_show_if_any_output_is_empty && output_text.is_empty() { return String::new() };
-        custom_module.all_output_as_text_format.replace("{text}", &custom_module.text).replace("{continous_output}", &output_text).replace('\n', "")
+        let text = custom_module.all_output_as_text_format.replace("{text}", &custom_module.text).replace("{continous_output}", &output_text).replace("{exit_code}", &exit_code).replace('\n', "");
+        fill_custom_module_json_placeholders(text, custom_module, custom_module_json_output(app, index))
     }
     // NO OUTPUT JUST TEXT
     else 
@@ -407,4 +910,189 @@ mod tests
         // index 5 is beyond vec → condition fails → falls to plain text branch
         assert_eq!(define_custom_module_text(5, &m, &app), "Fallback");
     }
+ 
+    // ---- exec_stream --------------------------------------------------------
+ 
+    #[test]
+    fn exec_stream_modules_are_not_polled()
+    {
+        let polled = CustomModule { continous_command: vec!["date".into()], ..CustomModule::default() };
+        let streamed = CustomModule { exec_stream: true, ..polled.clone() };
+        assert!(is_polled_custom_module(&polled));
+        assert!(!is_polled_custom_module(&streamed));
+        assert!(!is_polled_custom_module(&CustomModule::default()));
+    }
+ 
+    #[test]
+    fn custom_module_streams_lists_active_exec_stream_modules()
+    {
+        let mut app = AppData { ..Default::default() };
+        app.ron_config.custom_module.custom_modules = vec!
+        [
+            CustomModule { continous_command: vec!["date".into()], ..CustomModule::default() },
+            CustomModule { continous_command: vec!["tail".into(), "-f".into(), "log".into()], exec_stream: true, ..CustomModule::default() },
+            CustomModule { exec_stream: true, ..CustomModule::default() },
+            CustomModule { continous_command: vec!["inactive".into()], exec_stream: true, ..CustomModule::default() },
+        ];
+        app.modules_data.active_modules = [Modules::CustomModule(0), Modules::CustomModule(1), Modules::CustomModule(2)].into_iter().collect();
+        app.modules_data.custom_module_data.config_generation = 3;
+        let streams = custom_module_streams(&app);
+        assert_eq!(streams.len(), 1);
+        assert_eq!((streams[0].index, streams[0].generation), (1, 3));
+        assert_eq!(streams[0].command, vec!["tail".to_string(), "-f".to_string(), "log".to_string()]);
+        assert!(streams[0].env.contains(&("ICEBAR_MODULE".to_string(), "CustomModule(1)".to_string())));
+    }
+ 
+    #[test]
+    fn stream_backoff_doubles_up_to_max_and_resets_after_long_run()
+    {
+        assert_eq!(next_stream_backoff(STREAM_BACKOFF_MIN / 2, Duration::ZERO), STREAM_BACKOFF_MIN);
+        assert_eq!(next_stream_backoff(Duration::from_secs(1), Duration::ZERO), Duration::from_secs(2));
+        assert_eq!(next_stream_backoff(Duration::from_secs(40), Duration::ZERO), STREAM_BACKOFF_MAX);
+        assert_eq!(next_stream_backoff(Duration::from_secs(40), Duration::from_secs(120)), STREAM_BACKOFF_MIN);
+    }
+ 
+    // ---- JSON output --------------------------------------------------------
+ 
+    fn make_json_app() -> AppData
+    {
+        let mut app = AppData { ..Default::default() };
+        app.ron_config.custom_module.custom_modules = vec![CustomModule
+        {
+            output_format: CustomModuleOutputFormat::Json,
+            use_continous_output_as_text: true,
+            all_output_as_text_format: "{icon} {continous_output} ({percentage}% {alt})".into(),
+            format_icons: vec!["low".into(), "mid".into(), "high".into()],
+            ..CustomModule::default()
+        }];
+        app
+    }
+ 
+    #[test]
+    fn parse_custom_module_output_reads_waybar_fields()
+    {
+        let output = parse_custom_module_output(r#"{"text": "42", "tooltip": "line1\nline2", "class": "warning", "percentage": 41.6, "alt": "muted"}"#).unwrap();
+        assert_eq!(output, CustomModuleOutput { text: "42".into(), tooltip: "line1\nline2".into(), class: vec!["warning".into()], percentage: Some(42), alt: "muted".into() });
+    }
+ 
+    #[test]
+    fn parse_custom_module_output_accepts_class_arrays_numbers_and_last_line()
+    {
+        let output = parse_custom_module_output("{\"text\": \"old\"}\n{\"text\": 7, \"class\": [\"a\", \"\", \"b\"], \"percentage\": 150}\n").unwrap();
+        assert_eq!(output.text, "7");
+        assert_eq!(output.class, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(output.percentage, Some(100));
+        assert_eq!(parse_custom_module_output("plain text"), None);
+        assert_eq!(parse_custom_module_output("[1, 2]"), None);
+    }
+ 
+    #[test]
+    fn json_output_fills_text_and_placeholders()
+    {
+        let mut app = make_json_app();
+        receive_custom_module_output(&mut app, 0, r#"{"text": "Wifi", "percentage": 80, "alt": "on"}"#.into(), true);
+        let module = app.ron_config.custom_module.custom_modules[0].clone();
+        assert_eq!(define_custom_module_text(0, &module, &app), "high Wifi (80% on)");
+    }
+ 
+    #[test]
+    fn non_json_output_of_json_module_falls_back_to_raw_text()
+    {
+        let mut app = make_json_app();
+        receive_custom_module_output(&mut app, 0, "oops\n".into(), true);
+        assert_eq!(app.modules_data.custom_module_data.cached_continuous_outputs[0], "oops");
+        assert_eq!(app.modules_data.custom_module_data.cached_json_outputs[0], CustomModuleOutput { text: "oops".into(), ..Default::default() });
+    }
+ 
+    #[test]
+    fn text_modules_keep_raw_output()
+    {
+        let mut app = AppData { ..Default::default() };
+        app.ron_config.custom_module.custom_modules = vec![CustomModule::default()];
+        receive_custom_module_output(&mut app, 0, r#"{"text": "x"}"#.into(), false);
+        assert_eq!(app.modules_data.custom_module_data.cached_command_outputs[0], r#"{"text": "x"}"#);
+        assert!(app.modules_data.custom_module_data.cached_json_outputs.is_empty());
+    }
+ 
+    #[test]
+    fn custom_module_icon_prefers_alt_then_percentage()
+    {
+        let mut module = make_json_app().ron_config.custom_module.custom_modules[0].clone();
+        module.alt_icons.insert("muted".into(), "M".into());
+        let output = |percentage, alt: &str| CustomModuleOutput { percentage, alt: alt.into(), ..Default::default() };
+        assert_eq!(define_custom_module_icon(&module, &output(Some(0), "")), "low");
+        assert_eq!(define_custom_module_icon(&module, &output(Some(50), "")), "mid");
+        assert_eq!(define_custom_module_icon(&module, &output(Some(100), "")), "high");
+        assert_eq!(define_custom_module_icon(&module, &output(Some(100), "muted")), "M");
+        assert_eq!(define_custom_module_icon(&module, &output(None, "")), "");
+    }
+ 
+    #[test]
+    fn class_styles_override_module_colors_in_order()
+    {
+        let mut module = make_module();
+        module.class_styles.insert("warning".into(), CustomModuleClassStyle { button_color: Some(ColorType::RGB([200, 100, 0])), text_color: Some(ColorType::RGB([0, 0, 0])), ..Default::default() });
+        module.class_styles.insert("critical".into(), CustomModuleClassStyle { button_color: Some(ColorType::RGB([255, 0, 0])), ..Default::default() });
+        let styled = apply_custom_module_classes(&module, &["warning".into(), "critical".into(), "unknown".into()]);
+        assert_eq!(styled.button_color, ColorType::RGB([255, 0, 0]));
+        assert_eq!(styled.text_color, ColorType::RGB([0, 0, 0]));
+        assert_eq!(styled.border_color, module.border_color);
+    }
+ 
+    #[test]
+    fn tooltip_is_empty_when_disabled()
+    {
+        let mut app = make_json_app();
+        receive_custom_module_output(&mut app, 0, r#"{"text": "a", "tooltip": "details"}"#.into(), true);
+        assert_eq!(define_custom_module_tooltip(&app, 0), "details");
+        app.ron_config.custom_module.custom_modules[0].show_tooltip = false;
+        assert_eq!(define_custom_module_tooltip(&app, 0), "");
+    }
+ 
+    // ---- signals ------------------------------------------------------------
+ 
+    fn make_signal_app() -> AppData
+    {
+        let mut app = AppData { ..Default::default() };
+        let polled = CustomModule { continous_command: vec!["date".into()], ..CustomModule::default() };
+        app.ron_config.custom_module.custom_modules = vec!
+        [
+            CustomModule { signal: Some(8), ..polled.clone() },
+            CustomModule { signal: Some(2), ..polled.clone() },
+            CustomModule { signal: Some(8), ..polled.clone() },
+            CustomModule { signal: Some(5), exec_stream: true, ..polled.clone() },
+            CustomModule { signal: Some(9), ..polled.clone() },
+        ];
+        app.modules_data.active_modules = (0..4).map(Modules::CustomModule).collect();
+        app
+    }
+ 
+    #[test]
+    fn custom_module_signals_are_deduplicated_and_skip_streams_and_inactive_modules()
+    {
+        assert_eq!(custom_module_signals(&make_signal_app()), vec![CustomModuleSignal(2), CustomModuleSignal(8)]);
+    }
+ 
+    #[test]
+    fn signaled_custom_modules_returns_every_module_sharing_the_signal()
+    {
+        let app = make_signal_app();
+        assert_eq!(signaled_custom_modules(&app, 8), vec![0, 2]);
+        assert_eq!(signaled_custom_modules(&app, 5), Vec::<usize>::new());
+        assert_eq!(signaled_custom_modules(&app, 9), Vec::<usize>::new());
+    }
+ 
+    // ---- exit code ----------------------------------------------------------
+ 
+    #[test]
+    fn exit_code_placeholder_uses_last_exit_code()
+    {
+        let mut app = AppData { ..Default::default() };
+        app.modules_data.custom_module_data.cached_command_outputs = vec![String::new(), "up".into()];
+        let m = CustomModule { use_output_as_text: true, all_output_as_text_format: "{output} [{exit_code}]".into(), ..make_module() };
+        assert_eq!(define_custom_module_text(1, &m, &app), "up []");
+        receive_custom_module_exit_code(&mut app, 1, Some(3));
+        assert_eq!(app.modules_data.custom_module_data.cached_exit_codes, vec![None, Some(3)]);
+        assert_eq!(define_custom_module_text(1, &m, &app), "up [3]");
+    }
 }
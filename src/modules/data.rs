@@ -1,6 +1,6 @@
 // ============ IMPORTS ============
 use serde::{Serialize, Deserialize};
-use std::collections::{HashSet};
+use std::collections::{HashMap, HashSet};
 use iced::widget::image;
 
 
@@ -8,7 +8,8 @@ use iced::widget::image;
 
 
 // ============ CRATES ============
-use crate::modules::{image::ImageData, custom_modules::CustomModuleData, disk::DiskData, clock::ClockData, cpu::CpuData, cpu_temp::CpuTempData, focused_window::FocusedWindowData, media_player::MediaPlayerData, network::NetworkData, ram::RamData, volume::VolumeData, workspaces::WorkspaceData};
+use crate::modules::{image::ImageData, binding_mode::BindingModeData, custom_modules::CustomModuleData, disk::DiskData, clock::ClockData, cpu::CpuData, cpu_temp::CpuTempData, focused_window::FocusedWindowData, media_player::MediaPlayerData, network::NetworkData, ram::RamData, taskbar::TaskbarData, volume::VolumeData, workspaces::WorkspaceData};
+use crate::helpers::bindings::BindingData;
 
 
 
@@ -20,6 +21,8 @@ pub enum Modules
 {
     Image(usize),
     CustomModule(usize),
+    Workspaces,
+    FocusedWindow,
     HyprWorkspaces,
     NiriWorkspaces,
     SwayWorkspaces,
@@ -33,6 +36,8 @@ pub enum Modules
     Network,
     CpuTemp,
     Clock,
+    BindingMode,
+    Taskbar,
     Tray,
     Disk,
     Cpu,
@@ -43,13 +48,20 @@ pub enum Modules
 pub struct ModulesData
 {
     pub tray_icons: Vec<(Option<image::Handle>, String)>,
+    /// Application icons by `app_id` / class, `None` while resolving or when nothing was found.
+    pub app_icons: HashMap<String, Option<image::Handle>>,
     pub focused_window_data: FocusedWindowData,
+    pub binding_mode_data: BindingModeData,
+    pub binding_data: BindingData,
     pub custom_module_data: CustomModuleData,
     pub media_player_data: MediaPlayerData,
     pub workspace_data: WorkspaceData,
     pub active_modules: HashSet<Modules>,
+    /// Off half of the blink cycle of blinking state tiers, where the module shows its own style.
+    pub state_blink_off: bool,
     pub cpu_temp_data: CpuTempData,
     pub network_data: NetworkData,
+    pub taskbar_data: TaskbarData,
     pub volume_data: VolumeData,
     pub clock_data: ClockData,
     pub image_data: ImageData,
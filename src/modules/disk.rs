@@ -1,13 +1,14 @@
 // ============ IMPORTS ============
 use iced::widget::button;
-use std::mem;
+use std::{collections::HashMap, mem};
 
 
 
 
 
 // ============ CRATES ============
-use crate::helpers::style::{orient_text, UserStyle, set_style};
+use crate::helpers::style::{orient_text, UserStyle, apply_state_style, module_state, set_style, state_format};
+use crate::modules::data::Modules;
 use crate::AppData;
 
 
@@ -18,7 +19,7 @@ use crate::AppData;
 
 // ============ CONFIG ============
 use serde::{Deserialize, Serialize};
-use crate::helpers::style::{TextOrientation, SideOption};
+use crate::helpers::style::{StateStyle, TextOrientation, SideOption};
 use crate::helpers::color::{ColorType, Gradient};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -51,6 +52,7 @@ pub struct DiskConfig
     pub disk_button_shadow_x:               f32,
     pub disk_button_shadow_y:               f32,
     pub disk_button_shadow_blur:            f32,
+    pub disk_states:                        HashMap<String, StateStyle>,
 }
 
 impl Default for DiskConfig
@@ -85,6 +87,7 @@ impl Default for DiskConfig
             disk_button_shadow_x:               0.0,
             disk_button_shadow_y:               0.0,
             disk_button_shadow_blur:            0.0,
+            disk_states:                        HashMap::new(),
         }
     }
 }
@@ -135,7 +138,7 @@ pub fn define_disk_text(app: &AppData) -> String
 {
     let data = &app.modules_data.disk_data;
 
-    let text = app.ron_config.disk.disk_format
+    let text = state_format(&app.ron_config.disk.disk_format, module_state(app, &Modules::Disk))
         .replace("{total}",   &(data.total   / 1_073_741_824).to_string())
         .replace("{free}",    &(data.free    / 1_073_741_824).to_string())
         .replace("{used}",    &(data.used    / 1_073_741_824).to_string()) 
@@ -148,7 +151,7 @@ pub fn define_disk_text(app: &AppData) -> String
 
 pub fn define_disk_style(app: &AppData, status: button::Status) -> iced::widget::button::Style
 {
-    set_style(UserStyle
+    set_style(apply_state_style(UserStyle
     {
         status,
         normal:            app.ron_config.disk.disk_button_color,
@@ -167,7 +170,7 @@ pub fn define_disk_style(app: &AppData, status: button::Status) -> iced::widget:
         shadow_x:     app.ron_config.disk.disk_button_shadow_x,
         shadow_y:     app.ron_config.disk.disk_button_shadow_y,
         shadow_blur:  app.ron_config.disk.disk_button_shadow_blur,
-    })
+    }, module_state(app, &Modules::Disk), app.modules_data.state_blink_off))
 }
 
 
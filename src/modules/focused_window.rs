@@ -1,6 +1,7 @@
 // ============ IMPORTS ============
-use hyprland::shared::HyprDataActiveOptional;
+use hyprland::{data::FullscreenMode, shared::HyprDataActiveOptional};
 use iced::widget::button;
+use regex::Regex;
 
 
 
@@ -8,6 +9,8 @@ use iced::widget::button;
 
 // ============ CRATES ============
 use crate::helpers::style::{UserStyle, orient_text, set_style};
+use crate::helpers::icons::request_app_icons;
+use crate::update::Message;
 use crate::AppData;
 
 
@@ -26,6 +29,12 @@ use crate::helpers::color::{ColorType, Gradient};
 pub struct FocusedWindowConfig
 {
     pub focused_window_format:                        String,
+    pub focused_window_floating_text:                 String,
+    pub focused_window_fullscreen_text:               String,
+    pub focused_window_rewrite_rules:                 Vec<TitleRewriteRule>,
+    pub focused_window_show_icon:                     bool,
+    pub focused_window_icon_size:                     u32,
+    pub focused_window_icon_spacing:                  u32,
     pub focused_window_update_interval:               u64,
     pub dont_show_focused_window_if_empty:            bool,
     pub text_when_focused_window_is_empty:            String,
@@ -62,6 +71,12 @@ impl Default for FocusedWindowConfig
         Self
         {
             focused_window_format:                        "{title}".into(),
+            focused_window_floating_text:                 "floating".into(),
+            focused_window_fullscreen_text:               "fullscreen".into(),
+            focused_window_rewrite_rules:                 Vec::new(),
+            focused_window_show_icon:                     false,
+            focused_window_icon_size:                     16,
+            focused_window_icon_spacing:                  5,
             focused_window_update_interval:               500,
             dont_show_focused_window_if_empty:            false,
             text_when_focused_window_is_empty:            "No Window Focused".into(),
@@ -93,11 +108,70 @@ impl Default for FocusedWindowConfig
     }
 }
 
+/// Replaces every match of `pattern` in the title with `replacement` (`$1` style groups work),
+/// only for windows whose app id or class is `app_id` when it is set.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TitleRewriteRule
+{
+    pub app_id:      Option<String>,
+    pub pattern:     TitleRewritePattern,
+    pub replacement: String,
+}
+
+/// A rewrite pattern compiled once when the config is parsed, an invalid one is reported there and left without a regex so its rule is skipped.
+#[derive(Clone, Debug, Default)]
+pub struct TitleRewritePattern
+{
+    pub source: String,
+    pub regex: Option<Regex>,
+}
+
+impl TitleRewritePattern
+{
+    pub fn new(source: &str) -> Self
+    {
+        let regex = Regex::new(source).map_err(|e| eprintln!("[icebar] invalid focused window rewrite pattern {source:?}: {e}")).ok();
+        Self { source: source.to_string(), regex }
+    }
+}
+
+impl From<&str> for TitleRewritePattern
+{
+    fn from(source: &str) -> Self
+    {
+        Self::new(source)
+    }
+}
+
+impl Serialize for TitleRewritePattern
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for TitleRewritePattern
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+    {
+        Ok(Self::new(&String::deserialize(deserializer)?))
+    }
+}
+
 // ============ STRUCTS ============
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug, PartialEq)]
 pub struct FocusedWindowData
 {
     pub title: String,
+    pub app_id: String,
+    /// X11 class for XWayland windows (Hyprland's class for every window), the app id otherwise.
+    pub class: String,
+    pub pid: Option<i32>,
+    pub workspace: String,
+    pub floating: bool,
+    pub fullscreen: bool,
 }
 
 
@@ -105,75 +179,184 @@ pub struct FocusedWindowData
 
 
 // ============ FUNCTIONS ============
-pub fn read_focused_window_hypr() -> Option<String>
+pub fn read_focused_window_hypr() -> Option<FocusedWindowData>
 {
     let client = hyprland::data::Client::get_active().ok()??;
-    Some(client.title)
+    Some(FocusedWindowData
+    {
+        title:      client.title,
+        app_id:     client.class.clone(),
+        class:      client.class,
+        pid:        Some(client.pid),
+        workspace:  client.workspace.name,
+        floating:   client.floating,
+        fullscreen: !matches!(client.fullscreen, FullscreenMode::None),
+    })
 }
 
 
 
-pub fn read_focused_window_sway() -> Option<String>
+pub fn read_focused_window_sway() -> Option<FocusedWindowData>
 {
-    let mut conn = swayipc::Connection::new().ok()?;
-    let tree     = conn.get_tree().ok()?;
-    find_focused_sway(&tree)
+    let mut conn  = swayipc::Connection::new().ok()?;
+    let tree      = conn.get_tree().ok()?;
+    let workspace = conn.get_workspaces().ok()?.into_iter().find(|workspace| workspace.focused).map(|workspace| workspace.name).unwrap_or_default();
+    find_focused_sway_node(&tree).map(|node| sway_window_data(node, workspace))
+}
+
+
+fn sway_window_data(node: &swayipc::Node, workspace: String) -> FocusedWindowData
+{
+    let class  = node.window_properties.as_ref().and_then(|properties| properties.class.clone());
+    let app_id = node.app_id.clone().or_else(|| class.clone()).unwrap_or_default();
+    FocusedWindowData
+    {
+        title:      node.name.clone().unwrap_or_default(),
+        class:      class.unwrap_or_else(|| app_id.clone()),
+        app_id,
+        pid:        node.pid,
+        workspace,
+        floating:   node.node_type == swayipc::NodeType::FloatingCon,
+        fullscreen: node.fullscreen_mode.is_some_and(|mode| mode != 0),
+    }
 }
 
 
-fn find_focused_sway(node: &swayipc::Node) -> Option<String>
+fn find_focused_sway_node(node: &swayipc::Node) -> Option<&swayipc::Node>
 {
     use swayipc::NodeType;
 
     if node.focused
     {
-        // Only return a title for actual windows, not workspaces/outputs
+        // Only return actual windows, not workspaces/outputs
         return match node.node_type
         {
-            NodeType::Con | NodeType::FloatingCon => node.name.clone(),
+            NodeType::Con | NodeType::FloatingCon => Some(node),
             _                                     => None,
         };
     }
     for child in &node.nodes
     {
-        if let Some(title) = find_focused_sway(child) { return Some(title); }
+        if let Some(found) = find_focused_sway_node(child) { return Some(found); }
     }
     for child in &node.floating_nodes
     {
-        if let Some(title) = find_focused_sway(child) { return Some(title); }
+        if let Some(found) = find_focused_sway_node(child) { return Some(found); }
     }
     None
 }
 
 
-pub fn read_focused_window_niri() -> Option<String>
+pub fn read_focused_window_niri() -> Option<FocusedWindowData>
 {
     use niri_ipc::{Request, Response, socket::Socket};
     let mut socket = Socket::connect().ok()?;
     let reply      = socket.send(Request::FocusedWindow).ok()?;
-    match reply
+    let window     = match reply
+    {
+        Ok(Response::FocusedWindow(Some(w))) => w,
+        _=> return None,
+    };
+    // niri has no X11 class nor fullscreen flag, the workspace name needs a second request
+    let workspace = window.workspace_id.and_then(|id|
+    {
+        let mut socket = Socket::connect().ok()?;
+        match socket.send(Request::Workspaces).ok()?
+        {
+            Ok(Response::Workspaces(workspaces)) => workspaces.into_iter().find(|workspace| workspace.id == id).map(|workspace| workspace.name.unwrap_or_else(|| workspace.idx.to_string())),
+            _ => None,
+        }
+    });
+    let app_id = window.app_id.unwrap_or_default();
+    Some(FocusedWindowData { title: window.title.unwrap_or_default(), class: app_id.clone(), app_id, pid: window.pid, workspace: workspace.unwrap_or_default(), floating: window.is_floating, fullscreen: false })
+}
+
+
+
+/// Applies the rules in order, a rule whose pattern failed to compile is skipped.
+pub fn rewrite_focused_window_title(window: &FocusedWindowData, rules: &[TitleRewriteRule]) -> String
+{
+    let mut title = window.title.clone();
+    for rule in rules
+    {
+        if let Some(app_id) = &rule.app_id && !app_id.eq_ignore_ascii_case(&window.app_id) && !app_id.eq_ignore_ascii_case(&window.class) { continue; }
+        let Some(regex) = &rule.pattern.regex else { continue; };
+        title = regex.replace_all(&title, rule.replacement.as_str()).into_owned();
+    }
+    title
+}
+
+
+
+/// Stores the fetched window with its title rewritten, and looks up its icon when `focused_window_show_icon` is set.
+pub fn apply_fetched_focused_window(app: &mut AppData, window: Option<FocusedWindowData>) -> iced::Task<Message>
+{
+    let mut window = window.unwrap_or_default();
+    window.title = rewrite_focused_window_title(&window, &app.ron_config.focused_window.focused_window_rewrite_rules);
+    let app_id = window.app_id.clone();
+    app.modules_data.focused_window_data = window;
+    if !app.ron_config.focused_window.focused_window_show_icon || app_id.is_empty() { return iced::Task::none(); }
+    let size = app.ron_config.focused_window.focused_window_icon_size;
+    request_app_icons(app, vec![app_id], size)
+}
+
+
+
+/// Fills every placeholder in one pass, so placeholders inside the window's own title are left alone.
+pub fn fill_focused_window_placeholders(format: &str, window: &FocusedWindowData, floating_text: &str, fullscreen_text: &str) -> String
+{
+    let mut result = String::with_capacity(format.len());
+    let mut rest = format;
+    while let Some(start) = rest.find('{')
     {
-        Ok(Response::FocusedWindow(Some(w))) => w.title,
-        _=> None,
+        result.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let value = after.find('}').and_then(|end| match &after[1..end]
+        {
+            "title"      => Some((window.title.clone(), end)),
+            "app_id"     => Some((window.app_id.clone(), end)),
+            "class"      => Some((window.class.clone(), end)),
+            "pid"        => Some((window.pid.map(|pid| pid.to_string()).unwrap_or_default(), end)),
+            "workspace"  => Some((window.workspace.clone(), end)),
+            "floating"   => Some((if window.floating { floating_text.to_string() } else { String::new() }, end)),
+            "fullscreen" => Some((if window.fullscreen { fullscreen_text.to_string() } else { String::new() }, end)),
+            _            => None,
+        });
+        match value
+        {
+            Some((value, end)) => { result.push_str(&value); rest = &after[end + 1..]; }
+            None => { result.push('{'); rest = &after[1..]; }
+        }
     }
+    result.push_str(rest);
+    result
 }
 
 
 
 pub fn define_focused_window_text(app: &AppData) -> String
 {
-    let title = &app.modules_data.focused_window_data.title;
-    if title.is_empty()
+    let window = &app.modules_data.focused_window_data;
+    if window.title.is_empty()
     {
         if app.ron_config.focused_window.dont_show_focused_window_if_empty { return String::new(); };
         return orient_text(&app.ron_config.focused_window.text_when_focused_window_is_empty, &app.ron_config.focused_window.focused_window_text_orientation);
     };
-    let text  = app.ron_config.focused_window.focused_window_format.replace("{title}", title);
+    let config = &app.ron_config.focused_window;
+    let text  = fill_focused_window_placeholders(&config.focused_window_format, window, &config.focused_window_floating_text, &config.focused_window_fullscreen_text);
     orient_text(&text, &app.ron_config.focused_window.focused_window_text_orientation)
 }
 
 
 
+pub fn define_focused_window_icon(app: &AppData) -> Option<iced::widget::image::Handle>
+{
+    if !app.ron_config.focused_window.focused_window_show_icon { return None; }
+    app.modules_data.app_icons.get(&app.modules_data.focused_window_data.app_id).cloned().flatten()
+}
+
+
+
 pub fn define_focused_window_style(app: &AppData, status: button::Status) -> iced::widget::button::Style
 {
     set_style(UserStyle
@@ -209,6 +392,7 @@ mod tests
     use crate::AppData;
  
     // ---- helpers ------------------------------------------------------------
+
     fn make_node(name: Option<&str>, focused: bool, nodes: Vec<swayipc::Node>, floating: Vec<swayipc::Node>) -> swayipc::Node
     {
         let name_val = match name
@@ -262,21 +446,21 @@ mod tests
     fn find_focused_sway_returns_title_of_focused_node()
     {
         let node = make_node(Some("My Window"), true, vec![], vec![]);
-        assert_eq!(find_focused_sway(&node), Some("My Window".into()));
+        assert_eq!(find_focused_sway_node(&node).and_then(|found| found.name.as_deref()), Some("My Window"));
     }
  
     #[test]
     fn find_focused_sway_unfocused_root_returns_none()
     {
         let node = make_node(Some("Root"), false, vec![], vec![]);
-        assert!(find_focused_sway(&node).is_none());
+        assert!(find_focused_sway_node(&node).is_none());
     }
  
     #[test]
     fn find_focused_sway_focused_node_with_no_name_returns_none()
     {
         let node = make_node(None, true, vec![], vec![]);
-        assert!(find_focused_sway(&node).is_none());
+        assert!(find_focused_sway_node(&node).and_then(|found| found.name.as_deref()).is_none());
     }
  
     #[test]
@@ -284,7 +468,7 @@ mod tests
     {
         let child  = make_node(Some("Child"), true,  vec![], vec![]);
         let parent = make_node(None,          false, vec![child], vec![]);
-        assert_eq!(find_focused_sway(&parent), Some("Child".into()));
+        assert_eq!(find_focused_sway_node(&parent).and_then(|found| found.name.as_deref()), Some("Child"));
     }
  
     #[test]
@@ -293,7 +477,7 @@ mod tests
         let leaf = make_node(Some("Deep"), true,  vec![], vec![]);
         let mid  = make_node(None,         false, vec![leaf], vec![]);
         let root = make_node(None,         false, vec![mid],  vec![]);
-        assert_eq!(find_focused_sway(&root), Some("Deep".into()));
+        assert_eq!(find_focused_sway_node(&root).and_then(|found| found.name.as_deref()), Some("Deep"));
     }
  
     #[test]
@@ -302,7 +486,7 @@ mod tests
         let a    = make_node(Some("A"), false, vec![], vec![]);
         let b    = make_node(Some("B"), false, vec![], vec![]);
         let root = make_node(None,      false, vec![a, b], vec![]);
-        assert!(find_focused_sway(&root).is_none());
+        assert!(find_focused_sway_node(&root).is_none());
     }
  
     #[test]
@@ -311,7 +495,7 @@ mod tests
         let first  = make_node(Some("First"),  true, vec![], vec![]);
         let second = make_node(Some("Second"), true, vec![], vec![]);
         let root   = make_node(None, false, vec![first, second], vec![]);
-        assert_eq!(find_focused_sway(&root), Some("First".into()));
+        assert_eq!(find_focused_sway_node(&root).and_then(|found| found.name.as_deref()), Some("First"));
     }
  
     #[test]
@@ -319,7 +503,7 @@ mod tests
     {
         let floating = make_node(Some("Float"), true, vec![], vec![]);
         let root     = make_node(None, false, vec![], vec![floating]);
-        assert_eq!(find_focused_sway(&root), Some("Float".into()));
+        assert_eq!(find_focused_sway_node(&root).and_then(|found| found.name.as_deref()), Some("Float"));
     }
  
     #[test]
@@ -329,7 +513,7 @@ mod tests
         let regular  = make_node(Some("Regular"),  true, vec![], vec![]);
         let floating = make_node(Some("Floating"), true, vec![], vec![]);
         let root     = make_node(None, false, vec![regular], vec![floating]);
-        assert_eq!(find_focused_sway(&root), Some("Regular".into()));
+        assert_eq!(find_focused_sway_node(&root).and_then(|found| found.name.as_deref()), Some("Regular"));
     }
  
     #[test]
@@ -338,14 +522,14 @@ mod tests
         let regular  = make_node(Some("Regular"),  false, vec![], vec![]);
         let floating = make_node(Some("Floating"), true,  vec![], vec![]);
         let root     = make_node(None, false, vec![regular], vec![floating]);
-        assert_eq!(find_focused_sway(&root), Some("Floating".into()));
+        assert_eq!(find_focused_sway_node(&root).and_then(|found| found.name.as_deref()), Some("Floating"));
     }
  
     #[test]
     fn find_focused_sway_empty_tree_returns_none()
     {
         let root = make_node(None, false, vec![], vec![]);
-        assert!(find_focused_sway(&root).is_none());
+        assert!(find_focused_sway_node(&root).is_none());
     }
  
     // ---- define_focused_window_text -----------------------------------------
@@ -431,21 +615,21 @@ mod tests
     fn find_focused_sway_unicode_title()
     {
         let node = make_node(Some("日本語ウィンドウ"), true, vec![], vec![]);
-        assert_eq!(find_focused_sway(&node), Some("日本語ウィンドウ".into()));
+        assert_eq!(find_focused_sway_node(&node).and_then(|found| found.name.as_deref()), Some("日本語ウィンドウ"));
     }
 
     #[test]
     fn find_focused_sway_title_with_spaces()
     {
         let node = make_node(Some("My Text Editor"), true, vec![], vec![]);
-        assert_eq!(find_focused_sway(&node), Some("My Text Editor".into()));
+        assert_eq!(find_focused_sway_node(&node).and_then(|found| found.name.as_deref()), Some("My Text Editor"));
     }
 
     #[test]
     fn find_focused_sway_title_with_special_chars()
     {
         let node = make_node(Some("file.rs — VSCode"), true, vec![], vec![]);
-        assert_eq!(find_focused_sway(&node), Some("file.rs — VSCode".into()));
+        assert_eq!(find_focused_sway_node(&node).and_then(|found| found.name.as_deref()), Some("file.rs — VSCode"));
     }
 
     #[test]
@@ -453,7 +637,7 @@ mod tests
     {
         // Whitespace is a valid name string — we don't trim it
         let node = make_node(Some("   "), true, vec![], vec![]);
-        assert_eq!(find_focused_sway(&node), Some("   ".into()));
+        assert_eq!(find_focused_sway_node(&node).and_then(|found| found.name.as_deref()), Some("   "));
     }
 
     #[test]
@@ -463,7 +647,7 @@ mod tests
         let mid1  = make_node(None,          false, vec![leaf], vec![]);
         let mid2  = make_node(None,          false, vec![mid1], vec![]);
         let root  = make_node(None,          false, vec![mid2], vec![]);
-        assert_eq!(find_focused_sway(&root), Some("Leaf".into()));
+        assert_eq!(find_focused_sway_node(&root).and_then(|found| found.name.as_deref()), Some("Leaf"));
     }
 
     #[test]
@@ -472,7 +656,7 @@ mod tests
         // Root itself is focused — should return root's title without descending
         let child = make_node(Some("Child"), false, vec![], vec![]);
         let root  = make_node(Some("Root"),  true,  vec![child], vec![]);
-        assert_eq!(find_focused_sway(&root), Some("Root".into()));
+        assert_eq!(find_focused_sway_node(&root).and_then(|found| found.name.as_deref()), Some("Root"));
     }
 
     #[test]
@@ -481,7 +665,7 @@ mod tests
         let first  = make_node(Some("First"),  false, vec![], vec![]);
         let second = make_node(Some("Second"), true,  vec![], vec![]);
         let root   = make_node(None, false, vec![first, second], vec![]);
-        assert_eq!(find_focused_sway(&root), Some("Second".into()));
+        assert_eq!(find_focused_sway_node(&root).and_then(|found| found.name.as_deref()), Some("Second"));
     }
 
     #[test]
@@ -490,7 +674,7 @@ mod tests
         let f1   = make_node(Some("Float1"), true,  vec![], vec![]);
         let f2   = make_node(Some("Float2"), false, vec![], vec![]);
         let root = make_node(None, false, vec![], vec![f1, f2]);
-        assert_eq!(find_focused_sway(&root), Some("Float1".into()));
+        assert_eq!(find_focused_sway_node(&root).and_then(|found| found.name.as_deref()), Some("Float1"));
     }
 
     #[test]
@@ -499,7 +683,7 @@ mod tests
         let deep     = make_node(Some("Deep Float"), true,  vec![], vec![]);
         let floating = make_node(None,               false, vec![deep], vec![]);
         let root     = make_node(None, false, vec![], vec![floating]);
-        assert_eq!(find_focused_sway(&root), Some("Deep Float".into()));
+        assert_eq!(find_focused_sway_node(&root).and_then(|found| found.name.as_deref()), Some("Deep Float"));
     }
 
     #[test]
@@ -507,7 +691,7 @@ mod tests
     {
         // Some("") is different from None — we return it
         let node = make_node(Some(""), true, vec![], vec![]);
-        assert_eq!(find_focused_sway(&node), Some("".into()));
+        assert_eq!(find_focused_sway_node(&node).and_then(|found| found.name.as_deref()), Some(""));
     }
 
     // ---- define_focused_window_text: extra cases ----------------------------
@@ -593,7 +777,7 @@ mod tests
     #[test]
     fn focused_window_data_clone_is_independent()
     {
-        let mut original = FocusedWindowData { title: "Firefox".into() };
+        let mut original = FocusedWindowData { title: "Firefox".into(), ..Default::default() };
         let clone        = original.clone();
         original.title   = "Vim".into();
         assert_eq!(clone.title, "Firefox");
@@ -602,14 +786,14 @@ mod tests
     #[test]
     fn focused_window_data_title_can_be_set_and_read()
     {
-        let data = FocusedWindowData { title: "Alacritty".into() };
+        let data = FocusedWindowData { title: "Alacritty".into(), ..Default::default() };
         assert_eq!(data.title, "Alacritty");
     }
 
     #[test]
     fn focused_window_data_title_unicode()
     {
-        let data = FocusedWindowData { title: "ターミナル".into() };
+        let data = FocusedWindowData { title: "ターミナル".into(), ..Default::default() };
         assert_eq!(data.title, "ターミナル");
     }
 
@@ -782,4 +966,80 @@ mod tests
         app.ron_config.focused_window.focused_window_text_orientation    = TextOrientation::Horizontal;
         assert!(!define_focused_window_text(&app).contains('\n'));
     }
+
+    // ---- placeholders -------------------------------------------------------
+
+    fn firefox() -> FocusedWindowData
+    {
+        FocusedWindowData { title: "Docs — Mozilla Firefox".into(), app_id: "firefox".into(), class: "firefox".into(), pid: Some(42), workspace: "web".into(), floating: true, fullscreen: false }
+    }
+
+    #[test]
+    fn placeholders_fill_every_field()
+    {
+        let text = fill_focused_window_placeholders("{app_id}|{class}|{pid}|{workspace}|{floating}|{fullscreen}", &firefox(), "F", "FS");
+        assert_eq!(text, "firefox|firefox|42|web|F|");
+    }
+
+    #[test]
+    fn placeholders_keep_unknown_and_unclosed_braces()
+    {
+        assert_eq!(fill_focused_window_placeholders("{nope} {pid", &firefox(), "", ""), "{nope} {pid");
+        assert_eq!(fill_focused_window_placeholders("{{pid}}", &firefox(), "", ""), "{42}");
+    }
+
+    #[test]
+    fn placeholders_inside_title_are_not_expanded()
+    {
+        let window = FocusedWindowData { title: "{app_id}".into(), app_id: "kitty".into(), ..Default::default() };
+        assert_eq!(fill_focused_window_placeholders("{title} {app_id}", &window, "", ""), "{app_id} kitty");
+    }
+
+    #[test]
+    fn missing_pid_is_empty()
+    {
+        assert_eq!(fill_focused_window_placeholders("[{pid}]", &FocusedWindowData::default(), "", ""), "[]");
+    }
+
+    // ---- rewrite rules ------------------------------------------------------
+
+    #[test]
+    fn rewrite_rule_strips_suffix_for_matching_app()
+    {
+        let rules = vec![TitleRewriteRule { app_id: Some("Firefox".into()), pattern: " — Mozilla Firefox$".into(), replacement: String::new() }];
+        assert_eq!(rewrite_focused_window_title(&firefox(), &rules), "Docs");
+        let other = FocusedWindowData { app_id: "kitty".into(), ..firefox() };
+        assert_eq!(rewrite_focused_window_title(&other, &rules), "Docs — Mozilla Firefox");
+    }
+
+    #[test]
+    fn rewrite_rules_apply_in_order_with_groups_and_skip_invalid()
+    {
+        let rules = vec![
+            TitleRewriteRule { app_id: None, pattern: "(".into(), replacement: String::new() },
+            TitleRewriteRule { app_id: None, pattern: "^(\\w+) — (.*)$".into(), replacement: "$2: $1".into() },
+        ];
+        assert_eq!(rewrite_focused_window_title(&firefox(), &rules), "Mozilla Firefox: Docs");
+    }
+
+    #[test]
+    fn rewrite_pattern_is_compiled_once_when_parsed()
+    {
+        let rule: TitleRewriteRule = ron::from_str("(pattern: \" — (.*)$\", replacement: \"\")").unwrap();
+        assert!(rule.pattern.regex.is_some());
+        assert_eq!(rule.pattern.source, " — (.*)$");
+        assert!(TitleRewritePattern::from("(").regex.is_none());
+    }
+
+    #[test]
+    fn fetched_window_is_stored_rewritten()
+    {
+        let mut app = make_app("");
+        app.ron_config.focused_window.focused_window_rewrite_rules = vec![TitleRewriteRule { app_id: None, pattern: " — Mozilla Firefox".into(), replacement: String::new() }];
+        let _ = apply_fetched_focused_window(&mut app, Some(firefox()));
+        assert_eq!(app.modules_data.focused_window_data.title, "Docs");
+        assert_eq!(app.modules_data.focused_window_data.pid, Some(42));
+        let _ = apply_fetched_focused_window(&mut app, None);
+        assert_eq!(app.modules_data.focused_window_data, FocusedWindowData::default());
+    }
 }
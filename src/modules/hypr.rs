@@ -1,26 +1,46 @@
-use std::pin::Pin;
+use std::{collections::{HashMap, HashSet}, pin::Pin, sync::Mutex};
 
 // ============ IMPORTS ============
-use hyprland::{data::{Workspace, Workspaces}, dispatch::*, event_listener::EventListener, prelude::*};
+use hyprland::{data::{Client, Clients, Monitors, Workspace, Workspaces}, dispatch::*, event_listener::EventListener, prelude::*, shared::Address};
 
 
 
 
 
 // ============ CRATES ============
-use crate::{modules::workspaces::UserWorkspaceAction, update::Message};
+use crate::{modules::{taskbar::{TaskbarAction, TaskbarSnapshot, TaskbarWindow}, workspaces::{UserWorkspaceAction, WorkspaceInfo}}, update::Message};
+
+
+
+
+
+// ============ STATICS ============
+/// Addresses of the windows that asked for attention and weren't focused since.
+static URGENT_WINDOWS: Mutex<Option<HashSet<String>>> = Mutex::new(None);
 
 
 
 
 
 // ============ FUNCTIONS ============
+fn set_window_urgent(address: String, urgent: bool)
+{
+    if let Ok(mut guard) = URGENT_WINDOWS.lock()
+    {
+        let set = guard.get_or_insert_with(HashSet::new);
+        if urgent { set.insert(address); } else { set.remove(&address); }
+    }
+}
+
+
+
 pub fn hypr_event_subscription() -> Pin<Box<dyn futures::Stream<Item = Message> + Send>>
 {
     Box::pin(async_stream::stream!
     {
-        yield Message::UpdateHyprWorkspaces;
-        yield Message::UpdateFocusedWindowHypr;
+        yield Message::UpdateWorkspaces;
+        yield Message::UpdateFocusedWindow;
+        yield Message::UpdateTaskbar;
         loop
         {
             let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
@@ -36,35 +56,64 @@ pub fn hypr_event_subscription() -> Pin<Box<dyn futures::Stream<Item = Message>
  
                 // ── workspace events ──────────────────────────────────────
                 let t = tx_ws.clone();
-                listener.add_workspace_changed_handler(move |_| { let _ = t.send(Message::UpdateHyprWorkspaces); });
+                listener.add_workspace_changed_handler(move |_| { let _ = t.send(Message::UpdateWorkspaces); let _ = t.send(Message::UpdateTaskbar); });
+ 
+                let t = tx_ws.clone();
+                listener.add_workspace_added_handler(move |_| { let _ = t.send(Message::UpdateWorkspaces); });
  
                 let t = tx_ws.clone();
-                listener.add_workspace_added_handler(move |_| { let _ = t.send(Message::UpdateHyprWorkspaces); });
+                listener.add_workspace_deleted_handler(move |_| { let _ = t.send(Message::UpdateWorkspaces); });
  
                 let t = tx_ws.clone();
-                listener.add_workspace_deleted_handler(move |_| { let _ = t.send(Message::UpdateHyprWorkspaces); });
+                listener.add_workspace_moved_handler(move |_| { let _ = t.send(Message::UpdateWorkspaces); });
  
                 let t = tx_ws.clone();
-                listener.add_workspace_moved_handler(move |_| { let _ = t.send(Message::UpdateHyprWorkspaces); });
+                listener.add_workspace_renamed_handler(move |_| { let _ = t.send(Message::UpdateWorkspaces); });
  
+                // ── urgency events ────────────────────────────────────────
                 let t = tx_ws.clone();
-                listener.add_workspace_renamed_handler(move |_| { let _ = t.send(Message::UpdateHyprWorkspaces); });
+                listener.add_urgent_state_changed_handler(move |address| 
+                { 
+                    set_window_urgent(address.to_string(), true);
+                    let _ = t.send(Message::UpdateWorkspaces); 
+                    let _ = t.send(Message::UpdateTaskbar); 
+                });
  
                 // ── focused window events ─────────────────────────────────
                 let t = tx_win.clone();
-                listener.add_active_window_changed_handler(move |_| { let _ = t.send(Message::UpdateFocusedWindowHypr); });
+                listener.add_active_window_changed_handler(move |data| 
+                { 
+                    // Focusing a window answers its urgency request
+                    if let Some(data) = data 
+                    { 
+                        set_window_urgent(data.address.to_string(), false);
+                        let _ = t.send(Message::UpdateWorkspaces);
+                    }
+                    let _ = t.send(Message::UpdateFocusedWindow); 
+                    let _ = t.send(Message::UpdateTaskbar); 
+                });
+ 
+                let t = tx_win.clone();
+                listener.add_window_opened_handler(move |_| { let _ = t.send(Message::UpdateWorkspaces); let _ = t.send(Message::UpdateFocusedWindow); let _ = t.send(Message::UpdateTaskbar); });
  
                 let t = tx_win.clone();
-                listener.add_window_opened_handler(move |_| { let _ = t.send(Message::UpdateFocusedWindowHypr); });
+                listener.add_window_closed_handler(move |address| 
+                { 
+                    set_window_urgent(address.to_string(), false);
+                    let _ = t.send(Message::UpdateWorkspaces); 
+                    let _ = t.send(Message::UpdateFocusedWindow); 
+                    let _ = t.send(Message::UpdateTaskbar); 
+                });
  
                 let t = tx_win.clone();
-                listener.add_window_closed_handler(move |_| { let _ = t.send(Message::UpdateFocusedWindowHypr); });
+                listener.add_window_moved_handler(move |_| { let _ = t.send(Message::UpdateWorkspaces); let _ = t.send(Message::UpdateFocusedWindow); let _ = t.send(Message::UpdateTaskbar); });
  
                 let t = tx_win.clone();
-                listener.add_window_moved_handler(move |_| { let _ = t.send(Message::UpdateFocusedWindowHypr); });
+                listener.add_window_title_changed_handler(move |_| { let _ = t.send(Message::UpdateFocusedWindow); let _ = t.send(Message::UpdateTaskbar); });
  
+                // ── submap events ─────────────────────────────────────────
                 let t = tx_win.clone();
-                listener.add_window_title_changed_handler(move |_| { let _ = t.send(Message::UpdateFocusedWindowHypr); });
+                listener.add_sub_map_changed_handler(move |submap| { let _ = t.send(Message::BindingModeChanged(submap)); });
  
                 // Blocks until the compositor socket closes
                 if let Err(e) = listener.start_listener()
@@ -86,12 +135,32 @@ pub fn hypr_event_subscription() -> Pin<Box<dyn futures::Stream<Item = Message>
  
 
 
-pub fn workspace_count() -> Vec<i32>
+pub fn workspace_count() -> Vec<WorkspaceInfo>
 { 
     let result_workspaces = Workspaces::get();
     if let Ok(all_workspaces) = result_workspaces
     {
-        all_workspaces.iter().map(|item| item.id).collect()
+        let active_id = Workspace::get_active().ok().map(|workspace| workspace.id);
+        let visible_ids: Vec<i32> = Monitors::get().map(|monitors| monitors.iter().flat_map(|monitor| [monitor.active_workspace.id, monitor.special_workspace.id]).collect()).unwrap_or_default();
+        let urgent_addresses = URGENT_WINDOWS.lock().ok().and_then(|guard| guard.clone()).unwrap_or_default();
+        let clients = Clients::get().map(|clients| clients.to_vec()).unwrap_or_default();
+        let client_workspaces: Vec<(String, i32)> = clients.iter().map(|client| (client.address.to_string(), client.workspace.id)).collect();
+        let urgent_per_workspace = count_urgent_per_workspace(&urgent_addresses, &client_workspaces);
+        let classes_on = |workspace_id: i32| -> Vec<String> { clients.iter().filter(|client| client.workspace.id == workspace_id).map(|client| client.class.clone()).collect() };
+        all_workspaces.iter().map(|item| WorkspaceInfo
+        {
+            id: item.id,
+            number: item.id,
+            name: hypr_special_name(&item.name).unwrap_or(&item.name).to_string(),
+            output: Some(item.monitor.clone()),
+            is_focused: active_id == Some(item.id),
+            is_visible: visible_ids.contains(&item.id),
+            is_urgent: urgent_per_workspace.contains_key(&item.id),
+            is_special: hypr_special_name(&item.name).is_some(),
+            windows: item.windows as u32,
+            urgent_windows: urgent_per_workspace.get(&item.id).copied().unwrap_or(0),
+            app_ids: classes_on(item.id),
+        }).collect()
     }
     else
     {
@@ -101,6 +170,73 @@ pub fn workspace_count() -> Vec<i32>
 
 
 
+pub fn taskbar_snapshot() -> TaskbarSnapshot
+{
+    let urgent_addresses = URGENT_WINDOWS.lock().ok().and_then(|guard| guard.clone()).unwrap_or_default();
+    let active_address = Client::get_active().ok().flatten().map(|client| client.address.to_string());
+    let monitors = Monitors::get().map(|monitors| monitors.to_vec()).unwrap_or_default();
+    let workspace_outputs: HashMap<i32, String> = Workspaces::get().map(|workspaces| workspaces.iter().map(|workspace| (workspace.id, workspace.monitor.clone())).collect()).unwrap_or_default();
+    let windows = Clients::get().map(|clients| clients.to_vec()).unwrap_or_default().into_iter().filter(|client| client.mapped).map(|client|
+    {
+        let address = client.address.to_string();
+        TaskbarWindow
+        {
+            output: workspace_outputs.get(&client.workspace.id).cloned(),
+            is_focused: active_address.as_ref() == Some(&address),
+            is_urgent: urgent_addresses.contains(&address),
+            title: client.title,
+            app_id: client.class,
+            workspace: client.workspace.id,
+            id: address,
+        }
+    }).collect();
+
+    TaskbarSnapshot
+    {
+        windows,
+        focused_workspace: current_workspace(),
+        focused_output: monitors.iter().find(|monitor| monitor.focused).map(|monitor| monitor.name.clone()),
+        visible_workspaces: monitors.iter().flat_map(|monitor| [monitor.active_workspace.id, monitor.special_workspace.id]).collect(),
+    }
+}
+
+
+
+pub fn taskbar_action_hypr(id: &str, action: TaskbarAction)
+{
+    let window = WindowIdentifier::Address(Address::new(id));
+    let result = match action
+    {
+        TaskbarAction::Focus => Dispatch::call(DispatchType::FocusWindow(window)),
+        TaskbarAction::Close => Dispatch::call(DispatchType::CloseWindow(window)),
+    };
+    if let Err(err) = result { println!("Warning!!! Couldn't {action:?} The Window {id} Using Hyprland IPC\nErr: {err}") }
+}
+
+
+
+/// Maps each workspace id to how many of its windows (`(address, workspace id)`) are urgent.
+pub fn count_urgent_per_workspace(urgent: &HashSet<String>, clients: &[(String, i32)]) -> HashMap<i32, u32>
+{
+    let mut counts = HashMap::new();
+    for (address, workspace_id) in clients
+    {
+        if urgent.contains(address) { *counts.entry(*workspace_id).or_insert(0) += 1; }
+    }
+    counts
+}
+
+
+
+/// `special:magic` -> `magic`, the unnamed special workspace is just `special`.
+pub fn hypr_special_name(name: &str) -> Option<&str>
+{
+    if name == "special" { return Some(name); }
+    name.strip_prefix("special:")
+}
+
+
+
 pub fn current_workspace() -> i32 
 { 
     let result = Workspace::get_active();
@@ -124,6 +260,15 @@ pub fn change_workspace_hypr(action: UserWorkspaceAction)
         {
             let _ = Dispatch::call(DispatchType::Workspace(WorkspaceIdentifierWithSpecial::Id(id))); 
         }
+        UserWorkspaceAction::ChangeWithName(name) =>
+        {
+            let _ = Dispatch::call(DispatchType::Workspace(WorkspaceIdentifierWithSpecial::Name(&name))); 
+        }
+        UserWorkspaceAction::ToggleSpecial(name) =>
+        {
+            let special = if name == "special" { None } else { Some(name) };
+            let _ = Dispatch::call(DispatchType::ToggleSpecialWorkspace(special)); 
+        }
         UserWorkspaceAction::MoveNext =>
         {
             let _ = Dispatch::call(DispatchType::Workspace(WorkspaceIdentifierWithSpecial::Relative(1))); 
@@ -134,3 +279,39 @@ pub fn change_workspace_hypr(action: UserWorkspaceAction)
         }
     }
 }
+
+
+
+
+
+// ============ TESTS ============
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn special_name_strips_prefix()
+    {
+        assert_eq!(hypr_special_name("special:magic"), Some("magic"));
+        assert_eq!(hypr_special_name("special"), Some("special"));
+    }
+
+    #[test]
+    fn regular_workspaces_are_not_special()
+    {
+        assert_eq!(hypr_special_name("3"), None);
+        assert_eq!(hypr_special_name("specialist"), None);
+    }
+
+    #[test]
+    fn urgent_windows_are_counted_per_workspace()
+    {
+        let urgent: HashSet<String> = ["0xa".to_string(), "0xc".to_string()].into_iter().collect();
+        let clients = vec![("0xa".to_string(), 1), ("0xb".to_string(), 1), ("0xc".to_string(), 3)];
+        let counts = count_urgent_per_workspace(&urgent, &clients);
+        assert_eq!(counts.get(&1), Some(&1));
+        assert_eq!(counts.get(&3), Some(&1));
+        assert_eq!(counts.get(&2), None);
+    }
+}
@@ -1,7 +1,11 @@
 // ============ IMPORTS ============
 use serde::{Deserialize, Serialize};
-use iced::{ContentFit, widget::button};
-use std::{sync::Arc, path::Path};
+use iced::{ContentFit, Task, widget::button};
+use std::{collections::HashMap, sync::Arc, path::Path, time::{Duration, SystemTime}};
+use futures::stream::BoxStream;
+use futures_util::StreamExt;
+use notify::{EventKind, RecursiveMode, Watcher, recommended_watcher};
+use async_stream::stream;
 use iced_gif::gif;
 
 
@@ -9,7 +13,18 @@ use iced_gif::gif;
 
 
 // ============ CRATES ============
-use crate::helpers::{color::ColorType, style::{SideOption, UserStyle, set_style}};
+use crate::helpers::{color::ColorType, command::{CommandOptions, CommandRequest, merge_env, run_command, static_context_env}, icons::try_load_icon_sized, style::{SideOption, UserStyle, set_style}};
+use crate::modules::data::Modules;
+use crate::update::Message;
+use crate::AppData;
+
+
+
+
+
+// ============ CONST ============
+/// Scripts rendering charts or QR codes can take a while, they are only killed when really stuck.
+const IMAGE_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
 
 
 
@@ -20,6 +35,33 @@ use crate::helpers::{color::ColorType, style::{SideOption, UserStyle, set_style}
 pub struct ImageData
 {
     pub preloaded_images_handle: Vec<Option<(PreloadedImage, usize)>>,
+    /// Path each image is showing, `image_command` can replace the configured one.
+    pub image_paths: Vec<String>,
+    /// Modification time of the file each image last loaded, so a file rewritten under the same path is reloaded too.
+    pub image_modified: HashMap<usize, SystemTime>,
+}
+
+
+
+/// Identity of an `image_command` subscription.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ImageCommandStream
+{
+    pub index: usize,
+    pub command: Vec<String>,
+    pub shell: bool,
+    pub env: Vec<(String, String)>,
+    pub interval: u64,
+}
+
+
+
+/// Identity of a `watch_image_file` subscription, a new path restarts the watcher.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ImageFileWatch
+{
+    pub index: usize,
+    pub path: String,
 }
 
 
@@ -58,6 +100,13 @@ pub struct Image
     pub border_radius: [f32;4],
     pub command_to_exec_on_left_click: Vec<String>,
     pub command_to_exec_on_right_click: Vec<String>,
+    pub shell: bool,
+    pub env: HashMap<String, String>,
+    /// Prints the path of the image to show, its last non-empty line is used. Re-run every `image_command_interval` ms.
+    pub image_command: Vec<String>,
+    pub image_command_interval: u64,
+    /// Reloads the image whenever its file changes on disk.
+    pub watch_image_file: bool,
 }
 
 
@@ -98,6 +147,20 @@ impl Clone for PreloadedImage
 
 
 
+impl std::fmt::Debug for PreloadedImage
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            PreloadedImage::Static(handle) => f.debug_tuple("Static").field(handle).finish(),
+            PreloadedImage::Gif(_)         => f.write_str("Gif(..)"),
+        }
+    }
+}
+
+
+
 impl From<UserContentFit> for ContentFit
 {
     fn from(val: UserContentFit) -> Self
@@ -147,6 +210,56 @@ impl Default for Image
             border_radius: [3., 3., 3., 3.],
             command_to_exec_on_left_click: vec![], 
             command_to_exec_on_right_click: vec![],
+            shell: false,
+            env: HashMap::new(),
+            image_command: vec![],
+            image_command_interval: 5000,
+            watch_image_file: false,
+        }
+    }
+}
+
+
+
+pub fn image_click_request(index: usize, image: &Image, is_left_click: bool) -> CommandRequest
+{
+    CommandRequest
+    {
+        command: if is_left_click { image.command_to_exec_on_left_click.clone() } else { image.command_to_exec_on_right_click.clone() },
+        name: "Image Command".into(),
+        module: format!("Image({index})"),
+        is_left_click,
+        shell: image.shell,
+        env: image.env.clone(),
+        ..Default::default()
+    }
+}
+
+
+
+/// Reads a static image, GIF or SVG from disk. Static images are decoded from fresh bytes, so a file rewritten in place isn't served from the cache.
+pub fn load_image(path: &str, size: u32) -> Result<PreloadedImage, String>
+{
+    let file_path = Path::new(path);
+    if !file_path.exists() { return Err(format!("Image path does not exist: '{path}'")); }
+    let extension = file_path.extension().and_then(|extension| extension.to_str()).map(str::to_lowercase).unwrap_or_default();
+    match extension.as_str()
+    {
+        "gif" =>
+        {
+            let bytes = std::fs::read(path).map_err(|err| format!("Failed to preload GIF, ERR: {err}"))?;
+            let frames = gif::Frames::from_bytes(bytes).map_err(|err| format!("Failed to collect GIF frames, ERR: {err}"))?;
+            Ok(PreloadedImage::Gif(Arc::new(frames)))
+        }
+        "svg" =>
+        {
+            let (rgba, width, height) = try_load_icon_sized(file_path, Some(size)).ok_or_else(|| format!("Failed to render SVG: '{path}'"))?;
+            Ok(PreloadedImage::Static(iced::widget::image::Handle::from_rgba(width, height, rgba)))
+        }
+        _ =>
+        {
+            let bytes = std::fs::read(path).map_err(|err| format!("Failed to read image, ERR: {err}"))?;
+            Ok(PreloadedImage::Static(iced::widget::image::Handle::from_bytes(bytes)))
         }
     }
 }
@@ -160,58 +273,28 @@ pub fn preload_image(warning_err: &mut String, config_parsed_failed: &mut bool,
     for (index, image_module) in vec_of_image_modules.iter().enumerate()
     {
         println!("\n=== WALLPAPER PRELOAD ===");
-        if Path::new(&image_module.image_path).exists()
+        // Its path only comes with the first `image_command` run
+        if image_module.image_path.is_empty() && !image_module.image_command.is_empty()
         {
-            println!("Preloading Image, Please Wait...");
-            if image_module.image_path.ends_with(".gif")
+            vec_to_send.push(None);
+            continue;
+        }
+        println!("Preloading Image, Please Wait...");
+        match load_image(&image_module.image_path, image_module.width.max(image_module.height))
+        {
+            Ok(image) =>
             {
-                let result_bytes = std::fs::read(&image_module.image_path);
-                match result_bytes
-                {
-                    Ok(bytes) => 
-                    {
-                        let result_frames = gif::Frames::from_bytes(bytes);
-                        match result_frames
-                        {
-                            Ok(frames) =>
-                            {
-                                vec_to_send.push(Some((PreloadedImage::Gif(Arc::new(frames)), index)));
-                                println!("Image from Image({index}) Preload Completed Successfully!!!");
-                            },
-                            Err(err) => 
-                            {
-                                vec_to_send.push(None);
-                                let warning_msg = format!("Warning!!!: Failed to collect GIF frames, ERR: {err}");
-                                eprintln!("{warning_msg}");
-                                *warning_err = warning_msg;
-                                *config_parsed_failed = true;
-                            }
-                        }
-                    },
-                    Err(err) => 
-                    {
-                        vec_to_send.push(None);
-                        let warning_msg = format!("Warning!!!: Failed to preload GIF, ERR: {err}");
-                        eprintln!("{warning_msg}");
-                        *warning_err = warning_msg;
-                        *config_parsed_failed = true;
-                    }
-                }
+                vec_to_send.push(Some((image, index)));
+                println!("Image from Image({index}) Preload Completed Successfully!!!");
             }
-            else
+            Err(err) =>
             {
-                let handle = iced::widget::image::Handle::from_path(&image_module.image_path);
-                vec_to_send.push(Some((PreloadedImage::Static(handle), index)));
-                println!("Image from Image({index}) Preload Completed Successfully!!!");
-            };
-        }
-        else 
-        { 
-            vec_to_send.push(None);
-            let warning_msg = format!("WARNING!!!: Wallpaper path does not exist: '{}'. Error Coming from: Image({index})", image_module.image_path);
-            eprintln!("{warning_msg}");
-            *warning_err = warning_msg;
-            *config_parsed_failed = true;
+                vec_to_send.push(None);
+                let warning_msg = format!("WARNING!!!: {err}. Error Coming from: Image({index})");
+                eprintln!("{warning_msg}");
+                *warning_err = warning_msg;
+                *config_parsed_failed = true;
+            }
         }
     };
     vec_to_send
@@ -219,6 +302,145 @@ pub fn preload_image(warning_err: &mut String, config_parsed_failed: &mut bool,
 
 
 
+pub fn configured_image_paths(images: &[Image]) -> Vec<String>
+{
+    images.iter().map(|image| image.image_path.clone()).collect()
+}
+
+
+
+/// The last non-empty line of the command output, trimmed.
+pub fn image_path_from_output(output: &str) -> Option<String>
+{
+    output.lines().rev().map(str::trim).find(|line| !line.is_empty()).map(str::to_string)
+}
+
+
+
+/// Loads the image off the UI thread, a failed load keeps the previous image on screen.
+pub fn reload_image(app: &AppData, index: usize, path: String) -> Task<Message>
+{
+    let Some(image) = app.ron_config.image.images.get(index) else { return Task::none(); };
+    let size = image.width.max(image.height);
+    let loaded_path = path.clone();
+    Task::perform(tokio::task::spawn_blocking(move || load_image(&loaded_path, size)), move |result| match result
+    {
+        Ok(Ok(image)) => Message::ImageLoaded(index, path.clone(), image),
+        Ok(Err(err)) => { eprintln!("[icebar] Image({index}): {err}"); Message::Nothing }
+        Err(err) => { eprintln!("[icebar] Image({index}) loader failed: {err}"); Message::Nothing }
+    })
+}
+
+
+
+pub fn image_command_streams(app: &AppData) -> Vec<ImageCommandStream>
+{
+    let mut streams: Vec<ImageCommandStream> = app.modules_data.active_modules.iter().filter_map(|module| match module
+    {
+        Modules::Image(index) => app.ron_config.image.images.get(*index).filter(|image| !image.image_command.is_empty()).map(|image| ImageCommandStream
+        {
+            index: *index,
+            command: image.image_command.clone(),
+            shell: image.shell,
+            env: merge_env(static_context_env(app, &format!("Image({index})")), &image.env),
+            interval: image.image_command_interval,
+        }),
+        _ => None,
+    }).collect();
+    streams.sort_by_key(|stream| stream.index);
+    streams
+}
+
+
+
+pub fn image_command_stream(stream: &ImageCommandStream) -> BoxStream<'static, Message>
+{
+    let ImageCommandStream { index, command, shell, env, interval } = stream.clone();
+    stream!
+    {
+        let Some((program, args)) = command.split_first() else { return; };
+        loop
+        {
+            let options = CommandOptions
+            {
+                label: format!("Image({index}) image_command"),
+                timeout: Some(IMAGE_COMMAND_TIMEOUT),
+                exclusive_key: Some(format!("image-{index}")),
+                cancel_on_reload: true,
+                shell,
+                env: env.clone(),
+            };
+            if let Some(result) = run_command(program.clone(), args.to_vec(), options).await && let Some(path) = image_path_from_output(&result.stdout)
+            {
+                let modified = tokio::fs::metadata(&path).await.and_then(|metadata| metadata.modified()).ok();
+                yield Message::ImagePathChanged(index, path, modified);
+            }
+            tokio::time::sleep(Duration::from_millis(interval.max(100))).await;
+        }
+    }.boxed()
+}
+
+
+
+pub fn image_file_watches(app: &AppData) -> Vec<ImageFileWatch>
+{
+    let mut watches: Vec<ImageFileWatch> = app.modules_data.active_modules.iter().filter_map(|module| match module
+    {
+        Modules::Image(index) if app.ron_config.image.images.get(*index).is_some_and(|image| image.watch_image_file) =>
+        {
+            let path = app.modules_data.image_data.image_paths.get(*index).filter(|path| !path.is_empty())?;
+            Some(ImageFileWatch { index: *index, path: path.clone() })
+        }
+        _ => None,
+    }).collect();
+    watches.sort_by_key(|watch| watch.index);
+    watches
+}
+
+
+
+/// Same directory watch as the config file, so images replaced through a rename are still seen.
+pub fn image_file_watch_stream(watch: &ImageFileWatch) -> BoxStream<'static, Message>
+{
+    let ImageFileWatch { index, path } = watch.clone();
+    stream!
+    {
+        let image_path = std::path::PathBuf::from(&path);
+        let Some(watch_dir) = image_path.parent().map(Path::to_path_buf) else { return; };
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        std::thread::spawn(move ||
+        {
+            let tx_inner = tx.clone();
+            let mut watcher = match recommended_watcher(move |res: notify::Result<notify::Event>|
+            {
+                if let Ok(event) = res && event.paths.iter().any(|p| p == &image_path) && matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_))
+                {
+                    let _ = tx_inner.send(());
+                }
+            })
+            {
+                Ok(w)  => w,
+                Err(err) => { eprintln!("[icebar] Image({index}) couldn't watch its file: {err}"); return; }
+            };
+            if let Err(err) = watcher.watch(&watch_dir, RecursiveMode::NonRecursive) { eprintln!("[icebar] Image({index}) couldn't watch {}: {err}", watch_dir.display()); return; }
+
+            // Unlike the config watcher this one is replaced whenever the path changes, so it stops with its subscription
+            while !tx.is_closed() { std::thread::park_timeout(Duration::from_secs(1)); }
+        });
+
+        while rx.recv().await.is_some()
+        {
+            // Writers often touch the file several times in a row
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            while rx.try_recv().is_ok() {}
+            yield Message::ImageFileChanged(index);
+        }
+    }.boxed()
+}
+
+
+
 pub fn define_image_style(image: &Image, status: button::Status) -> iced::widget::button::Style
 {
     let text_holder = ColorType::RGB([255, 255, 255]);
@@ -482,6 +704,69 @@ mod tests
         assert!(result[0].is_none());
     }
 
+    #[test]
+    fn preload_image_waits_for_the_command_without_warning()
+    {
+        let images = vec![Image { image_command: vec!["qr-script".to_string()], ..Default::default() }];
+        let mut config_parsed_failed = false;
+        let result = preload_image(&mut String::new(), &mut config_parsed_failed, &images);
+        assert!(result[0].is_none());
+        assert!(!config_parsed_failed);
+    }
+
+    // ── load_image ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn load_image_reports_missing_files()
+    {
+        let err = load_image("/nonexistent/chart.png", 30).unwrap_err();
+        assert!(err.contains("/nonexistent/chart.png"));
+    }
+
+    #[test]
+    fn load_image_rasterizes_svgs()
+    {
+        use std::io::Write;
+        let mut tmp = tempfile::NamedTempFile::with_suffix(".svg").unwrap();
+        tmp.write_all(br#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10"><rect width="10" height="10" fill="red"/></svg>"#).unwrap();
+        let image = load_image(tmp.path().to_str().unwrap(), 20).unwrap();
+        assert!(matches!(image, PreloadedImage::Static(_)));
+    }
+
+    // ── image_command ────────────────────────────────────────────────────────
+
+    #[test]
+    fn image_path_comes_from_the_last_non_empty_line()
+    {
+        assert_eq!(image_path_from_output("generating...\n/tmp/chart.png\n\n"), Some("/tmp/chart.png".to_string()));
+        assert_eq!(image_path_from_output("  /tmp/avatar.svg  "), Some("/tmp/avatar.svg".to_string()));
+        assert_eq!(image_path_from_output("\n  \n"), None);
+    }
+
+    #[test]
+    fn image_command_streams_only_cover_active_dynamic_images()
+    {
+        let mut app = AppData { ..Default::default() };
+        app.ron_config.image.images = vec![Image::default(), Image { image_command: vec!["chart.sh".into()], image_command_interval: 60_000, ..Default::default() }];
+        app.modules_data.active_modules = [Modules::Image(0), Modules::Image(1)].into_iter().collect();
+        let streams = image_command_streams(&app);
+        assert_eq!(streams.len(), 1);
+        assert_eq!(streams[0].index, 1);
+        assert_eq!(streams[0].interval, 60_000);
+    }
+
+    #[test]
+    fn image_file_watches_follow_the_current_path()
+    {
+        let mut app = AppData { ..Default::default() };
+        app.ron_config.image.images = vec![Image { watch_image_file: true, image_path: "/tmp/a.png".into(), ..Default::default() }];
+        app.modules_data.active_modules = [Modules::Image(0)].into_iter().collect();
+        app.modules_data.image_data.image_paths = vec!["/tmp/b.png".into()];
+        assert_eq!(image_file_watches(&app), vec![ImageFileWatch { index: 0, path: "/tmp/b.png".into() }]);
+        app.modules_data.image_data.image_paths = vec![String::new()];
+        assert!(image_file_watches(&app).is_empty());
+    }
+
     // ── define_image_style ───────────────────────────────────────────────────
 
     fn make_image(normal: [u32;3], hovered: [u32;3], pressed: [u32;3]) -> Image
@@ -650,4 +935,13 @@ mod tests
         let back: Image = ron::from_str(&s).unwrap();
         assert_eq!(back.command_to_exec_on_left_click, vec!["wofi", "--show", "drun"]);
     }
+
+    #[test]
+    fn image_deserializes_dynamic_fields()
+    {
+        let img: Image = ron::from_str(r#"(image_command: ["sh", "-c", "echo /tmp/qr.png"], image_command_interval: 1000, watch_image_file: true)"#).unwrap();
+        assert_eq!(img.image_command.len(), 3);
+        assert_eq!(img.image_command_interval, 1000);
+        assert!(img.watch_image_file);
+    }
 }
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 
 
 // ============ CRATES ============
-use crate::helpers::{color::{ColorType, Gradient}, string::{convert_text_to_rich_text}, style::{UserStyle, orient_text, set_style, TextOrientation, SideOption}};
+use crate::helpers::{color::{ColorType, Gradient}, command::{CommandOptions, CommandResult, SYSTEM_COMMAND_TIMEOUT, run_command}, string::{convert_text_to_rich_text}, style::{UserStyle, orient_text, set_style, TextOrientation, SideOption}};
 use crate::ron::ActionOnClick;
 use crate::update::Message;
 use crate::AppData;
@@ -167,7 +167,6 @@ impl Default for MediaPlayerButtonConfig
 #[derive(Default, Debug, Clone)]
 pub struct MediaPlayerData
 {
-    pub is_hovering_media_player_meta_data: bool,
     pub metadata: String,
     pub status: String 
 }
@@ -186,35 +185,18 @@ pub enum MediaPlayerAction
 
 
 // ============ FUNCTIONS ============
-pub async fn get_player_data_with_format(player: &str, format: &str) -> MediaPlayerData
+/// `None` when a previous run for this player is still in flight, timed out or was cancelled by a reload, so the module keeps showing its last data.
+pub async fn get_player_data_with_format(player: &str, format: &str) -> Option<MediaPlayerData>
 {
-    let result_metadata_output = tokio::process::Command::new("playerctl").arg(format!("--player={}", player)).arg("metadata").arg("--format").arg(format).output().await;
-    let result_status_output = tokio::process::Command::new("playerctl").arg(format!("--player={}", player)).arg("status").output().await;
+    let options = |what: &str| CommandOptions { label: format!("playerctl {what}"), timeout: Some(SYSTEM_COMMAND_TIMEOUT), exclusive_key: Some(format!("playerctl-{player}-{what}")), cancel_on_reload: true, ..Default::default() };
+    let result_metadata_output = run_command("playerctl".into(), vec![format!("--player={}", player), "metadata".into(), "--format".into(), format.into()], options("metadata")).await.filter(CommandResult::finished)?;
+    let result_status_output = run_command("playerctl".into(), vec![format!("--player={}", player), "status".into()], options("status")).await.filter(CommandResult::finished)?;
 
-    let metadata_string = if let Ok(metadata_output) = result_metadata_output
+    Some(MediaPlayerData 
     {
-        String::from_utf8_lossy(&metadata_output.stdout).to_string().replace("\n", "")
-    }
-    else
-    {
-        String::new()
-    };
-
-    let status_string = if let Ok(status_output) = result_status_output
-    {
-        String::from_utf8_lossy(&status_output.stdout).to_string().replace("\n", "")
-    }
-    else
-    {
-        String::new()
-    };
-
-    MediaPlayerData 
-    {
-        is_hovering_media_player_meta_data: false,
-        metadata: metadata_string, 
-        status: status_string
-    }
+        metadata: result_metadata_output.stdout.replace("\n", ""), 
+        status: result_status_output.stdout.replace("\n", "")
+    })
 }
 
 
@@ -236,13 +218,10 @@ pub fn media_player_action(player: &str, action: MediaPlayerAction) -> Task<crat
         MediaPlayerAction::VolumeDown => Some("0.1-"),
         _                             => None,
     };
-    Task::perform(async move 
-    {
-        let mut cmd = tokio::process::Command::new("playerctl");
-        cmd.arg(format!("--player={}", player)).arg(arg);
-        if let Some(extra) = extra_arg { cmd.arg(extra); }
-        let _ = cmd.output().await;
-    },|_| Message::Nothing)
+    let mut args = vec![format!("--player={}", player), arg.to_string()];
+    if let Some(extra) = extra_arg { args.push(extra.to_string()); }
+    let options = CommandOptions { label: "playerctl".into(), timeout: Some(SYSTEM_COMMAND_TIMEOUT), ..Default::default() };
+    Task::perform(run_command("playerctl".into(), args, options), |_| Message::Nothing)
 }
 
 
@@ -464,7 +443,6 @@ mod tests
         let mut app = AppData { ..Default::default() };
         app.modules_data.media_player_data = MediaPlayerData
         {
-            is_hovering_media_player_meta_data: false,
             metadata: metadata.into(),
             status: status.into(),
         };
@@ -1,9 +1,13 @@
 pub mod custom_modules;
+pub mod binding_mode;
 pub mod focused_window;
 pub mod media_player;
 pub mod workspaces;
+pub mod compositor;
 pub mod cpu_temp;
 pub mod network;
+pub mod taskbar;
+pub mod wayland;
 pub mod volume;
 pub mod clock;
 pub mod image;
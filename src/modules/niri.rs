@@ -1,25 +1,296 @@
+use std::pin::Pin;
+
 // ============ IMPORTS ============
-use niri_ipc::{Action, Request, Response, Workspace, WorkspaceReferenceArg, socket::Socket};
+use niri_ipc::{Action, Event, Request, Response, Window, Workspace, WorkspaceReferenceArg, socket::Socket};
+use std::collections::HashMap;
 
 
 
 
 
-// ============ FUNCTIONS ============
-use crate::modules::workspaces::UserWorkspaceAction;
+// ============ CRATES ============
+use crate::{modules::{focused_window::FocusedWindowData, taskbar::{TaskbarAction, TaskbarSnapshot, TaskbarWindow}, workspaces::{UserWorkspaceAction, WorkspaceInfo}}, update::Message};
+
+
+
+
+
+// ============ ENUM/STRUCT, ETC ============
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct NiriWorkspaceState
+{
+    pub id: u64,
+    pub idx: u8,
+    pub name: Option<String>,
+    pub output: Option<String>,
+    pub is_active: bool,
+    pub is_focused: bool,
+    pub is_urgent: bool,
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct NiriWindowState
+{
+    pub title: Option<String>,
+    pub app_id: Option<String>,
+    pub workspace_id: Option<u64>,
+    pub pid: Option<i32>,
+    pub is_floating: bool,
+    pub is_urgent: bool,
+}
+
+/// Mirror of the compositor state, kept up to date from the event stream so nothing has to be polled.
+#[derive(Default, Debug)]
+pub struct NiriState
+{
+    pub workspaces: Vec<NiriWorkspaceState>,
+    pub windows: HashMap<u64, NiriWindowState>,
+    pub focused_window: Option<u64>,
+}
+
+/// What an event changed, as far as the bar can see.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NiriChange
+{
+    pub workspaces: bool,
+    pub focused_window: bool,
+}
+
+
+
+impl From<&Workspace> for NiriWorkspaceState
+{
+    fn from(workspace: &Workspace) -> Self
+    {
+        NiriWorkspaceState { id: workspace.id, idx: workspace.idx, name: workspace.name.clone(), output: workspace.output.clone(), is_active: workspace.is_active, is_focused: workspace.is_focused, is_urgent: workspace.is_urgent }
+    }
+}
+
+impl From<&Window> for NiriWindowState
+{
+    fn from(window: &Window) -> Self
+    {
+        NiriWindowState { title: window.title.clone(), app_id: window.app_id.clone(), workspace_id: window.workspace_id, pid: window.pid, is_floating: window.is_floating, is_urgent: window.is_urgent }
+    }
+}
+
+
+
+impl NiriState
+{
+    pub fn apply_event(&mut self, event: Event) -> NiriChange
+    {
+        self.track(|state| match event
+        {
+            Event::WorkspacesChanged { workspaces } => state.set_workspaces(workspaces.iter().map(NiriWorkspaceState::from).collect()),
+            Event::WorkspaceActivated { id, focused } => state.activate_workspace(id, focused),
+            Event::WindowsChanged { windows } =>
+            {
+                state.windows = windows.iter().map(|window| (window.id, NiriWindowState::from(window))).collect();
+                state.focused_window = windows.iter().find(|window| window.is_focused).map(|window| window.id);
+            }
+            Event::WindowOpenedOrChanged { window } =>
+            {
+                if window.is_focused { state.focused_window = Some(window.id); }
+                state.windows.insert(window.id, NiriWindowState::from(&window));
+            }
+            Event::WindowClosed { id } =>
+            {
+                state.windows.remove(&id);
+                if state.focused_window == Some(id) { state.focused_window = None; }
+            }
+            Event::WindowFocusChanged { id } => state.focused_window = id,
+            Event::WorkspaceUrgencyChanged { id, urgent } =>
+            {
+                if let Some(workspace) = state.workspaces.iter_mut().find(|workspace| workspace.id == id) { workspace.is_urgent = urgent; }
+            }
+            Event::WindowUrgencyChanged { id, urgent } =>
+            {
+                if let Some(window) = state.windows.get_mut(&id) { window.is_urgent = urgent; }
+            }
+            _ => {}
+        })
+    }
+
+    pub fn set_workspaces(&mut self, mut workspaces: Vec<NiriWorkspaceState>)
+    {
+        workspaces.sort_by_key(|workspace| (workspace.output.clone(), workspace.idx));
+        self.workspaces = workspaces;
+    }
+
+    /// The activated workspace becomes the active one of its output, and the focused one overall when `focused` is set.
+    pub fn activate_workspace(&mut self, id: u64, focused: bool)
+    {
+        let Some(output) = self.workspaces.iter().find(|workspace| workspace.id == id).map(|workspace| workspace.output.clone()) else { return; };
+        for workspace in &mut self.workspaces
+        {
+            if workspace.output == output { workspace.is_active = workspace.id == id; }
+            if focused { workspace.is_focused = workspace.id == id; }
+        }
+    }
+
+    /// Workspaces are keyed by niri's own id, the per-output `idx` is what gets displayed.
+    pub fn current_workspace(&self) -> i32
+    {
+        self.workspaces.iter().find(|workspace| workspace.is_focused).map_or(0, |workspace| workspace.id as i32)
+    }
+
+    pub fn workspace_list(&self) -> Vec<WorkspaceInfo>
+    {
+        self.workspaces.iter().map(|workspace| 
+        {
+            let windows = || self.windows.values().filter(|window| window.workspace_id == Some(workspace.id));
+            let urgent_windows = windows().filter(|window| window.is_urgent).count() as u32;
+            WorkspaceInfo
+            {
+                id: workspace.id as i32,
+                number: workspace.idx as i32,
+                name: workspace.name.clone().unwrap_or_else(|| workspace.idx.to_string()),
+                output: workspace.output.clone(),
+                is_focused: workspace.is_focused,
+                is_visible: workspace.is_active,
+                is_urgent: workspace.is_urgent || urgent_windows > 0,
+                is_special: false,
+                windows: windows().count() as u32,
+                urgent_windows,
+                app_ids: self.app_ids_on(workspace.id),
+            }
+        }).collect()
+    }
+
+    /// Sorted by window id so the icons don't shuffle around between refreshes.
+    fn app_ids_on(&self, workspace_id: u64) -> Vec<String>
+    {
+        let mut windows: Vec<(&u64, &NiriWindowState)> = self.windows.iter().filter(|(_, window)| window.workspace_id == Some(workspace_id)).collect();
+        windows.sort_by_key(|(id, _)| **id);
+        windows.into_iter().filter_map(|(_, window)| window.app_id.clone()).collect()
+    }
+
+    pub fn taskbar_snapshot(&self) -> TaskbarSnapshot
+    {
+        let mut ids: Vec<&u64> = self.windows.keys().collect();
+        ids.sort();
+        let windows = ids.into_iter().map(|id|
+        {
+            let window = &self.windows[id];
+            let workspace = window.workspace_id.and_then(|workspace_id| self.workspaces.iter().find(|workspace| workspace.id == workspace_id));
+            TaskbarWindow
+            {
+                id: id.to_string(),
+                title: window.title.clone().unwrap_or_default(),
+                app_id: window.app_id.clone().unwrap_or_default(),
+                workspace: window.workspace_id.map_or(0, |workspace_id| workspace_id as i32),
+                output: workspace.and_then(|workspace| workspace.output.clone()),
+                is_focused: self.focused_window == Some(*id),
+                is_urgent: window.is_urgent,
+            }
+        }).collect();
+        let focused = self.workspaces.iter().find(|workspace| workspace.is_focused);
+        TaskbarSnapshot
+        {
+            windows,
+            focused_workspace: self.current_workspace(),
+            focused_output: focused.and_then(|workspace| workspace.output.clone()),
+            visible_workspaces: self.workspaces.iter().filter(|workspace| workspace.is_active).map(|workspace| workspace.id as i32).collect(),
+        }
+    }
+
+    pub fn focused_title(&self) -> Option<String>
+    {
+        self.focused_window.and_then(|id| self.windows.get(&id)).and_then(|window| window.title.clone())
+    }
+
+    /// niri has no X11 class nor fullscreen flag, the class mirrors the app id.
+    pub fn focused_window_data(&self) -> Option<FocusedWindowData>
+    {
+        let window = self.focused_window.and_then(|id| self.windows.get(&id))?;
+        let workspace = window.workspace_id.and_then(|id| self.workspaces.iter().find(|workspace| workspace.id == id)).map(|workspace| workspace.name.clone().unwrap_or_else(|| workspace.idx.to_string()));
+        let app_id = window.app_id.clone().unwrap_or_default();
+        Some(FocusedWindowData { title: window.title.clone().unwrap_or_default(), class: app_id.clone(), app_id, pid: window.pid, workspace: workspace.unwrap_or_default(), floating: window.is_floating, fullscreen: false })
+    }
+
+    fn track(&mut self, change: impl FnOnce(&mut Self)) -> NiriChange
+    {
+        let workspaces_before = (self.current_workspace(), self.workspace_list());
+        let window_before = self.focused_window_data();
+        change(self);
+        NiriChange
+        {
+            workspaces: workspaces_before != (self.current_workspace(), self.workspace_list()),
+            focused_window: window_before != self.focused_window_data(),
+        }
+    }
+}
 
 
 
 
 
 // ============ FUNCTIONS ============
-pub fn workspace_count() -> Vec<i32>
+pub fn niri_event_subscription() -> Pin<Box<dyn futures::Stream<Item = Message> + Send>>
 {
-    let workspaces = niri_ipc_workspaces_setup();
-    let mut idxs: Vec<i32> = workspaces.iter().map(|w| w.idx as i32).collect();
-    idxs.sort_unstable();
-    idxs.dedup();
-    idxs
+    Box::pin(async_stream::stream!
+    {
+        yield Message::UpdateWorkspaces;
+        yield Message::UpdateFocusedWindow;
+        loop
+        {
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
+            std::thread::spawn(move ||
+            {
+                let mut socket = match Socket::connect()
+                {
+                    Ok(socket) => socket,
+                    Err(e) =>
+                    {
+                        eprintln!("[icebar] niri socket connect failed: {e}");
+                        return; // thread exits → tx drops → reconnect
+                    }
+                };
+                match socket.send(Request::EventStream)
+                {
+                    Ok(Ok(Response::Handled)) => {}
+                    Ok(Ok(_)) => { eprintln!("[icebar] niri event stream: unexpected response"); return; }
+                    Ok(Err(e)) => { eprintln!("[icebar] niri event stream refused: {e}"); return; }
+                    Err(e) => { eprintln!("[icebar] niri event stream request failed: {e}"); return; }
+                }
+
+                // niri sends the full workspace and window state right after the request, so no initial query is needed
+                let mut read_event = socket.read_events();
+                let mut state = NiriState::default();
+                loop
+                {
+                    let taskbar_before = state.taskbar_snapshot();
+                    let change = match read_event()
+                    {
+                        Ok(event) => state.apply_event(event),
+                        Err(e) =>
+                        {
+                            eprintln!("[icebar] niri event error: {e}");
+                            break; // socket error → thread exits → reconnect
+                        }
+                    };
+                    let mut messages = Vec::new();
+                    if change.workspaces { messages.push(Message::WorkspacesFetched(state.current_workspace(), state.workspace_list())); }
+                    if change.focused_window { messages.push(Message::FocusedWindowFetched(state.focused_window_data())); }
+                    let taskbar = state.taskbar_snapshot();
+                    if taskbar != taskbar_before { messages.push(Message::TaskbarFetched(taskbar)); }
+                    if messages.into_iter().any(|message| tx.send(message).is_err()) { break; }
+                }
+            });
+            while let Some(msg) = rx.recv().await { yield msg; }
+
+            eprintln!("[icebar] niri event listener stopped — reconnecting in 2s");
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        }
+    })
+}
+
+
+
+pub fn workspace_count() -> Vec<WorkspaceInfo>
+{
+    niri_ipc_state().workspace_list()
 }
 
 
@@ -27,10 +298,10 @@ pub fn workspace_count() -> Vec<i32>
 pub fn current_workspace() -> i32 
 { 
     let workspaces = niri_ipc_workspaces_setup();
-    let result_focused_idx = workspaces.iter().find(|w| w.is_focused).map(|w| w.idx);
-    if let Some(focused_idx) = result_focused_idx 
+    let result_focused_id = workspaces.iter().find(|w| w.is_focused).map(|w| w.id);
+    if let Some(focused_id) = result_focused_id 
     {
-        focused_idx as i32
+        focused_id as i32
     }
     else
     {
@@ -55,10 +326,18 @@ pub fn change_workspace_niri(action: UserWorkspaceAction)
     
     match action 
     {
+        // Workspaces are keyed by niri's id (see `NiriState::workspace_list`), so the index here is that id
         UserWorkspaceAction::ChangeWithIndex(id) =>
         {
-            let safe_id = id.clamp(1, 255) as u8;
-            let _ = socket.send(Request::Action(Action::FocusWorkspace{reference: WorkspaceReferenceArg::Index(safe_id)}));
+            let _ = socket.send(Request::Action(Action::FocusWorkspace{reference: WorkspaceReferenceArg::Id(id.max(0) as u64)}));
+        }
+        UserWorkspaceAction::ChangeWithName(name) =>
+        {
+            let _ = socket.send(Request::Action(Action::FocusWorkspace{reference: WorkspaceReferenceArg::Name(name)}));
+        }
+        UserWorkspaceAction::ToggleSpecial(_) =>
+        {
+            println!("Warning!!!: Niri Doesn't Have Special Workspaces.");
         }
         UserWorkspaceAction::MoveNext =>
         {
@@ -73,6 +352,41 @@ pub fn change_workspace_niri(action: UserWorkspaceAction)
 
 
 
+pub fn taskbar_snapshot() -> TaskbarSnapshot
+{
+    niri_ipc_state().taskbar_snapshot()
+}
+
+
+
+pub fn taskbar_action_niri(id: &str, action: TaskbarAction)
+{
+    let Ok(id) = id.parse::<u64>() else { return; };
+    let Ok(mut socket) = Socket::connect() else { eprintln!("Failed To Connect To Niri Socket"); return; };
+    let request = match action
+    {
+        TaskbarAction::Focus => Request::Action(Action::FocusWindow { id }),
+        TaskbarAction::Close => Request::Action(Action::CloseWindow { id: Some(id) }),
+    };
+    if let Err(err) = socket.send(request) { println!("Warning!!! Couldn't {action:?} The Window {id} Using Niri IPC\nErr: {err}") }
+}
+
+
+
+fn niri_ipc_state() -> NiriState
+{
+    let mut state = NiriState::default();
+    state.set_workspaces(niri_ipc_workspaces_setup().iter().map(NiriWorkspaceState::from).collect());
+    if let Ok(mut socket) = Socket::connect() && let Ok(Ok(Response::Windows(windows))) = socket.send(Request::Windows)
+    {
+        state.windows = windows.iter().map(|window| (window.id, NiriWindowState::from(window))).collect();
+        state.focused_window = windows.iter().find(|window| window.is_focused).map(|window| window.id);
+    }
+    state
+}
+
+
+
 fn niri_ipc_workspaces_setup() -> Vec<Workspace>
 {
     let result_socket = Socket::connect();
@@ -117,3 +431,168 @@ fn niri_ipc_workspaces_setup() -> Vec<Workspace>
         }
     }
 }
+
+
+
+
+
+// ============ TESTS ============
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn ws(id: u64, idx: u8, output: &str, is_active: bool, is_focused: bool) -> NiriWorkspaceState
+    {
+        NiriWorkspaceState { id, idx, output: Some(output.to_string()), is_active, is_focused, ..Default::default() }
+    }
+
+    fn two_outputs() -> NiriState
+    {
+        let mut state = NiriState::default();
+        state.set_workspaces(vec![ws(1, 1, "DP-1", true, true), ws(2, 2, "DP-1", false, false), ws(3, 1, "HDMI-A-1", true, false)]);
+        state
+    }
+
+    fn window(title: &str, workspace_id: u64) -> NiriWindowState
+    {
+        NiriWindowState { title: Some(title.to_string()), workspace_id: Some(workspace_id), ..Default::default() }
+    }
+
+    // ---- workspaces ---------------------------------------------------------
+
+    #[test]
+    fn activate_focused_workspace_moves_focus()
+    {
+        let mut state = two_outputs();
+        state.activate_workspace(2, true);
+        assert_eq!(state.current_workspace(), 2);
+        assert!(!state.workspaces.iter().find(|w| w.id == 1).unwrap().is_active);
+        // the other output keeps its active workspace
+        assert!(state.workspaces.iter().find(|w| w.id == 3).unwrap().is_active);
+    }
+
+    #[test]
+    fn activate_unfocused_workspace_keeps_focus()
+    {
+        let mut state = two_outputs();
+        state.activate_workspace(2, false);
+        assert_eq!(state.current_workspace(), 1);
+        assert!(state.workspaces.iter().find(|w| w.id == 2).unwrap().is_active);
+    }
+
+    #[test]
+    fn activate_unknown_workspace_is_ignored()
+    {
+        let mut state = two_outputs();
+        state.activate_workspace(42, true);
+        assert_eq!(state.current_workspace(), 1);
+    }
+
+    #[test]
+    fn workspace_list_keys_by_id_and_numbers_by_idx()
+    {
+        let list = two_outputs().workspace_list();
+        assert_eq!(list.iter().map(|w| (w.id, w.number)).collect::<Vec<_>>(), vec![(1, 1), (2, 2), (3, 1)]);
+        assert_eq!(list[2].output.as_deref(), Some("HDMI-A-1"));
+        assert_eq!(list[0].name, "1");
+    }
+
+    #[test]
+    fn workspace_list_uses_niri_names_and_counts_windows()
+    {
+        let mut state = two_outputs();
+        state.workspaces[1].name = Some("web".to_string());
+        state.windows.insert(10, window("a", 2));
+        state.windows.insert(11, window("b", 2));
+        state.windows.insert(12, window("c", 3));
+        let list = state.workspace_list();
+        assert_eq!(list[1].name, "web");
+        assert_eq!(list.iter().map(|w| w.windows).collect::<Vec<_>>(), vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn activation_event_reports_change_only_when_needed()
+    {
+        let mut state = two_outputs();
+        assert!(state.apply_event(Event::WorkspaceActivated { id: 2, focused: true }).workspaces);
+        assert_eq!(state.apply_event(Event::WorkspaceActivated { id: 2, focused: true }), NiriChange::default());
+    }
+
+    // ---- focused window -----------------------------------------------------
+
+    #[test]
+    fn focus_change_reports_new_title()
+    {
+        let mut state = two_outputs();
+        state.windows.insert(7, window("Editor", 1));
+        state.windows.insert(8, window("Terminal", 1));
+        assert_eq!(state.apply_event(Event::WindowFocusChanged { id: Some(8) }), NiriChange { workspaces: false, focused_window: true });
+        assert_eq!(state.focused_title(), Some("Terminal".to_string()));
+        assert_eq!(state.apply_event(Event::WindowFocusChanged { id: Some(8) }), NiriChange::default());
+    }
+
+    #[test]
+    fn focused_window_data_names_the_workspace()
+    {
+        let mut state = two_outputs();
+        state.workspaces[1].name = Some("web".to_string());
+        state.windows.insert(7, NiriWindowState { app_id: Some("firefox".to_string()), pid: Some(42), is_floating: true, ..window("Docs", 2) });
+        state.windows.insert(8, window("Shell", 3));
+        state.focused_window = Some(7);
+        let data = state.focused_window_data().unwrap();
+        assert_eq!((data.title.as_str(), data.app_id.as_str(), data.class.as_str(), data.workspace.as_str()), ("Docs", "firefox", "firefox", "web"));
+        assert_eq!((data.pid, data.floating), (Some(42), true));
+        state.focused_window = Some(8);
+        assert_eq!(state.focused_window_data().unwrap().workspace, "1");
+    }
+
+    #[test]
+    fn closing_focused_window_clears_title_and_window_count()
+    {
+        let mut state = two_outputs();
+        state.windows.insert(7, window("Editor", 1));
+        state.focused_window = Some(7);
+        assert_eq!(state.apply_event(Event::WindowClosed { id: 7 }), NiriChange { workspaces: true, focused_window: true });
+        assert_eq!(state.focused_title(), None);
+    }
+
+    // ---- urgency ------------------------------------------------------------
+
+    #[test]
+    fn window_urgency_marks_its_workspace()
+    {
+        let mut state = two_outputs();
+        state.windows.insert(7, window("Chat", 3));
+        assert!(state.apply_event(Event::WindowUrgencyChanged { id: 7, urgent: true }).workspaces);
+        let list = state.workspace_list();
+        assert!(list[2].is_urgent);
+        assert_eq!(list[2].urgent_windows, 1);
+        assert!(state.apply_event(Event::WindowUrgencyChanged { id: 7, urgent: false }).workspaces);
+        assert!(!state.workspace_list()[2].is_urgent);
+    }
+
+    #[test]
+    fn workspace_urgency_event_is_applied()
+    {
+        let mut state = two_outputs();
+        assert!(state.apply_event(Event::WorkspaceUrgencyChanged { id: 2, urgent: true }).workspaces);
+        assert!(state.workspace_list()[1].is_urgent);
+    }
+
+    // ---- taskbar --------------------------------------------------------------
+
+    #[test]
+    fn taskbar_snapshot_maps_windows_to_outputs()
+    {
+        let mut state = two_outputs();
+        state.windows.insert(12, window("Chat", 3));
+        state.windows.insert(10, window("Editor", 1));
+        state.focused_window = Some(12);
+        let snapshot = state.taskbar_snapshot();
+        assert_eq!(snapshot.windows.iter().map(|w| w.id.as_str()).collect::<Vec<_>>(), vec!["10", "12"]);
+        assert_eq!(snapshot.windows[1].output.as_deref(), Some("HDMI-A-1"));
+        assert!(snapshot.windows[1].is_focused);
+        assert_eq!(snapshot.focused_workspace, state.current_workspace());
+    }
+}
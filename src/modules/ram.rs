@@ -1,12 +1,14 @@
 // ============ IMPORTS ============
 use iced::widget::button;
+use std::collections::HashMap;
 
 
 
 
 
 // ============ CRATES ============
-use crate::helpers::style::{UserStyle, orient_text, set_style};
+use crate::helpers::style::{UserStyle, apply_state_style, module_state, orient_text, set_style, state_format};
+use crate::modules::data::Modules;
 use crate::AppData;
 
 
@@ -17,7 +19,7 @@ use crate::AppData;
 
 // ============ CONFIG ============
 use serde::{Deserialize, Serialize};
-use crate::helpers::style::{TextOrientation, SideOption};
+use crate::helpers::style::{StateStyle, TextOrientation, SideOption};
 use crate::helpers::color::{ColorType, Gradient};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -49,6 +51,7 @@ pub struct RamConfig
     pub ram_button_shadow_x:               f32,
     pub ram_button_shadow_y:               f32,
     pub ram_button_shadow_blur:            f32,
+    pub ram_states:                        HashMap<String, StateStyle>,
 }
 
 impl Default for RamConfig
@@ -82,6 +85,7 @@ impl Default for RamConfig
             ram_button_shadow_x:               0.0,
             ram_button_shadow_y:               0.0,
             ram_button_shadow_blur:            0.0,
+            ram_states:                        HashMap::new(),
         }
     }
 }
@@ -143,7 +147,7 @@ fn parse_kb(line: &str) -> Option<u64>
 pub fn define_ram_text(app: &AppData) -> String
 {
     let d    = &app.modules_data.ram_data;
-    let text = app.ron_config.ram.ram_format
+    let text = state_format(&app.ron_config.ram.ram_format, module_state(app, &Modules::Ram))
         .replace("{used}",    &d.used_mb.to_string())
         .replace("{total}",   &d.total_mb.to_string())
         .replace("{percent}", &format!("{:.0}", d.percent));
@@ -154,7 +158,7 @@ pub fn define_ram_text(app: &AppData) -> String
 
 pub fn define_ram_style(app: &AppData, status: button::Status) -> iced::widget::button::Style
 {
-    set_style(UserStyle
+    set_style(apply_state_style(UserStyle
     {
         status,
         normal:            app.ron_config.ram.ram_button_color,
@@ -173,7 +177,7 @@ pub fn define_ram_style(app: &AppData, status: button::Status) -> iced::widget::
         shadow_x:     app.ron_config.ram.ram_button_shadow_x,
         shadow_y:     app.ron_config.ram.ram_button_shadow_y,
         shadow_blur:  app.ron_config.ram.ram_button_shadow_blur,
-    })
+    }, module_state(app, &Modules::Ram), app.modules_data.state_blink_off))
 }
 
 
@@ -1,14 +1,22 @@
 use std::pin::Pin;
 
 // ============ IMPORTS ============
-use swayipc::{Connection, EventType, Event};
+use swayipc::{Connection, EventType, Event, Node, NodeType, WindowChange};
  
 
 
 
 
 // ============ CRATES ============
-use crate::{modules::workspaces::UserWorkspaceAction, update::Message};
+use crate::{modules::{taskbar::{TaskbarAction, TaskbarSnapshot, TaskbarWindow}, workspaces::{UserWorkspaceAction, WorkspaceInfo}}, update::Message};
+
+
+
+
+
+// ============ STATICS ============
+/// Key of the pseudo workspace standing for the scratchpad.
+pub const SWAY_SCRATCHPAD_ID: i32 = i32::MIN;
 
 
 
@@ -19,16 +27,19 @@ pub fn sway_event_subscription() -> Pin<Box<dyn futures::Stream<Item = Message>
 {
     Box::pin(async_stream::stream!
     {
-        yield Message::UpdateSwayWorkspaces;
-        yield Message::UpdateFocusedWindowSway;
+        yield Message::UpdateWorkspaces;
+        yield Message::UpdateFocusedWindow;
+        yield Message::UpdateTaskbar;
         loop
         {
             let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
             let tx_thread = tx.clone();
             std::thread::spawn(move ||
             {
-                let subs = [EventType::Workspace, EventType::Window];
+                let subs = [EventType::Workspace, EventType::Window, EventType::Mode];
  
+                // The mode event only fires on changes, so start from the current one
+                if let Ok(mode) = Connection::new().and_then(|mut conn| conn.get_binding_state()) { let _ = tx_thread.send(Message::BindingModeChanged(mode)); }
                 let events = match Connection::new().and_then(|conn| conn.subscribe(subs))
                 {
                     Ok(e)  => e,
@@ -45,12 +56,20 @@ pub fn sway_event_subscription() -> Pin<Box<dyn futures::Stream<Item = Message>
                     {
                         Ok(Event::Workspace(_)) =>
                         {
-                            let _ = tx_thread.send(Message::UpdateSwayWorkspaces);
+                            let _ = tx_thread.send(Message::UpdateWorkspaces);
+                            let _ = tx_thread.send(Message::UpdateTaskbar);
                         }
-                        Ok(Event::Window(_)) =>
+                        Ok(Event::Window(window_event)) =>
                         {
-                            let _ = tx_thread.send(Message::UpdateFocusedWindowSway);
+                            // Urgency and window counts live on the workspaces, so refresh them too
+                            if matches!(window_event.change, WindowChange::Urgent | WindowChange::New | WindowChange::Close | WindowChange::Move)
+                            {
+                                let _ = tx_thread.send(Message::UpdateWorkspaces);
+                            }
+                            let _ = tx_thread.send(Message::UpdateFocusedWindow);
+                            let _ = tx_thread.send(Message::UpdateTaskbar);
                         }
+                        Ok(Event::Mode(mode_event)) => { let _ = tx_thread.send(Message::BindingModeChanged(mode_event.change)); }
                         Ok(_)  => {}
                         Err(e) =>
                         {
@@ -83,7 +102,7 @@ pub fn current_workspace() -> i32
             {
                 if workspace.focused
                 {
-                    return workspace.num;
+                    return sway_workspace_key(workspace.num, workspace.id);
                 }
             }
         }
@@ -93,7 +112,116 @@ pub fn current_workspace() -> i32
 
 
 
-pub fn workspace_count() -> Vec<i32>
+/// Numbered workspaces are keyed by their number, the others by their (negated) container id so they can't collide.
+pub fn sway_workspace_key(num: i32, container_id: i64) -> i32
+{
+    if num > 0 { num } else { -(container_id.clamp(1, i32::MAX as i64) as i32) }
+}
+
+
+
+/// Counts the windows under `node`, or only the urgent ones when `only_urgent` is set.
+fn sway_window_count(node: &Node, only_urgent: bool) -> u32
+{
+    let is_window = matches!(node.node_type, NodeType::Con | NodeType::FloatingCon) && node.nodes.is_empty() && node.floating_nodes.is_empty() && node.pid.is_some();
+    if is_window { return u32::from(!only_urgent || node.urgent); }
+    node.nodes.iter().chain(node.floating_nodes.iter()).map(|child| sway_window_count(child, only_urgent)).sum()
+}
+
+
+
+/// `app_id` of the Wayland windows under `node`, the X11 class for XWayland ones.
+fn sway_app_ids(node: &Node) -> Vec<String>
+{
+    let is_window = matches!(node.node_type, NodeType::Con | NodeType::FloatingCon) && node.nodes.is_empty() && node.floating_nodes.is_empty() && node.pid.is_some();
+    if is_window
+    {
+        let class = node.window_properties.as_ref().and_then(|properties| properties.class.clone());
+        return node.app_id.clone().or(class).into_iter().collect();
+    }
+    node.nodes.iter().chain(node.floating_nodes.iter()).flat_map(sway_app_ids).collect()
+}
+
+
+
+/// Walks the tree like `find_focused_sway`, remembering the output and workspace each window sits on.
+/// The hidden `__i3` output holds the scratchpad, its windows are keyed by `SWAY_SCRATCHPAD_ID`.
+fn collect_sway_windows(node: &Node, output: Option<&str>, workspace: i32, windows: &mut Vec<TaskbarWindow>)
+{
+    let (output, workspace) = match node.node_type
+    {
+        NodeType::Output => (node.name.as_deref(), workspace),
+        NodeType::Workspace if node.name.as_deref() == Some("__i3_scratch") => (output, SWAY_SCRATCHPAD_ID),
+        NodeType::Workspace => (output, sway_workspace_key(node.num.unwrap_or(-1), node.id)),
+        _ => (output, workspace),
+    };
+    let is_window = matches!(node.node_type, NodeType::Con | NodeType::FloatingCon) && node.nodes.is_empty() && node.floating_nodes.is_empty() && node.pid.is_some();
+    if is_window
+    {
+        let class = node.window_properties.as_ref().and_then(|properties| properties.class.clone());
+        windows.push(TaskbarWindow
+        {
+            id: node.id.to_string(),
+            title: node.name.clone().unwrap_or_default(),
+            app_id: node.app_id.clone().or(class).unwrap_or_default(),
+            workspace,
+            output: output.filter(|name| *name != "__i3").map(str::to_string),
+            is_focused: node.focused,
+            is_urgent: node.urgent,
+        });
+        return;
+    }
+    for child in node.nodes.iter().chain(node.floating_nodes.iter())
+    {
+        collect_sway_windows(child, output, workspace, windows);
+    }
+}
+
+
+
+pub fn taskbar_snapshot() -> TaskbarSnapshot
+{
+    let Ok(mut connection) = Connection::new() else { return TaskbarSnapshot::default(); };
+    let mut windows = Vec::new();
+    if let Ok(tree) = connection.get_tree() { collect_sway_windows(&tree, None, 0, &mut windows); }
+    let workspaces = connection.get_workspaces().unwrap_or_default();
+    let focused = workspaces.iter().find(|workspace| workspace.focused);
+    TaskbarSnapshot
+    {
+        windows,
+        focused_workspace: focused.map_or(0, |workspace| sway_workspace_key(workspace.num, workspace.id)),
+        focused_output: focused.map(|workspace| workspace.output.clone()),
+        visible_workspaces: workspaces.iter().filter(|workspace| workspace.visible).map(|workspace| sway_workspace_key(workspace.num, workspace.id)).collect(),
+    }
+}
+
+
+
+pub fn taskbar_action_sway(id: &str, action: TaskbarAction)
+{
+    let command = match action
+    {
+        TaskbarAction::Focus => format!("[con_id={id}] focus"),
+        TaskbarAction::Close => format!("[con_id={id}] kill"),
+    };
+    match Connection::new()
+    {
+        Ok(mut conn) => if let Err(err) = conn.run_command(command) { println!("Warning!!! Couldn't {action:?} The Window {id} Using SwayIPC\nErr: {err}") },
+        Err(err) => println!("\n\n\nFailed To Connect With The SwayIPC!!!\nErr: {err}\n\n\n"),
+    }
+}
+
+
+
+fn find_sway_workspace<'a>(node: &'a Node, name: &str) -> Option<&'a Node>
+{
+    if node.node_type == NodeType::Workspace && node.name.as_deref() == Some(name) { return Some(node); }
+    node.nodes.iter().find_map(|child| find_sway_workspace(child, name))
+}
+
+
+
+pub fn workspace_count() -> Vec<WorkspaceInfo>
 { 
     let result_connection = Connection::new();
     if let Ok(mut connection) = result_connection
@@ -101,9 +229,30 @@ pub fn workspace_count() -> Vec<i32>
         let result_workspace_data = connection.get_workspaces();
         if let Ok(workspace_data) = result_workspace_data
         {
-            let mut workspace_num: Vec<i32> = workspace_data.iter().map(|item| item.num).collect();
-            workspace_num.retain(|&x| x != 0);
-            return workspace_num;
+            let tree = connection.get_tree().ok();
+            let windows_on = |name: &str, only_urgent: bool| tree.as_ref().and_then(|tree| find_sway_workspace(tree, name)).map_or(0, |node| sway_window_count(node, only_urgent));
+            let mut workspaces: Vec<WorkspaceInfo> = workspace_data.iter().filter(|item| item.num != 0).map(|item| WorkspaceInfo
+            {
+                id: sway_workspace_key(item.num, item.id),
+                number: item.num,
+                name: item.name.clone(),
+                output: Some(item.output.clone()),
+                is_focused: item.focused,
+                is_visible: item.visible,
+                is_urgent: item.urgent,
+                is_special: false,
+                windows: windows_on(&item.name, false),
+                urgent_windows: windows_on(&item.name, true),
+                app_ids: tree.as_ref().and_then(|tree| find_sway_workspace(tree, &item.name)).map(sway_app_ids).unwrap_or_default(),
+            }).collect();
+
+            // The scratchpad lives in the hidden `__i3_scratch` workspace
+            let scratchpad_windows = windows_on("__i3_scratch", false);
+            if scratchpad_windows > 0
+            {
+                workspaces.push(WorkspaceInfo { id: SWAY_SCRATCHPAD_ID, number: 0, name: "scratchpad".into(), is_special: true, windows: scratchpad_windows, ..Default::default() });
+            }
+            return workspaces;
         };
     }
     Vec::new()
@@ -125,6 +274,16 @@ pub fn change_workspace_sway(action: UserWorkspaceAction)
                     let output = conn.run_command(format!("workspace number {index}"));
                     if let Err(err) = output { println!("Warning!!! Couldn't Switch Workspaces With Index Using SwayIPC\nErr: {err}") }
                 }
+                UserWorkspaceAction::ChangeWithName(name) =>
+                {
+                    let output = conn.run_command(format!("workspace \"{}\"", name.replace('"', "\\\"")));
+                    if let Err(err) = output { println!("Warning!!! Couldn't Switch Workspaces With Name Using SwayIPC\nErr: {err}") }
+                }
+                UserWorkspaceAction::ToggleSpecial(_) =>
+                {
+                    let output = conn.run_command("scratchpad show");
+                    if let Err(err) = output { println!("Warning!!! Couldn't Toggle The Scratchpad Using SwayIPC\nErr: {err}") }
+                }
                 UserWorkspaceAction::MoveNext =>
                 {
                     let result_workspaces = conn.get_workspaces();
@@ -161,3 +320,56 @@ pub fn change_workspace_sway(action: UserWorkspaceAction)
         Err(err) => println!("\n\n\nFailed To Connect With The SwayIPC!!!\nErr: {err}\n\n\n")
     }
 }
+
+
+
+
+
+// ============ TESTS ============
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn numbered_workspace_key_is_its_number()
+    {
+        assert_eq!(sway_workspace_key(3, 42), 3);
+    }
+
+    #[test]
+    fn unnumbered_workspace_key_is_negative_container_id()
+    {
+        assert_eq!(sway_workspace_key(-1, 42), -42);
+        assert_ne!(sway_workspace_key(-1, 42), SWAY_SCRATCHPAD_ID);
+    }
+
+    fn node(kind: &str, name: &str, extra: &str, nodes: Vec<Node>) -> Node
+    {
+        let nodes_json = serde_json::to_string(&nodes).unwrap();
+        let json = format!(r#"{{
+            "id": 7, "name": "{name}", "type": "{kind}", "focused": false, "urgent": false, "sticky": false,
+            "border": "normal", "current_border_width": 0, "layout": "none", "orientation": "none", "percent": null,
+            "focus": [], "marks": [], "fullscreen_mode": 0,
+            "rect": {{"x":0,"y":0,"width":0,"height":0}}, "window_rect": {{"x":0,"y":0,"width":0,"height":0}},
+            "deco_rect": {{"x":0,"y":0,"width":0,"height":0}}, "geometry": {{"x":0,"y":0,"width":0,"height":0}},
+            {extra}
+            "nodes": {nodes_json}, "floating_nodes": []
+        }}"#);
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn sway_windows_remember_output_and_workspace()
+    {
+        let window = node("con", "vim", r#""pid": 42, "app_id": "foot","#, vec![]);
+        let workspace = node("workspace", "2", r#""num": 2,"#, vec![window]);
+        let output = node("output", "DP-1", "", vec![workspace]);
+        let root = node("root", "root", "", vec![output]);
+        let mut windows = Vec::new();
+        collect_sway_windows(&root, None, 0, &mut windows);
+        assert_eq!(windows.len(), 1);
+        assert_eq!((windows[0].title.as_str(), windows[0].app_id.as_str(), windows[0].workspace), ("vim", "foot", 2));
+        assert_eq!(windows[0].output.as_deref(), Some("DP-1"));
+    }
+}
@@ -0,0 +1,329 @@
+// ============ IMPORTS ============
+use iced::widget::button;
+
+
+
+
+
+// ============ CRATES ============
+use crate::helpers::style::{UserStyle, set_style};
+use crate::modules::compositor::taskbar_backend;
+use crate::AppData;
+
+
+
+
+
+
+
+// ============ CONFIG ============
+use serde::{Deserialize, Serialize};
+use crate::helpers::style::SideOption;
+use crate::helpers::color::{ColorType, Gradient};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TaskbarConfig
+{
+    pub taskbar_format:                         String,
+    pub taskbar_filter:                         TaskbarFilter,
+    pub taskbar_title_limit_len:                usize,
+    pub taskbar_show_title:                     bool,
+    pub taskbar_show_icons:                     bool,
+    pub taskbar_icon_size:                      u32,
+    pub taskbar_icon_spacing:                   u32,
+    pub taskbar_spacing:                        u32,
+    pub taskbar_padding:                        u16,
+    pub taskbar_text_size:                      u32,
+    pub taskbar_text_color:                     ColorType,
+    pub taskbar_button_color:                   ColorType,
+    pub taskbar_button_hovered_color:           ColorType,
+    pub taskbar_button_hovered_text_color:      ColorType,
+    pub taskbar_button_pressed_color:           ColorType,
+    pub taskbar_button_pressed_text_color:      ColorType,
+    pub taskbar_button_focused_color:           ColorType,
+    pub taskbar_focused_text_color:             ColorType,
+    pub taskbar_button_urgent_color:            ColorType,
+    pub taskbar_urgent_text_color:              ColorType,
+    pub taskbar_border_color:                   ColorType,
+    pub taskbar_border_size:                    f32,
+    pub taskbar_border_radius:                  [f32; 4],
+    pub taskbar_side_separator:                 Option<SideOption>,
+    pub taskbar_side_separator_color:           ColorType,
+    pub taskbar_side_separator_width:           f32,
+    pub taskbar_side_separator_height:          f32,
+    pub taskbar_button_gradient_color:          Option<Gradient>,
+    pub taskbar_button_focused_gradient_color:  Option<Gradient>,
+    pub taskbar_button_urgent_gradient_color:   Option<Gradient>,
+    pub taskbar_button_hovered_gradient_color:  Option<Gradient>,
+    pub taskbar_button_pressed_gradient_color:  Option<Gradient>,
+    pub taskbar_button_shadow_color:            Option<ColorType>,
+    pub taskbar_button_shadow_x:                f32,
+    pub taskbar_button_shadow_y:                f32,
+    pub taskbar_button_shadow_blur:             f32,
+}
+
+impl Default for TaskbarConfig
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            taskbar_format:                         "{title}".into(),
+            taskbar_filter:                         TaskbarFilter::All,
+            taskbar_title_limit_len:                20,
+            taskbar_show_title:                     true,
+            taskbar_show_icons:                     true,
+            taskbar_icon_size:                      16,
+            taskbar_icon_spacing:                   4,
+            taskbar_spacing:                        4,
+            taskbar_padding:                        4,
+            taskbar_text_size:                      13,
+            taskbar_text_color:                     ColorType::RGB([192, 191, 188]),
+            taskbar_button_color:                   ColorType::RGB([42, 42, 42]),
+            taskbar_button_hovered_color:           ColorType::RGB([61, 61, 61]),
+            taskbar_button_hovered_text_color:      ColorType::RGB([255, 255, 255]),
+            taskbar_button_pressed_color:           ColorType::RGB([28, 28, 28]),
+            taskbar_button_pressed_text_color:      ColorType::RGB([255, 255, 255]),
+            taskbar_button_focused_color:           ColorType::RGB([74, 74, 90]),
+            taskbar_focused_text_color:             ColorType::RGB([255, 255, 255]),
+            taskbar_button_urgent_color:            ColorType::RGB([192, 60, 40]),
+            taskbar_urgent_text_color:              ColorType::RGB([255, 255, 255]),
+            taskbar_border_color:                   ColorType::RGB([42, 42, 42]),
+            taskbar_border_size:                    1.0,
+            taskbar_border_radius:                  [6.0, 6.0, 6.0, 6.0],
+            taskbar_side_separator:                 None,
+            taskbar_side_separator_color:           ColorType::RGB([61, 61, 61]),
+            taskbar_side_separator_width:           1.,
+            taskbar_side_separator_height:          18.,
+            taskbar_button_gradient_color:          None,
+            taskbar_button_focused_gradient_color:  None,
+            taskbar_button_urgent_gradient_color:   None,
+            taskbar_button_hovered_gradient_color:  None,
+            taskbar_button_pressed_gradient_color:  None,
+            taskbar_button_shadow_color:            None,
+            taskbar_button_shadow_x:                0.0,
+            taskbar_button_shadow_y:                0.0,
+            taskbar_button_shadow_blur:             0.0,
+        }
+    }
+}
+
+// ============ ENUM/STRUCT, ETC ============
+#[derive(Default, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum TaskbarFilter
+{
+    #[default] All,
+    CurrentWorkspace,
+    CurrentOutput,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TaskbarAction
+{
+    Focus,
+    Close,
+}
+
+/// A toplevel window. `id` is the compositor's own handle (Hyprland address, Sway container id, Niri window id),
+/// `workspace` uses the same keys as the workspace modules.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct TaskbarWindow
+{
+    pub id: String,
+    pub title: String,
+    pub app_id: String,
+    pub workspace: i32,
+    pub output: Option<String>,
+    pub is_focused: bool,
+    pub is_urgent: bool,
+}
+
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct TaskbarSnapshot
+{
+    pub windows: Vec<TaskbarWindow>,
+    pub focused_workspace: i32,
+    pub focused_output: Option<String>,
+    /// Workspaces shown on any output right now.
+    pub visible_workspaces: Vec<i32>,
+}
+
+#[derive(Default, Clone)]
+pub struct TaskbarData
+{
+    pub snapshot: TaskbarSnapshot,
+}
+
+
+
+
+
+// ============ FUNCTIONS ============
+pub fn fetch_taskbar_snapshot() -> Option<TaskbarSnapshot>
+{
+    taskbar_backend().map(|backend| backend.windows())
+}
+
+
+
+pub fn taskbar_window_action(id: String, action: TaskbarAction)
+{
+    match taskbar_backend()
+    {
+        Some(backend) => backend.window_action(&id, action),
+        None => println!("Warning!!! No Supported Compositor Found For The Taskbar"),
+    }
+}
+
+
+
+/// Applies `taskbar_filter`. A bar pinned to an output (`display`) follows that output, otherwise the focused one.
+pub fn filter_taskbar_windows<'a>(snapshot: &'a TaskbarSnapshot, filter: &TaskbarFilter, bar_output: Option<&str>) -> Vec<&'a TaskbarWindow>
+{
+    let output = bar_output.map(str::to_string).or_else(|| snapshot.focused_output.clone());
+    snapshot.windows.iter().filter(|window| match filter
+    {
+        TaskbarFilter::All => true,
+        TaskbarFilter::CurrentOutput => output.is_none() || window.output == output,
+        TaskbarFilter::CurrentWorkspace if bar_output.is_some() => window.output == output && snapshot.visible_workspaces.contains(&window.workspace),
+        TaskbarFilter::CurrentWorkspace => window.workspace == snapshot.focused_workspace,
+    }).collect()
+}
+
+
+
+pub fn visible_taskbar_windows(app: &AppData) -> Vec<&TaskbarWindow>
+{
+    filter_taskbar_windows(&app.modules_data.taskbar_data.snapshot, &app.ron_config.taskbar.taskbar_filter, app.ron_config.general.display.as_deref())
+}
+
+
+
+pub fn define_taskbar_text(app: &AppData, window: &TaskbarWindow) -> String
+{
+    if !app.ron_config.taskbar.taskbar_show_title { return String::new(); }
+    app.ron_config.taskbar.taskbar_format.replace("{title}", &window.title).replace("{app_id}", &window.app_id)
+}
+
+
+
+pub fn define_taskbar_style(app: &AppData, status: button::Status, window: &TaskbarWindow) -> iced::widget::button::Style
+{
+    let config = &app.ron_config.taskbar;
+    let (normal, normal_text, normal_gradient) = if window.is_focused
+    { (config.taskbar_button_focused_color, config.taskbar_focused_text_color, config.taskbar_button_focused_gradient_color.clone()) }
+    else if window.is_urgent
+    { (config.taskbar_button_urgent_color, config.taskbar_urgent_text_color, config.taskbar_button_urgent_gradient_color.clone()) }
+    else
+    { (config.taskbar_button_color, config.taskbar_text_color, config.taskbar_button_gradient_color.clone()) };
+
+    set_style(UserStyle
+    {
+        status,
+        normal,
+        normal_text,
+        normal_gradient,
+        hovered:           config.taskbar_button_hovered_color,
+        hovered_text:      config.taskbar_button_hovered_text_color,
+        pressed:           config.taskbar_button_pressed_color,
+        pressed_text:      config.taskbar_button_pressed_text_color,
+        border_color:      config.taskbar_border_color,
+        border_size:       config.taskbar_border_size,
+        border_radius:     config.taskbar_border_radius,
+        hovered_gradient:  config.taskbar_button_hovered_gradient_color.clone(),
+        pressed_gradient:  config.taskbar_button_pressed_gradient_color.clone(),
+        shadow_color:      config.taskbar_button_shadow_color,
+        shadow_x:          config.taskbar_button_shadow_x,
+        shadow_y:          config.taskbar_button_shadow_y,
+        shadow_blur:       config.taskbar_button_shadow_blur,
+    })
+}
+
+
+
+
+
+// ============ TESTS ============
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use iced::{Background, Color};
+
+    fn window(id: &str, workspace: i32, output: &str) -> TaskbarWindow
+    {
+        TaskbarWindow { id: id.into(), title: format!("title {id}"), app_id: "foot".into(), workspace, output: Some(output.into()), ..Default::default() }
+    }
+
+    fn snapshot() -> TaskbarSnapshot
+    {
+        TaskbarSnapshot
+        {
+            windows: vec![window("a", 1, "DP-1"), window("b", 2, "DP-1"), window("c", 3, "HDMI-A-1")],
+            focused_workspace: 1,
+            focused_output: Some("DP-1".into()),
+            visible_workspaces: vec![1, 3],
+        }
+    }
+
+    fn ids(windows: Vec<&TaskbarWindow>) -> Vec<&str>
+    {
+        windows.into_iter().map(|window| window.id.as_str()).collect()
+    }
+
+    // ---- filter_taskbar_windows -----------------------------------------------
+
+    #[test]
+    fn filter_all_keeps_every_window()
+    {
+        assert_eq!(ids(filter_taskbar_windows(&snapshot(), &TaskbarFilter::All, None)), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn filter_current_workspace_follows_focus_without_display()
+    {
+        assert_eq!(ids(filter_taskbar_windows(&snapshot(), &TaskbarFilter::CurrentWorkspace, None)), vec!["a"]);
+    }
+
+    #[test]
+    fn filter_current_workspace_follows_bar_output()
+    {
+        assert_eq!(ids(filter_taskbar_windows(&snapshot(), &TaskbarFilter::CurrentWorkspace, Some("HDMI-A-1"))), vec!["c"]);
+    }
+
+    #[test]
+    fn filter_current_output_uses_display_then_focused_output()
+    {
+        assert_eq!(ids(filter_taskbar_windows(&snapshot(), &TaskbarFilter::CurrentOutput, None)), vec!["a", "b"]);
+        assert_eq!(ids(filter_taskbar_windows(&snapshot(), &TaskbarFilter::CurrentOutput, Some("HDMI-A-1"))), vec!["c"]);
+    }
+
+    // ---- text and style -------------------------------------------------------
+
+    #[test]
+    fn taskbar_text_fills_placeholders_or_hides()
+    {
+        let mut app = AppData { ..Default::default() };
+        app.ron_config.taskbar.taskbar_format = "{app_id}: {title}".into();
+        assert_eq!(define_taskbar_text(&app, &window("a", 1, "DP-1")), "foot: title a");
+        app.ron_config.taskbar.taskbar_show_title = false;
+        assert_eq!(define_taskbar_text(&app, &window("a", 1, "DP-1")), "");
+    }
+
+    #[test]
+    fn focused_window_wins_over_urgent_style()
+    {
+        let mut app = AppData { ..Default::default() };
+        app.ron_config.taskbar.taskbar_button_focused_color = ColorType::RGB([1, 2, 3]);
+        app.ron_config.taskbar.taskbar_button_urgent_color = ColorType::RGB([250, 0, 0]);
+        let mut focused = window("a", 1, "DP-1");
+        focused.is_focused = true;
+        focused.is_urgent = true;
+        let style = define_taskbar_style(&app, button::Status::Active, &focused);
+        assert_eq!(style.background, Some(Background::Color(Color::from_rgb8(1, 2, 3))));
+        focused.is_focused = false;
+        let style = define_taskbar_style(&app, button::Status::Active, &focused);
+        assert_eq!(style.background, Some(Background::Color(Color::from_rgb8(250, 0, 0))));
+    }
+}
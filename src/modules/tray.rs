@@ -1,6 +1,6 @@
 // ============ IMPORTS ============
 use zbus::{Connection, fdo::DBusProxy, interface, message::Header, object_server::SignalEmitter};
-use iced::{Element, widget::{button, image, text}, futures::Stream};
+use iced::{Element, Length, Task, Theme, widget::{button, column, container, image, row, text}, futures::Stream};
 use std::{pin::Pin, collections::{HashMap, HashSet}, sync::Mutex};
 use tokio::sync::mpsc::{self, Sender};
 use serde::{Deserialize, Serialize};
@@ -13,8 +13,9 @@ use std::sync::LazyLock;
 
 // ============ CRATES ============
 use crate::helpers::{color::{ColorType, Gradient}, icons::fetch_icon, style::{UserStyle, set_style, SideOption}};
+use crate::context_menu::{MenuIcon, context_menu_background_button_style, resolve_popup_anchor};
 use crate::update::Message;
-use crate::AppData;
+use crate::{AppData, WindowInfo};
 
 
 
@@ -69,12 +70,16 @@ pub struct StatusNotifierWatcher
     pub connection: Connection,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct MenuItem 
 {
     pub _visible: bool,
     pub label: String,
     pub id: i32,
+    pub children: Vec<MenuItem>,
+    pub icon: Option<MenuIcon>,
+    pub command: Vec<String>,
+    pub is_separator: bool,
 }
 
 
@@ -110,6 +115,11 @@ pub struct TrayConfig
     pub tray_button_shadow_x:              f32,
     pub tray_button_shadow_y:              f32,
     pub tray_button_shadow_blur:           f32,
+    pub tray_pinned_items:                 Option<usize>,
+    pub tray_expander_text:                String,
+    pub tray_expander_text_size:           u32,
+    pub tray_drawer_columns:               usize,
+    pub tray_drawer_spacing:               u32,
 }
 
 impl Default for TrayConfig
@@ -140,6 +150,11 @@ impl Default for TrayConfig
             tray_button_shadow_x:              0.0,
             tray_button_shadow_y:              0.0,
             tray_button_shadow_blur:           0.0,
+            tray_pinned_items:                 None,
+            tray_expander_text:                "▾".to_string(),
+            tray_expander_text_size:           14,
+            tray_drawer_columns:               4,
+            tray_drawer_spacing:               4,
         }
     }
 }
@@ -411,22 +426,34 @@ fn extract_layout_node(id: i32, props: &HashMap<String, zbus::zvariant::OwnedVal
         }
     };
 
-    if let Some(label) = get_str("label") 
+    let extract_children = |out: &mut Vec<MenuItem>|
     {
-        let visible = get_bool("visible").unwrap_or(true);
-        let enabled = get_bool("enabled").unwrap_or(true);
-        let ty      = get_str("type").unwrap_or_else(|| "default".into());
-        if id != 0 && visible && enabled && ty != "separator" 
+        for child in children 
         {
-            out.push(MenuItem { id, label, _visible: visible });
+            if let Ok((child_id, child_props, child_children)) = DBusMenuLayout::try_from(child.clone()) 
+            {
+                extract_layout_node(child_id, &child_props, &child_children, out);
+            }
         }
+    };
+
+    // The root node is invisible, its children are the top level of the menu
+    if id == 0
+    {
+        extract_children(out);
+        return;
     }
 
-    for child in children 
+    if let Some(label) = get_str("label") 
     {
-        if let Ok((child_id, child_props, child_children)) = DBusMenuLayout::try_from(child.clone()) 
+        let visible = get_bool("visible").unwrap_or(true);
+        let enabled = get_bool("enabled").unwrap_or(true);
+        let ty      = get_str("type").unwrap_or_else(|| "default".into());
+        if visible && enabled && ty != "separator" 
         {
-            extract_layout_node(child_id, &child_props, &child_children, out);
+            let mut submenu = Vec::new();
+            extract_children(&mut submenu);
+            out.push(MenuItem { id, label, _visible: visible, children: submenu, ..Default::default() });
         }
     }
 }
@@ -447,7 +474,7 @@ pub async fn load_menu(service: &str, menu_path: &str) -> zbus::Result<Vec<MenuI
 {
     let conn = Connection::session().await?;
     let proxy = zbus::Proxy::new(&conn, service, menu_path, "com.canonical.dbusmenu").await?;
-    let (_, (root_id, root_props, root_children)): (u32, DBusMenuLayout) = proxy.call("GetLayout", &(0i32, 1i32, Vec::<String>::new())).await?;
+    let (_, (root_id, root_props, root_children)): (u32, DBusMenuLayout) = proxy.call("GetLayout", &(0i32, -1i32, Vec::<String>::new())).await?;
     let mut entries = Vec::new();
     extract_layout_node(root_id, &root_props, &root_children, &mut entries);
     Ok(entries)
@@ -496,6 +523,66 @@ pub fn define_tray_icon<'a>(app: &'a AppData, icon: &'a Option<iced::widget::ima
 
 
 
+/// Returns how many tray items are drawn inline on the bar and whether the rest goes into the drawer.
+pub fn split_tray_items(total: usize, pinned: Option<usize>) -> (usize, bool)
+{
+    match pinned
+    {
+        Some(pinned) if pinned < total => (pinned, true),
+        _ => (total, false),
+    }
+}
+
+
+
+pub fn get_tray_drawer_size(app: &AppData) -> (u32, u32)
+{
+    let (inline_count, _) = split_tray_items(app.modules_data.tray_icons.len(), app.ron_config.tray.tray_pinned_items);
+    let overflow_count = app.modules_data.tray_icons.len() - inline_count;
+    let columns = app.ron_config.tray.tray_drawer_columns.max(1).min(overflow_count.max(1)) as u32;
+    let rows = overflow_count.div_ceil(app.ron_config.tray.tray_drawer_columns.max(1)).max(1) as u32;
+    let cell_size = app.ron_config.tray.tray_icon_size + app.ron_config.tray.tray_button_size as u32 * 2;
+    let spacing = app.ron_config.tray.tray_drawer_spacing;
+    let background_size = app.ron_config.context_menu.context_menu_background_size as u32;
+    (
+        columns * cell_size + columns.saturating_sub(1) * spacing + background_size * 2,
+        rows * cell_size + rows.saturating_sub(1) * spacing + background_size * 2
+    )
+}
+
+
+
+pub fn create_tray_drawer(app: &mut AppData) -> Task<Message>
+{
+    let id = iced::window::Id::unique();
+    app.ids.insert(id, WindowInfo::TrayDrawer);
+    resolve_popup_anchor(app, id)
+}
+
+
+
+pub fn tray_drawer_view(app: &AppData) -> Element<'_, Message>
+{
+    let (inline_count, _) = split_tray_items(app.modules_data.tray_icons.len(), app.ron_config.tray.tray_pinned_items);
+    let overflow: Vec<(usize, &Option<image::Handle>)> = app.modules_data.tray_icons.iter().enumerate().skip(inline_count).map(|(i, (icon, _))| (i, icon)).collect();
+
+    let rows: Vec<Element<_>> = overflow.chunks(app.ron_config.tray.tray_drawer_columns.max(1)).map(|chunk|
+    {
+        let buttons: Vec<Element<_>> = chunk.iter().map(|(i, icon)|
+        {
+            button(define_tray_icon(app, *icon)).style(|_: &Theme, status: button::Status| define_tray_style(app, status)).padding(app.ron_config.tray.tray_button_size).on_press(Message::TrayIconClicked(*i)).into()
+        }).collect();
+        row(buttons).spacing(app.ron_config.tray.tray_drawer_spacing).into()
+    }).collect();
+
+    container
+    (
+        column(rows).spacing(app.ron_config.tray.tray_drawer_spacing)
+    ).padding(app.ron_config.context_menu.context_menu_background_size).width(Length::Fill).height(Length::Fill).style(move |_: &Theme| context_menu_background_button_style(&app.ron_config)).into()
+}
+
+
+
 
 
 // ============ TESTS ============
@@ -589,6 +676,32 @@ mod tests
         assert_eq!(out.len(), 1);
     }
 
+    fn child_node(id: i32, label: &str, children: Vec<OwnedValue>) -> OwnedValue
+    {
+        let props: HashMap<String, OwnedValue> = make_props(label, true, true, "default");
+        OwnedValue::try_from(Value::from((id, props, children.into_iter().map(Value::from).collect::<Vec<Value>>()))).unwrap()
+    }
+
+    #[test]
+    fn extract_layout_root_children_are_top_level()
+    {
+        let mut out = Vec::new();
+        let children = [child_node(1, "Open", vec![]), child_node(2, "Quit", vec![])];
+        extract_layout_node(0, &HashMap::new(), &children, &mut out);
+        assert_eq!(out.iter().map(|i| i.label.as_str()).collect::<Vec<_>>(), ["Open", "Quit"]);
+    }
+
+    #[test]
+    fn extract_layout_nested_children_become_submenu()
+    {
+        let mut out = Vec::new();
+        let children = [child_node(1, "More", vec![child_node(3, "Inner", vec![])])];
+        extract_layout_node(0, &HashMap::new(), &children, &mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].children.len(), 1);
+        assert_eq!(out[0].children[0].label, "Inner");
+    }
+
     #[test]
     fn extract_layout_no_label_produces_nothing()
     {
@@ -634,6 +747,74 @@ mod tests
         assert_eq!(style.background, Some(Background::Color(Color::from_rgb8(80, 90, 100))));
     }
  
+    // ---- split_tray_items ---------------------------------------------------
+
+    #[test]
+    fn split_tray_items_without_pin_shows_everything_inline()
+    {
+        assert_eq!(split_tray_items(6, None), (6, false));
+    }
+
+    #[test]
+    fn split_tray_items_pin_below_total_overflows()
+    {
+        assert_eq!(split_tray_items(6, Some(2)), (2, true));
+    }
+
+    #[test]
+    fn split_tray_items_pin_equal_or_above_total_does_not_overflow()
+    {
+        assert_eq!(split_tray_items(3, Some(3)), (3, false));
+        assert_eq!(split_tray_items(3, Some(10)), (3, false));
+    }
+
+    #[test]
+    fn split_tray_items_pin_zero_moves_everything_to_drawer()
+    {
+        assert_eq!(split_tray_items(4, Some(0)), (0, true));
+    }
+
+    // ---- get_tray_drawer_size -----------------------------------------------
+
+    fn make_drawer_app(icon_count: usize, pinned: usize, columns: usize) -> AppData
+    {
+        let mut app = AppData { ..Default::default() };
+        app.modules_data.tray_icons = (0..icon_count).map(|i| (None, format!("svc{i}|/StatusNotifierItem"))).collect();
+        app.ron_config.tray.tray_pinned_items = Some(pinned);
+        app.ron_config.tray.tray_drawer_columns = columns;
+        app.ron_config.tray.tray_icon_size = 20;
+        app.ron_config.tray.tray_button_size = 5;
+        app.ron_config.tray.tray_drawer_spacing = 4;
+        app.ron_config.context_menu.context_menu_background_size = 0;
+        app
+    }
+
+    #[test]
+    fn tray_drawer_size_single_row()
+    {
+        // 3 overflowing items, 4 columns -> 3 cells of 30px + 2 gaps of 4px
+        let (w, h) = get_tray_drawer_size(&make_drawer_app(5, 2, 4));
+        assert_eq!(w, 98);
+        assert_eq!(h, 30);
+    }
+
+    #[test]
+    fn tray_drawer_size_wraps_into_rows()
+    {
+        // 5 overflowing items, 2 columns -> 3 rows
+        let (w, h) = get_tray_drawer_size(&make_drawer_app(6, 1, 2));
+        assert_eq!(w, 64);
+        assert_eq!(h, 98);
+    }
+
+    #[test]
+    fn tray_drawer_size_zero_columns_is_treated_as_one()
+    {
+        let (w, h) = get_tray_drawer_size(&make_drawer_app(3, 1, 0));
+        assert_eq!(w, 30);
+        assert_eq!(h, 64);
+    }
+
     #[test]
     fn tray_style_all_statuses_produce_background()
     {
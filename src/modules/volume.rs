@@ -1,6 +1,6 @@
 // ============ IMPORTS ============
 use libpulse_binding::{callbacks::ListResult, context::{Context, FlagSet as ContextFlagSet, introspect::Introspector, subscribe::{Facility, InterestMaskSet}}, mainloop::threaded::Mainloop, volume::Volume};
-use std::{pin::Pin, sync::{Arc, Mutex}};
+use std::{collections::HashMap, pin::Pin, sync::{Arc, Mutex}};
 use serde::{Deserialize, Serialize};
 use iced::{Task, widget::button};
  
@@ -10,8 +10,9 @@ use iced::{Task, widget::button};
  
  
 // ============ CRATES ============
-use crate::helpers::{color::{ColorType, Gradient}, style::{TextOrientation, UserStyle, orient_text, set_style, SideOption}};
+use crate::helpers::{color::{ColorType, Gradient}, command::{CommandOptions, SYSTEM_COMMAND_TIMEOUT, run_command}, style::{StateStyle, TextOrientation, UserStyle, apply_state_style, module_state, orient_text, set_style, SideOption}};
 use crate::ron::ActionOnClick;
+use crate::modules::data::Modules;
 use crate::update::Message;
 use crate::AppData;
 
@@ -54,6 +55,7 @@ pub struct VolumeOutputConfig
     pub volume_output_button_shadow_x:               f32,
     pub volume_output_button_shadow_y:               f32,
     pub volume_output_button_shadow_blur:            f32,
+    pub volume_output_states:                        HashMap<String, StateStyle>,
 }
 
 impl Default for VolumeOutputConfig
@@ -90,6 +92,7 @@ impl Default for VolumeOutputConfig
             volume_output_button_shadow_x:               0.0,
             volume_output_button_shadow_y:               0.0,
             volume_output_button_shadow_blur:            0.0,
+            volume_output_states:                        HashMap::new(),
         }
     }
 }
@@ -188,6 +191,7 @@ pub struct VolumeInputConfig
     pub volume_input_button_shadow_x:               f32,
     pub volume_input_button_shadow_y:               f32,
     pub volume_input_button_shadow_blur:            f32,
+    pub volume_input_states:                        HashMap<String, StateStyle>,
 }
 
 impl Default for VolumeInputConfig
@@ -224,6 +228,7 @@ impl Default for VolumeInputConfig
             volume_input_button_shadow_x:               0.0,
             volume_input_button_shadow_y:               0.0,
             volume_input_button_shadow_blur:            0.0,
+            volume_input_states:                        HashMap::new(),
         }
     }
 }
@@ -298,8 +303,6 @@ pub struct VolumeData
     pub input_volume_level: String,
     pub volume_output_is_muted: bool,
     pub volume_input_is_muted: bool,
-    pub is_hovering_volume_output: bool,
-    pub is_hovering_volume_input: bool,
     pub volume_output_raw: f32,
     pub volume_input_raw: f32,
 }
@@ -478,10 +481,8 @@ pub fn volume(volume_modifier: VolumeAction) -> Task<crate::update::Message>
         VolumeAction::DecreaseInput(v)  => ("set-volume", "@DEFAULT_SOURCE@", format!("{}%-", v)),
         VolumeAction::MuteInput         => ("set-mute",   "@DEFAULT_SOURCE@", "toggle".into()),
     };
-    Task::perform(async move 
-    {
-        let _ = tokio::process::Command::new("wpctl").args([args.0, args.1, &args.2]).output().await;
-    },|_| Message::Nothing,)
+    let options = CommandOptions { label: "wpctl".into(), timeout: Some(SYSTEM_COMMAND_TIMEOUT), ..Default::default() };
+    Task::perform(run_command("wpctl".into(), vec![args.0.into(), args.1.into(), args.2], options), |_| Message::Nothing)
 }
  
 
@@ -512,7 +513,7 @@ pub fn define_volume_output_style(app: &AppData, status: button::Status) -> iced
         let border_size =       app.ron_config.volume_output.volume_output_border_size;
         let border_color =  app.ron_config.volume_output.volume_output_border_color;
         let border_radius =     app.ron_config.volume_output.volume_output_border_radius;
-        set_style(UserStyle { status, hovered, hovered_text, pressed_text, pressed, normal, normal_text, border_color, border_size, border_radius, normal_gradient: app.ron_config.volume_output.volume_output_button_gradient_color.clone(), hovered_gradient: app.ron_config.volume_output.volume_output_button_hovered_gradient_color.clone(), pressed_gradient: app.ron_config.volume_output.volume_output_button_pressed_gradient_color.clone(), shadow_color: app.ron_config.volume_output.volume_output_button_shadow_color, shadow_x: app.ron_config.volume_output.volume_output_button_shadow_x, shadow_y: app.ron_config.volume_output.volume_output_button_shadow_y, shadow_blur: app.ron_config.volume_output.volume_output_button_shadow_blur })
+        set_style(apply_state_style(UserStyle { status, hovered, hovered_text, pressed_text, pressed, normal, normal_text, border_color, border_size, border_radius, normal_gradient: app.ron_config.volume_output.volume_output_button_gradient_color.clone(), hovered_gradient: app.ron_config.volume_output.volume_output_button_hovered_gradient_color.clone(), pressed_gradient: app.ron_config.volume_output.volume_output_button_pressed_gradient_color.clone(), shadow_color: app.ron_config.volume_output.volume_output_button_shadow_color, shadow_x: app.ron_config.volume_output.volume_output_button_shadow_x, shadow_y: app.ron_config.volume_output.volume_output_button_shadow_y, shadow_blur: app.ron_config.volume_output.volume_output_button_shadow_blur }, module_state(app, &Modules::VolumeOutput), app.modules_data.state_blink_off))
     }
 }
 
@@ -544,7 +545,7 @@ pub fn define_volume_input_style(app: &AppData, status: button::Status) -> iced:
         let border_size =       app.ron_config.volume_input.volume_input_border_size;
         let border_color =  app.ron_config.volume_input.volume_input_border_color;
         let border_radius =     app.ron_config.volume_input.volume_input_border_radius;
-        set_style(UserStyle { status, hovered, hovered_text, pressed_text, pressed, normal, normal_text, border_color, border_size, border_radius, normal_gradient: app.ron_config.volume_input.volume_input_button_gradient_color.clone(), hovered_gradient: app.ron_config.volume_input.volume_input_button_hovered_gradient_color.clone(), pressed_gradient: app.ron_config.volume_input.volume_input_button_pressed_gradient_color.clone(), shadow_color: app.ron_config.volume_input.volume_input_button_shadow_color, shadow_x: app.ron_config.volume_input.volume_input_button_shadow_x, shadow_y: app.ron_config.volume_input.volume_input_button_shadow_y, shadow_blur: app.ron_config.volume_input.volume_input_button_shadow_blur })
+        set_style(apply_state_style(UserStyle { status, hovered, hovered_text, pressed_text, pressed, normal, normal_text, border_color, border_size, border_radius, normal_gradient: app.ron_config.volume_input.volume_input_button_gradient_color.clone(), hovered_gradient: app.ron_config.volume_input.volume_input_button_hovered_gradient_color.clone(), pressed_gradient: app.ron_config.volume_input.volume_input_button_pressed_gradient_color.clone(), shadow_color: app.ron_config.volume_input.volume_input_button_shadow_color, shadow_x: app.ron_config.volume_input.volume_input_button_shadow_x, shadow_y: app.ron_config.volume_input.volume_input_button_shadow_y, shadow_blur: app.ron_config.volume_input.volume_input_button_shadow_blur }, module_state(app, &Modules::VolumeInput), app.modules_data.state_blink_off))
     }
 }
 
@@ -554,6 +555,18 @@ pub fn define_volume_text(text: &str, text_orientation: &TextOrientation) -> Str
 
 
 
+/// The formatted volume level, or the active state's format ({percent} is the percentage) when it has one.
+pub fn define_volume_level(level: &str, raw: f32, state: Option<&StateStyle>) -> String
+{
+    match state.and_then(|state| state.format.as_deref())
+    {
+        Some(format) => format.replace("{percent}", &((raw * 100.).round() as u32).to_string()),
+        None => level.to_string(),
+    }
+}
+
+
+
 
 
 // ============ TESTS ============
@@ -580,6 +593,15 @@ mod tests
         assert_eq!(result, "a\nb\nc");
     }
  
+    #[test]
+    fn volume_level_uses_state_format_when_set()
+    {
+        let loud = StateStyle { threshold: 90., format: Some("LOUD {percent}%".into()), ..Default::default() };
+        assert_eq!(define_volume_level("🔊 95%", 0.95, Some(&loud)), "LOUD 95%");
+        assert_eq!(define_volume_level("🔊 95%", 0.95, Some(&StateStyle::default())), "🔊 95%");
+        assert_eq!(define_volume_level("🔊 95%", 0.95, None), "🔊 95%");
+    }
+ 
     #[test]
     fn volume_text_empty_string_both_orientations()
     {
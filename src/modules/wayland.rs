@@ -0,0 +1,768 @@
+use std::{collections::HashMap, pin::Pin, sync::{Mutex, atomic::{AtomicBool, Ordering}}};
+
+// ============ IMPORTS ============
+use wayland_client::{backend::ObjectId, event_created_child, globals::{registry_queue_init, GlobalListContents}, protocol::{wl_output::{self, WlOutput}, wl_registry::{self, WlRegistry}, wl_seat::WlSeat}, Connection, Dispatch, Proxy, QueueHandle, WEnum};
+use wayland_protocols::ext::workspace::v1::client::{ext_workspace_group_handle_v1::{self, ExtWorkspaceGroupHandleV1}, ext_workspace_handle_v1::{self, ExtWorkspaceHandleV1}, ext_workspace_manager_v1::{self, ExtWorkspaceManagerV1}};
+
+
+
+
+
+// ============ CRATES ============
+use crate::{modules::{focused_window::FocusedWindowData, workspaces::{UserWorkspaceAction, WorkspaceInfo}}, update::Message};
+use self::river_control::{zriver_command_callback_v1::{self, ZriverCommandCallbackV1}, zriver_control_v1::ZriverControlV1};
+use self::river_status::{zriver_output_status_v1::{self, ZriverOutputStatusV1}, zriver_seat_status_v1::{self, ZriverSeatStatusV1}, zriver_status_manager_v1::ZriverStatusManagerV1};
+
+
+
+
+
+// ============ PROTOCOLS ============
+// River's protocols aren't part of wayland-protocols, the bindings are generated from the xml files in `protocols/`.
+#[allow(dead_code, non_camel_case_types, non_upper_case_globals, unused_imports, clippy::all)]
+pub mod river_status
+{
+    use wayland_client;
+    use wayland_client::protocol::*;
+
+    pub mod __interfaces
+    {
+        use wayland_client::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!("protocols/river-status-unstable-v1.xml");
+    }
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!("protocols/river-status-unstable-v1.xml");
+}
+
+#[allow(dead_code, non_camel_case_types, non_upper_case_globals, unused_imports, clippy::all)]
+pub mod river_control
+{
+    use wayland_client;
+    use wayland_client::protocol::*;
+
+    pub mod __interfaces
+    {
+        use wayland_client::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!("protocols/river-control-unstable-v1.xml");
+    }
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!("protocols/river-control-unstable-v1.xml");
+}
+
+
+
+
+
+// ============ ENUM/STRUCT, ETC ============
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkspaceProtocol
+{
+    /// `zriver_status_manager_v1`, workspaces are River's 32 tags.
+    River,
+    /// `ext_workspace_manager_v1`, implemented by labwc, Wayfire, and others.
+    ExtWorkspace,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RiverOutput
+{
+    pub name: Option<String>,
+    pub focused_tags: u32,
+    /// One tag bitmask per view on the output.
+    pub view_tags: Vec<u32>,
+    pub urgent_tags: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExtWorkspace
+{
+    /// Assigned in announcement order, ext-workspace only gives opaque handles.
+    pub key: i32,
+    pub name: String,
+    pub coordinates: Vec<u32>,
+    pub output: Option<String>,
+    pub is_active: bool,
+    pub is_urgent: bool,
+    pub is_hidden: bool,
+}
+
+struct OutputEntry
+{
+    global_name: u32,
+    status: RiverOutput,
+}
+
+struct ExtWorkspaceEntry
+{
+    handle: ExtWorkspaceHandleV1,
+    group: Option<ObjectId>,
+    workspace: ExtWorkspace,
+}
+
+/// What the listener thread learns from the compositor.
+#[derive(Default)]
+struct WaylandState
+{
+    outputs: HashMap<ObjectId, OutputEntry>,
+    groups: HashMap<ObjectId, Vec<ObjectId>>,
+    workspaces: Vec<ExtWorkspaceEntry>,
+    next_key: i32,
+    river_status: Option<ZriverStatusManagerV1>,
+    focused_output: Option<ObjectId>,
+    focused_view: Option<String>,
+    mode: Option<String>,
+    changed: bool,
+}
+
+/// What the rest of the bar reads, and the objects needed to send requests from outside the listener thread.
+struct WaylandShared
+{
+    connection: Connection,
+    queue: QueueHandle<WaylandState>,
+    protocol: WorkspaceProtocol,
+    ext_manager: Option<ExtWorkspaceManagerV1>,
+    ext_handles: Vec<(i32, ExtWorkspaceHandleV1)>,
+    river_control: Option<ZriverControlV1>,
+    seat: Option<WlSeat>,
+    river_focused_tags: u32,
+    current: i32,
+    workspaces: Vec<WorkspaceInfo>,
+    focused_title: Option<String>,
+}
+
+
+
+
+
+// ============ STATICS ============
+static WAYLAND_SHARED: Mutex<Option<WaylandShared>> = Mutex::new(None);
+static WAYLAND_UNSUPPORTED: AtomicBool = AtomicBool::new(false);
+
+
+
+
+
+// ============ FUNCTIONS ============
+pub fn wayland_event_subscription() -> Pin<Box<dyn futures::Stream<Item = Message> + Send>>
+{
+    Box::pin(async_stream::stream!
+    {
+        loop
+        {
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
+            std::thread::spawn(move || run_wayland_listener(tx));
+            while let Some(msg) = rx.recv().await { yield msg; }
+
+            if WAYLAND_UNSUPPORTED.load(Ordering::Relaxed) { break; }
+            eprintln!("[icebar] wayland workspace listener stopped — reconnecting in 2s");
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        }
+    })
+}
+
+
+
+fn run_wayland_listener(tx: tokio::sync::mpsc::UnboundedSender<Message>)
+{
+    let connection = match Connection::connect_to_env()
+    {
+        Ok(connection) => connection,
+        Err(e) => { eprintln!("[icebar] wayland connect failed: {e}"); return; }
+    };
+    let (globals, mut queue) = match registry_queue_init::<WaylandState>(&connection)
+    {
+        Ok(init) => init,
+        Err(e) => { eprintln!("[icebar] wayland registry failed: {e}"); return; }
+    };
+    let qh = queue.handle();
+    let mut state = WaylandState::default();
+
+    // River also announces ext-workspace on recent versions, its own protocol is preferred since it exposes the tag bitmasks
+    state.river_status = globals.bind::<ZriverStatusManagerV1, _, _>(&qh, 1..=4, ()).ok();
+    let ext_manager = if state.river_status.is_none() { globals.bind::<ExtWorkspaceManagerV1, _, _>(&qh, 1..=1, ()).ok() } else { None };
+    let protocol = match (&state.river_status, &ext_manager)
+    {
+        (Some(_), _) => WorkspaceProtocol::River,
+        (None, Some(_)) => WorkspaceProtocol::ExtWorkspace,
+        (None, None) =>
+        {
+            eprintln!("[icebar] the compositor supports neither ext-workspace-v1 nor river-status, workspaces are disabled");
+            WAYLAND_UNSUPPORTED.store(true, Ordering::Relaxed);
+            return;
+        }
+    };
+
+    let seat = globals.bind::<WlSeat, _, _>(&qh, 1..=5, ()).ok();
+    let river_control = if protocol == WorkspaceProtocol::River { globals.bind::<ZriverControlV1, _, _>(&qh, 1..=1, ()).ok() } else { None };
+    if let (Some(manager), Some(seat)) = (&state.river_status, &seat) { manager.get_river_seat_status(seat, &qh, ()); }
+    let registry = globals.registry().clone();
+    for global in globals.contents().clone_list()
+    {
+        if global.interface == "wl_output" { state.add_output(&registry, global.name, global.version, &qh); }
+    }
+
+    if let Ok(mut guard) = WAYLAND_SHARED.lock()
+    {
+        *guard = Some(WaylandShared { connection: connection.clone(), queue: qh.clone(), protocol, ext_manager, ext_handles: Vec::new(), river_control, seat, river_focused_tags: 0, current: 0, workspaces: Vec::new(), focused_title: None });
+    }
+
+    if let Err(e) = queue.roundtrip(&mut state) { eprintln!("[icebar] wayland roundtrip failed: {e}"); }
+    state.changed = true;
+    loop
+    {
+        if state.changed
+        {
+            state.changed = false;
+            if state.publish(protocol).into_iter().any(|message| tx.send(message).is_err()) { break; }
+        }
+        if let Err(e) = queue.blocking_dispatch(&mut state)
+        {
+            eprintln!("[icebar] wayland dispatch error: {e}");
+            break; // connection lost → thread exits → reconnect
+        }
+    }
+
+    if let Ok(mut guard) = WAYLAND_SHARED.lock() { *guard = None; }
+}
+
+
+
+impl WaylandState
+{
+    fn add_output(&mut self, registry: &WlRegistry, global_name: u32, version: u32, qh: &QueueHandle<Self>)
+    {
+        let output: WlOutput = registry.bind(global_name, version.min(4), qh, ());
+        if let Some(manager) = &self.river_status { manager.get_river_output_status(&output, qh, output.id()); }
+        self.outputs.insert(output.id(), OutputEntry { global_name, status: RiverOutput::default() });
+    }
+
+    fn output_name(&self, id: &ObjectId) -> Option<String>
+    {
+        self.outputs.get(id).and_then(|output| output.status.name.clone())
+    }
+
+    /// Copies the state to `WAYLAND_SHARED` and returns the messages telling the modules about it.
+    fn publish(&self, protocol: WorkspaceProtocol) -> Vec<Message>
+    {
+        let mut river_focused_tags = 0;
+        let (current, workspaces) = match protocol
+        {
+            WorkspaceProtocol::River =>
+            {
+                let mut outputs: Vec<(&ObjectId, &RiverOutput)> = self.outputs.iter().map(|(id, output)| (id, &output.status)).collect();
+                outputs.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+                let focused = self.focused_output.as_ref().and_then(|focused| outputs.iter().position(|(id, _)| *id == focused));
+                let statuses: Vec<RiverOutput> = outputs.into_iter().map(|(_, status)| status.clone()).collect();
+                river_focused_tags = focused.and_then(|index| statuses.get(index)).or(statuses.first()).map_or(0, |output| output.focused_tags);
+                river_workspaces(&statuses, focused)
+            }
+            WorkspaceProtocol::ExtWorkspace =>
+            {
+                let list: Vec<ExtWorkspace> = self.workspaces.iter().map(|entry|
+                {
+                    let output = entry.group.as_ref().and_then(|group| self.groups.get(group)).and_then(|outputs| outputs.first()).and_then(|output| self.output_name(output));
+                    ExtWorkspace { output, ..entry.workspace.clone() }
+                }).collect();
+                ext_workspace_list(&list)
+            }
+        };
+
+        if let Ok(mut guard) = WAYLAND_SHARED.lock() && let Some(shared) = guard.as_mut()
+        {
+            shared.current = current;
+            shared.workspaces = workspaces.clone();
+            shared.ext_handles = self.workspaces.iter().map(|entry| (entry.workspace.key, entry.handle.clone())).collect();
+            shared.river_focused_tags = river_focused_tags;
+            shared.focused_title = self.focused_view.clone();
+        }
+        let mut messages = vec![Message::WorkspacesFetched(current, workspaces), Message::FocusedWindowFetched(focused_window_data(self.focused_view.clone()))];
+        if let Some(mode) = &self.mode { messages.push(Message::BindingModeChanged(mode.clone())); }
+        messages
+    }
+}
+
+
+
+impl WaylandShared
+{
+    fn river_command(&self, args: &[&str])
+    {
+        let (Some(control), Some(seat)) = (&self.river_control, &self.seat) else
+        {
+            eprintln!("[icebar] river-control isn't available, can't change tags");
+            return;
+        };
+        for arg in args { control.add_argument(arg.to_string()); }
+        control.run_command(seat, &self.queue, ());
+        if let Err(e) = self.connection.flush() { eprintln!("[icebar] river command failed: {e}"); }
+    }
+
+    fn activate_ext_workspace(&self, key: i32)
+    {
+        let Some(manager) = &self.ext_manager else { return; };
+        let Some((_, handle)) = self.ext_handles.iter().find(|(handle_key, _)| *handle_key == key) else { return; };
+        handle.activate();
+        manager.commit();
+        if let Err(e) = self.connection.flush() { eprintln!("[icebar] ext-workspace activate failed: {e}"); }
+    }
+}
+
+
+
+/// Decodes a wayland `array` argument holding native endian `u32`s.
+pub fn u32_array(bytes: &[u8]) -> Vec<u32>
+{
+    bytes.chunks_exact(4).map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])).collect()
+}
+
+
+
+/// One workspace per tag that is focused, occupied, or urgent on any output, keyed by tag number (1 to 32).
+/// Every tag focused on the seat's output is marked focused, so a multi-tag view highlights all of them.
+pub fn river_workspaces(outputs: &[RiverOutput], focused: Option<usize>) -> (i32, Vec<WorkspaceInfo>)
+{
+    let focused_output = focused.and_then(|index| outputs.get(index)).or(outputs.first());
+    let focused_tags = focused_output.map_or(0, |output| output.focused_tags);
+    let mut workspaces = Vec::new();
+    for tag in 0..32
+    {
+        let bit = 1u32 << tag;
+        let views_on = |output: &RiverOutput| output.view_tags.iter().filter(|tags| *tags & bit != 0).count() as u32;
+        let shown_on = outputs.iter().find(|output| output.focused_tags & bit != 0);
+        let windows: u32 = outputs.iter().map(views_on).sum();
+        let is_urgent = outputs.iter().any(|output| output.urgent_tags & bit != 0);
+        if shown_on.is_none() && windows == 0 && !is_urgent { continue; }
+
+        let home = shown_on.or_else(|| outputs.iter().find(|output| views_on(output) > 0)).or(focused_output);
+        let number = tag + 1;
+        workspaces.push(WorkspaceInfo
+        {
+            id: number,
+            number,
+            name: number.to_string(),
+            output: home.and_then(|output| output.name.clone()),
+            is_focused: focused_tags & bit != 0,
+            is_visible: shown_on.is_some(),
+            is_urgent,
+            windows,
+            ..Default::default()
+        });
+    }
+    let current = if focused_tags == 0 { 0 } else { focused_tags.trailing_zeros() as i32 + 1 };
+    (current, workspaces)
+}
+
+
+
+/// The tag bitmask for a tag number, `None` outside River's 32 tags.
+pub fn river_tag_mask(number: i32) -> Option<u32>
+{
+    (1..=32).contains(&number).then(|| 1u32 << (number - 1))
+}
+
+
+
+/// Moves one tag past the highest focused tag, or before the lowest one, staying within the 32 tags.
+pub fn river_shift_tags(focused_tags: u32, next: bool) -> u32
+{
+    if focused_tags == 0 { return 1; }
+    let target = if next { (31 - focused_tags.leading_zeros() + 1).min(31) } else { focused_tags.trailing_zeros().saturating_sub(1) };
+    1 << target
+}
+
+
+
+/// Hidden workspaces are left out. Workspaces without coordinates are numbered by their position on their output.
+/// Every active workspace is marked focused, ext-workspace has no notion of which output has the focus.
+pub fn ext_workspace_list(workspaces: &[ExtWorkspace]) -> (i32, Vec<WorkspaceInfo>)
+{
+    let mut shown: Vec<&ExtWorkspace> = workspaces.iter().filter(|workspace| !workspace.is_hidden).collect();
+    shown.sort_by(|a, b| a.coordinates.cmp(&b.coordinates));
+
+    let mut per_output: HashMap<Option<String>, i32> = HashMap::new();
+    let list: Vec<WorkspaceInfo> = shown.into_iter().map(|workspace|
+    {
+        let position = per_output.entry(workspace.output.clone()).or_insert(0);
+        *position += 1;
+        let number = workspace.coordinates.first().map_or(*position, |coordinate| *coordinate as i32 + 1);
+        WorkspaceInfo
+        {
+            id: workspace.key,
+            number,
+            name: if workspace.name.is_empty() { number.to_string() } else { workspace.name.clone() },
+            output: workspace.output.clone(),
+            is_focused: workspace.is_active,
+            is_visible: workspace.is_active,
+            is_urgent: workspace.is_urgent,
+            ..Default::default()
+        }
+    }).collect();
+    let current = list.iter().find(|workspace| workspace.is_focused).map_or(0, |workspace| workspace.id);
+    (current, list)
+}
+
+
+
+/// The workspace before or after `current` on the same output.
+pub fn neighbour_workspace(workspaces: &[WorkspaceInfo], current: i32, next: bool) -> Option<i32>
+{
+    let output = workspaces.iter().find(|workspace| workspace.id == current)?.output.clone();
+    let same_output: Vec<i32> = workspaces.iter().filter(|workspace| workspace.output == output).map(|workspace| workspace.id).collect();
+    let index = same_output.iter().position(|id| *id == current)?;
+    let target = if next { index.checked_add(1)? } else { index.checked_sub(1)? };
+    same_output.get(target).copied()
+}
+
+
+
+pub fn workspace_snapshot() -> (i32, Vec<WorkspaceInfo>)
+{
+    WAYLAND_SHARED.lock().ok().and_then(|guard| guard.as_ref().map(|shared| (shared.current, shared.workspaces.clone()))).unwrap_or_default()
+}
+
+
+
+/// Only River reports the focused window, and only its title. ext-workspace has nothing about windows.
+pub fn focused_window_wayland() -> Option<FocusedWindowData>
+{
+    focused_window_data(WAYLAND_SHARED.lock().ok().and_then(|guard| guard.as_ref().and_then(|shared| shared.focused_title.clone())))
+}
+
+
+
+fn focused_window_data(title: Option<String>) -> Option<FocusedWindowData>
+{
+    title.map(|title| FocusedWindowData { title, ..Default::default() })
+}
+
+
+
+/// River's tags always exist, ext-workspace keys don't match workspace numbers so placeholders can't be reached.
+pub fn supports_persistent_workspaces() -> bool
+{
+    WAYLAND_SHARED.lock().ok().and_then(|guard| guard.as_ref().map(|shared| shared.protocol == WorkspaceProtocol::River)).unwrap_or(false)
+}
+
+
+
+pub fn change_workspace_wayland(action: UserWorkspaceAction)
+{
+    let Ok(guard) = WAYLAND_SHARED.lock() else { return; };
+    let Some(shared) = guard.as_ref() else
+    {
+        eprintln!("[icebar] wayland workspace listener isn't running");
+        return;
+    };
+
+    match shared.protocol
+    {
+        WorkspaceProtocol::River =>
+        {
+            let tags = match action
+            {
+                UserWorkspaceAction::ChangeWithIndex(number) => river_tag_mask(number),
+                UserWorkspaceAction::ChangeWithName(name) => name.parse().ok().and_then(river_tag_mask),
+                UserWorkspaceAction::MoveNext => Some(river_shift_tags(shared.river_focused_tags, true)),
+                UserWorkspaceAction::MovePrev => Some(river_shift_tags(shared.river_focused_tags, false)),
+                UserWorkspaceAction::ToggleSpecial(_) => None,
+            };
+            if let Some(tags) = tags { shared.river_command(&["set-focused-tags", &tags.to_string()]); }
+        }
+        WorkspaceProtocol::ExtWorkspace =>
+        {
+            let key = match action
+            {
+                UserWorkspaceAction::ChangeWithIndex(key) => Some(key),
+                UserWorkspaceAction::ChangeWithName(name) => shared.workspaces.iter().find(|workspace| workspace.name == name).map(|workspace| workspace.id),
+                UserWorkspaceAction::MoveNext => neighbour_workspace(&shared.workspaces, shared.current, true),
+                UserWorkspaceAction::MovePrev => neighbour_workspace(&shared.workspaces, shared.current, false),
+                UserWorkspaceAction::ToggleSpecial(_) => None,
+            };
+            if let Some(key) = key { shared.activate_ext_workspace(key); }
+        }
+    }
+}
+
+
+
+
+
+// ============ DISPATCH ============
+impl Dispatch<WlRegistry, GlobalListContents> for WaylandState
+{
+    fn event(state: &mut Self, registry: &WlRegistry, event: wl_registry::Event, _: &GlobalListContents, _: &Connection, qh: &QueueHandle<Self>)
+    {
+        match event
+        {
+            wl_registry::Event::Global { name, interface, version } if interface == "wl_output" => state.add_output(registry, name, version, qh),
+            wl_registry::Event::GlobalRemove { name } =>
+            {
+                state.outputs.retain(|_, output| output.global_name != name);
+                state.changed = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlOutput, ()> for WaylandState
+{
+    fn event(state: &mut Self, output: &WlOutput, event: wl_output::Event, _: &(), _: &Connection, _: &QueueHandle<Self>)
+    {
+        if let wl_output::Event::Name { name } = event && let Some(entry) = state.outputs.get_mut(&output.id())
+        {
+            entry.status.name = Some(name);
+            state.changed = true;
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for WaylandState
+{
+    fn event(_: &mut Self, _: &WlSeat, _: <WlSeat as Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+impl Dispatch<ExtWorkspaceManagerV1, ()> for WaylandState
+{
+    fn event(state: &mut Self, _: &ExtWorkspaceManagerV1, event: ext_workspace_manager_v1::Event, _: &(), _: &Connection, _: &QueueHandle<Self>)
+    {
+        match event
+        {
+            ext_workspace_manager_v1::Event::WorkspaceGroup { workspace_group } => { state.groups.insert(workspace_group.id(), Vec::new()); }
+            ext_workspace_manager_v1::Event::Workspace { workspace } =>
+            {
+                state.next_key += 1;
+                state.workspaces.push(ExtWorkspaceEntry { handle: workspace, group: None, workspace: ExtWorkspace { key: state.next_key, ..Default::default() } });
+            }
+            ext_workspace_manager_v1::Event::Done => state.changed = true,
+            ext_workspace_manager_v1::Event::Finished => eprintln!("[icebar] ext-workspace manager finished"),
+            _ => {}
+        }
+    }
+
+    event_created_child!(WaylandState, ExtWorkspaceManagerV1, [
+        ext_workspace_manager_v1::EVT_WORKSPACE_GROUP_OPCODE => (ExtWorkspaceGroupHandleV1, ()),
+        ext_workspace_manager_v1::EVT_WORKSPACE_OPCODE => (ExtWorkspaceHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ExtWorkspaceGroupHandleV1, ()> for WaylandState
+{
+    fn event(state: &mut Self, group: &ExtWorkspaceGroupHandleV1, event: ext_workspace_group_handle_v1::Event, _: &(), _: &Connection, _: &QueueHandle<Self>)
+    {
+        match event
+        {
+            ext_workspace_group_handle_v1::Event::OutputEnter { output } => state.groups.entry(group.id()).or_default().push(output.id()),
+            ext_workspace_group_handle_v1::Event::OutputLeave { output } =>
+            {
+                if let Some(outputs) = state.groups.get_mut(&group.id()) { outputs.retain(|id| *id != output.id()); }
+            }
+            ext_workspace_group_handle_v1::Event::WorkspaceEnter { workspace } =>
+            {
+                if let Some(entry) = state.workspaces.iter_mut().find(|entry| entry.handle == workspace) { entry.group = Some(group.id()); }
+            }
+            ext_workspace_group_handle_v1::Event::WorkspaceLeave { workspace } =>
+            {
+                if let Some(entry) = state.workspaces.iter_mut().find(|entry| entry.handle == workspace && entry.group == Some(group.id())) { entry.group = None; }
+            }
+            ext_workspace_group_handle_v1::Event::Removed =>
+            {
+                state.groups.remove(&group.id());
+                group.destroy();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ExtWorkspaceHandleV1, ()> for WaylandState
+{
+    fn event(state: &mut Self, handle: &ExtWorkspaceHandleV1, event: ext_workspace_handle_v1::Event, _: &(), _: &Connection, _: &QueueHandle<Self>)
+    {
+        if let ext_workspace_handle_v1::Event::Removed = event
+        {
+            state.workspaces.retain(|entry| entry.handle != *handle);
+            handle.destroy();
+            return;
+        }
+        let Some(entry) = state.workspaces.iter_mut().find(|entry| entry.handle == *handle) else { return; };
+        match event
+        {
+            ext_workspace_handle_v1::Event::Name { name } => entry.workspace.name = name,
+            ext_workspace_handle_v1::Event::Coordinates { coordinates } => entry.workspace.coordinates = u32_array(&coordinates),
+            ext_workspace_handle_v1::Event::State { state: WEnum::Value(flags) } =>
+            {
+                entry.workspace.is_active = flags.contains(ext_workspace_handle_v1::State::Active);
+                entry.workspace.is_urgent = flags.contains(ext_workspace_handle_v1::State::Urgent);
+                entry.workspace.is_hidden = flags.contains(ext_workspace_handle_v1::State::Hidden);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ZriverStatusManagerV1, ()> for WaylandState
+{
+    fn event(_: &mut Self, _: &ZriverStatusManagerV1, _: <ZriverStatusManagerV1 as Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+impl Dispatch<ZriverOutputStatusV1, ObjectId> for WaylandState
+{
+    fn event(state: &mut Self, _: &ZriverOutputStatusV1, event: zriver_output_status_v1::Event, output: &ObjectId, _: &Connection, _: &QueueHandle<Self>)
+    {
+        let Some(entry) = state.outputs.get_mut(output) else { return; };
+        match event
+        {
+            zriver_output_status_v1::Event::FocusedTags { tags } => entry.status.focused_tags = tags,
+            zriver_output_status_v1::Event::ViewTags { tags } => entry.status.view_tags = u32_array(&tags),
+            zriver_output_status_v1::Event::UrgentTags { tags } => entry.status.urgent_tags = tags,
+            _ => return,
+        }
+        state.changed = true;
+    }
+}
+
+impl Dispatch<ZriverSeatStatusV1, ()> for WaylandState
+{
+    fn event(state: &mut Self, _: &ZriverSeatStatusV1, event: zriver_seat_status_v1::Event, _: &(), _: &Connection, _: &QueueHandle<Self>)
+    {
+        match event
+        {
+            zriver_seat_status_v1::Event::FocusedOutput { output } => state.focused_output = Some(output.id()),
+            zriver_seat_status_v1::Event::FocusedView { title } => state.focused_view = (!title.is_empty()).then_some(title),
+            zriver_seat_status_v1::Event::Mode { name } => state.mode = Some(name),
+            _ => return,
+        }
+        state.changed = true;
+    }
+}
+
+impl Dispatch<ZriverControlV1, ()> for WaylandState
+{
+    fn event(_: &mut Self, _: &ZriverControlV1, _: <ZriverControlV1 as Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+impl Dispatch<ZriverCommandCallbackV1, ()> for WaylandState
+{
+    fn event(_: &mut Self, _: &ZriverCommandCallbackV1, event: zriver_command_callback_v1::Event, _: &(), _: &Connection, _: &QueueHandle<Self>)
+    {
+        if let zriver_command_callback_v1::Event::Failure { failure_message } = event { eprintln!("[icebar] river command failed: {failure_message}"); }
+    }
+}
+
+
+
+
+
+// ============ TESTS ============
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn river_output(name: &str, focused_tags: u32, view_tags: Vec<u32>, urgent_tags: u32) -> RiverOutput
+    {
+        RiverOutput { name: Some(name.into()), focused_tags, view_tags, urgent_tags }
+    }
+
+    // ---- u32_array ------------------------------------------------------------
+
+    #[test]
+    fn u32_array_decodes_native_endian_words()
+    {
+        let bytes: Vec<u8> = [5u32, 1 << 8].iter().flat_map(|value| value.to_ne_bytes()).collect();
+        assert_eq!(u32_array(&bytes), vec![5, 1 << 8]);
+        assert!(u32_array(&[1, 2]).is_empty());
+    }
+
+    // ---- river_workspaces -----------------------------------------------------
+
+    #[test]
+    fn river_shows_focused_occupied_and_urgent_tags()
+    {
+        let outputs = [river_output("DP-1", 0b1, vec![0b1, 0b100], 0b1000)];
+        let (current, workspaces) = river_workspaces(&outputs, Some(0));
+        assert_eq!(current, 1);
+        assert_eq!(workspaces.iter().map(|workspace| workspace.id).collect::<Vec<_>>(), vec![1, 3, 4]);
+        assert_eq!(workspaces[1].windows, 1);
+        assert!(workspaces[2].is_urgent);
+    }
+
+    #[test]
+    fn river_marks_every_focused_tag_of_the_focused_output()
+    {
+        let outputs = [river_output("DP-1", 0b11, vec![], 0), river_output("HDMI-A-1", 0b100, vec![], 0)];
+        let (current, workspaces) = river_workspaces(&outputs, Some(0));
+        assert_eq!(current, 1);
+        let focused: Vec<i32> = workspaces.iter().filter(|workspace| workspace.is_focused).map(|workspace| workspace.id).collect();
+        assert_eq!(focused, vec![1, 2]);
+        assert!(workspaces[2].is_visible && !workspaces[2].is_focused);
+        assert_eq!(workspaces[2].output.as_deref(), Some("HDMI-A-1"));
+    }
+
+    // ---- river tags -----------------------------------------------------------
+
+    #[test]
+    fn river_tag_mask_covers_the_32_tags()
+    {
+        assert_eq!(river_tag_mask(1), Some(1));
+        assert_eq!(river_tag_mask(32), Some(1 << 31));
+        assert_eq!(river_tag_mask(0), None);
+        assert_eq!(river_tag_mask(33), None);
+    }
+
+    #[test]
+    fn river_shift_tags_moves_from_the_edges()
+    {
+        assert_eq!(river_shift_tags(0b110, true), 0b1000);
+        assert_eq!(river_shift_tags(0b110, false), 0b1);
+        assert_eq!(river_shift_tags(1, false), 1);
+        assert_eq!(river_shift_tags(1 << 31, true), 1 << 31);
+        assert_eq!(river_shift_tags(0, true), 1);
+    }
+
+    // ---- ext_workspace_list ---------------------------------------------------
+
+    #[test]
+    fn ext_workspaces_skip_hidden_and_number_per_output()
+    {
+        let workspaces = [
+            ExtWorkspace { key: 1, name: "web".into(), output: Some("DP-1".into()), is_active: true, ..Default::default() },
+            ExtWorkspace { key: 2, output: Some("DP-1".into()), is_hidden: true, ..Default::default() },
+            ExtWorkspace { key: 3, output: Some("DP-1".into()), is_urgent: true, ..Default::default() },
+            ExtWorkspace { key: 4, output: Some("HDMI-A-1".into()), is_active: true, ..Default::default() },
+        ];
+        let (current, list) = ext_workspace_list(&workspaces);
+        assert_eq!(current, 1);
+        assert_eq!(list.iter().map(|workspace| (workspace.id, workspace.number, workspace.name.as_str())).collect::<Vec<_>>(), vec![(1, 1, "web"), (3, 2, "2"), (4, 1, "1")]);
+        assert!(list[1].is_urgent);
+        assert!(list[2].is_focused);
+    }
+
+    #[test]
+    fn ext_workspaces_follow_coordinates()
+    {
+        let workspaces = [
+            ExtWorkspace { key: 1, coordinates: vec![1], ..Default::default() },
+            ExtWorkspace { key: 2, coordinates: vec![0], ..Default::default() },
+        ];
+        let (current, list) = ext_workspace_list(&workspaces);
+        assert_eq!(current, 0);
+        assert_eq!(list.iter().map(|workspace| (workspace.id, workspace.number)).collect::<Vec<_>>(), vec![(2, 1), (1, 2)]);
+    }
+
+    // ---- neighbour_workspace --------------------------------------------------
+
+    #[test]
+    fn neighbour_workspace_stays_on_the_same_output()
+    {
+        let on = |id: i32, output: &str| WorkspaceInfo { id, output: Some(output.into()), ..Default::default() };
+        let workspaces = [on(1, "DP-1"), on(2, "HDMI-A-1"), on(3, "DP-1")];
+        assert_eq!(neighbour_workspace(&workspaces, 1, true), Some(3));
+        assert_eq!(neighbour_workspace(&workspaces, 3, false), Some(1));
+        assert_eq!(neighbour_workspace(&workspaces, 1, false), None);
+        assert_eq!(neighbour_workspace(&workspaces, 9, true), None);
+    }
+}
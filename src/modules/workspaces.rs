@@ -16,7 +16,6 @@ use crate::helpers::color::{ColorType, Gradient};
 #[serde(default)]
 pub struct WorkspaceConfig
 {
-    pub niri_workspaces_update_interval:          u64,
     pub reverse_scroll_on_workspace:              bool,
     pub persistent_workspaces:                    Option<u8>,
     pub workspace_height:                         u32,
@@ -52,6 +51,22 @@ pub struct WorkspaceConfig
     pub workspace_button_shadow_x:                f32,
     pub workspace_button_shadow_y:                f32,
     pub workspace_button_shadow_blur:             f32,
+    pub workspace_output_mode:                    WorkspaceOutputMode,
+    pub workspace_output_divider_color:           ColorType,
+    pub workspace_output_divider_width:           f32,
+    pub workspace_output_divider_height:          f32,
+    pub workspace_show_special:                   bool,
+    pub workspace_special_text:                   String,
+    pub workspace_button_urgent_color:            ColorType,
+    pub workspace_urgent_text_color:              ColorType,
+    pub workspace_button_urgent_gradient_color:   Option<Gradient>,
+    pub workspace_urgent_blink:                   bool,
+    pub workspace_urgent_blink_interval:          u64,
+    pub workspace_show_window_icons:              bool,
+    pub workspace_window_icon_size:               u32,
+    pub workspace_window_icon_spacing:            u32,
+    pub workspace_max_window_icons:               usize,
+    pub workspace_dedup_window_icons:             bool,
 }
 
 impl Default for WorkspaceConfig
@@ -60,7 +75,6 @@ impl Default for WorkspaceConfig
     {
         Self
         {
-            niri_workspaces_update_interval:          225,
             reverse_scroll_on_workspace:              false,
             persistent_workspaces:                    None,
             workspace_height:                         30,
@@ -96,33 +110,93 @@ impl Default for WorkspaceConfig
             workspace_button_shadow_x:                0.0,
             workspace_button_shadow_y:                0.0,
             workspace_button_shadow_blur:             0.0,
+            workspace_output_mode:                    WorkspaceOutputMode::All,
+            workspace_output_divider_color:           ColorType::RGB([75, 75, 75]),
+            workspace_output_divider_width:           1.,
+            workspace_output_divider_height:          16.,
+            workspace_show_special:                   false,
+            workspace_special_text:                   "{name} {windows}".into(),
+            workspace_button_urgent_color:            ColorType::RGB([200, 60, 40]),
+            workspace_urgent_text_color:              ColorType::RGB([255, 255, 255]),
+            workspace_button_urgent_gradient_color:   None,
+            workspace_urgent_blink:                   false,
+            workspace_urgent_blink_interval:          500,
+            workspace_show_window_icons:              false,
+            workspace_window_icon_size:               14,
+            workspace_window_icon_spacing:            2,
+            workspace_max_window_icons:               4,
+            workspace_dedup_window_icons:             true,
         }
     }
 }
 
 // ============ ENUM/STRUCT, ETC ============
+#[derive(Default, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum WorkspaceOutputMode
+{
+    #[default] All,
+    CurrentOutput,
+    GroupedByOutput,
+}
+
+/// A workspace as reported by the compositor. `id` is the key used by the buttons and messages (unique per backend),
+/// `number` is what the user sees and what `workspace_text` is indexed with.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct WorkspaceInfo
+{
+    pub id: i32,
+    pub number: i32,
+    pub name: String,
+    pub output: Option<String>,
+    pub is_focused: bool,
+    pub is_visible: bool,
+    pub is_urgent: bool,
+    pub is_special: bool,
+    pub windows: u32,
+    pub urgent_windows: u32,
+    /// `app_id` (or X11 class) of every window on the workspace, in compositor order.
+    pub app_ids: Vec<String>,
+}
+
 #[derive(Default, Clone)]
 pub struct WorkspaceData
 {
-    pub is_hovering_workspace: bool,
     pub visible_workspaces: Vec<i32>,
+    pub workspace_groups: Vec<Vec<i32>>,
+    pub workspaces: Vec<WorkspaceInfo>,
+    pub urgent_blink_on: bool,
     pub current_workspace: i32 
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum UserWorkspaceAction
 {
     ChangeWithIndex(i32),
+    ChangeWithName(String),
+    ToggleSpecial(String),
     MoveNext,
     MovePrev
 }
 
 
 
+impl WorkspaceInfo
+{
+    /// Stand-in for a persistent workspace the compositor hasn't created yet.
+    pub fn placeholder(number: i32) -> Self
+    {
+        WorkspaceInfo { id: number, number, name: number.to_string(), ..Default::default() }
+    }
+}
+
+
+
 
 
 // ============ CRATES ============
-use crate::helpers::style::{UserStyle, orient_text, set_style};
+use crate::helpers::{style::{UserStyle, orient_text, set_style}, workspaces::build_output_workspace_groups};
+use crate::helpers::icons::request_app_icons;
+use crate::update::Message;
 use crate::AppData;
 
 
@@ -130,6 +204,123 @@ use crate::AppData;
 
 
 // ============ FUNCTIONS ============
+pub fn apply_fetched_workspaces(app: &mut AppData, current: i32, real: Vec<WorkspaceInfo>, persistent: Option<u8>)
+{
+    let show_special = app.ron_config.workspace.workspace_show_special;
+    let real: Vec<WorkspaceInfo> = real.into_iter().filter(|workspace| show_special || !workspace.is_special).collect();
+    let groups = build_output_workspace_groups(&real, persistent, &app.ron_config.workspace.workspace_output_mode, app.ron_config.general.display.as_deref());
+    app.modules_data.workspace_data.current_workspace = current;
+    app.modules_data.workspace_data.visible_workspaces = groups.concat();
+    app.modules_data.workspace_data.workspace_groups = groups;
+    app.modules_data.workspace_data.workspaces = real;
+}
+
+
+
+/// Looks up the icons of the applications open on the workspaces, when `workspace_show_window_icons` is set.
+pub fn request_workspace_icons(app: &mut AppData) -> iced::Task<Message>
+{
+    if !app.ron_config.workspace.workspace_show_window_icons { return iced::Task::none(); }
+    let app_ids: Vec<String> = app.modules_data.workspace_data.workspaces.iter().flat_map(|workspace| workspace.app_ids.iter().cloned()).collect();
+    let size = app.ron_config.workspace.workspace_window_icon_size;
+    request_app_icons(app, app_ids, size)
+}
+
+
+
+/// The `app_id`s whose icon is shown on a workspace button, de-duplicated when asked and capped at `max`.
+pub fn select_window_icons(app_ids: &[String], dedup: bool, max: usize) -> Vec<&str>
+{
+    let mut selected: Vec<&str> = Vec::new();
+    for app_id in app_ids
+    {
+        if selected.len() >= max { break; }
+        if app_id.is_empty() || (dedup && selected.contains(&app_id.as_str())) { continue; }
+        selected.push(app_id);
+    }
+    selected
+}
+
+
+
+pub fn define_workspace_icons(app: &AppData, id: i32) -> Vec<iced::widget::image::Handle>
+{
+    if !app.ron_config.workspace.workspace_show_window_icons { return Vec::new(); }
+    let Some(info) = find_workspace_info(&app.modules_data.workspace_data, id) else { return Vec::new(); };
+    let config = &app.ron_config.workspace;
+    select_window_icons(&info.app_ids, config.workspace_dedup_window_icons, config.workspace_max_window_icons).into_iter().filter_map(|app_id| app.modules_data.app_icons.get(app_id).cloned().flatten()).collect()
+}
+
+
+
+/// Scrolling down moves to the next workspace, up to the previous one, swapped by `reverse_scroll_on_workspace`.
+pub fn scroll_workspace_action(y: f32, reverse: bool) -> Option<UserWorkspaceAction>
+{
+    if y == 0. { return None; }
+    if (y < 0.) != reverse { Some(UserWorkspaceAction::MoveNext) } else { Some(UserWorkspaceAction::MovePrev) }
+}
+
+
+
+pub fn find_workspace_info(data: &WorkspaceData, id: i32) -> Option<&WorkspaceInfo>
+{
+    data.workspaces.iter().find(|workspace| workspace.id == id)
+}
+
+
+
+/// The current workspace, plus every other focused one (River can show several tags at once).
+pub fn is_workspace_selected(data: &WorkspaceData, id: i32) -> bool
+{
+    id == data.current_workspace || find_workspace_info(data, id).is_some_and(|workspace| workspace.is_focused)
+}
+
+
+
+/// Special workspaces are toggled, workspaces without a positive key (Sway names without a number, Hyprland named ones) are reached by name.
+pub fn workspace_action_for(data: &WorkspaceData, id: i32) -> UserWorkspaceAction
+{
+    match find_workspace_info(data, id)
+    {
+        Some(workspace) if workspace.is_special => UserWorkspaceAction::ToggleSpecial(workspace.name.clone()),
+        Some(workspace) if workspace.id <= 0 => UserWorkspaceAction::ChangeWithName(workspace.name.clone()),
+        _ => UserWorkspaceAction::ChangeWithIndex(id),
+    }
+}
+
+
+
+pub fn fill_workspace_placeholders(text: &str, id: i32, info: Option<&WorkspaceInfo>) -> String
+{
+    let (number, name, windows, urgent) = match info
+    {
+        Some(workspace) => (workspace.number, workspace.name.clone(), workspace.windows, workspace.urgent_windows),
+        None => (id, id.to_string(), 0, 0),
+    };
+    text.replace("{name}", &name).replace("{id}", &number.to_string()).replace("{windows}", &windows.to_string()).replace("{urgent}", &urgent.to_string())
+}
+
+
+
+/// Urgent workspaces use the urgent style, blinking between it and the normal one when `workspace_urgent_blink` is set.
+/// The focused workspace always keeps the selected style.
+pub fn is_workspace_urgent_shown(app: &AppData, id: i32) -> bool
+{
+    let data = &app.modules_data.workspace_data;
+    if is_workspace_selected(data, id) { return false; }
+    let is_urgent = find_workspace_info(data, id).is_some_and(|workspace| workspace.is_urgent);
+    is_urgent && (!app.ron_config.workspace.workspace_urgent_blink || data.urgent_blink_on)
+}
+
+
+
+pub fn has_urgent_workspace(data: &WorkspaceData) -> bool
+{
+    data.workspaces.iter().any(|workspace| workspace.is_urgent)
+}
+
+
+
 pub fn define_workspaces_style(app: &AppData, status: button::Status, i: &i32) -> iced::widget::button::Style
 {
     let hovered = app.ron_config.workspace.workspace_button_hovered_color;
@@ -137,54 +328,57 @@ pub fn define_workspaces_style(app: &AppData, status: button::Status, i: &i32) -
     let pressed_text = app.ron_config.workspace.workspace_button_pressed_text_color;
     let pressed = app.ron_config.workspace.workspace_button_pressed_color;
 
-    let normal = if app.modules_data.workspace_data.current_workspace == *i 
-    { app.ron_config.workspace.workspace_button_selected_color }
-    else 
-    { app.ron_config.workspace.workspace_button_color };
+    let workspace = &app.ron_config.workspace;
+    let is_current = is_workspace_selected(&app.modules_data.workspace_data, *i);
+    let is_urgent = is_workspace_urgent_shown(app, *i);
 
-    let normal_text = if app.modules_data.workspace_data.current_workspace == *i 
-    { app.ron_config.workspace.workspace_selected_text_color }
+    let (normal, normal_text, normal_gradient) = if is_current
+    { (workspace.workspace_button_selected_color, workspace.workspace_selected_text_color, workspace.workspace_button_selected_gradient_color.clone()) }
+    else if is_urgent
+    { (workspace.workspace_button_urgent_color, workspace.workspace_urgent_text_color, workspace.workspace_button_urgent_gradient_color.clone()) }
     else
-    { app.ron_config.workspace.workspace_text_color };
+    { (workspace.workspace_button_color, workspace.workspace_text_color, workspace.workspace_button_gradient_color.clone()) };
 
-    let border_size = app.ron_config.workspace.workspace_border_size;
-    let border_color = app.ron_config.workspace.workspace_border_color;
-    let border_radius = app.ron_config.workspace.workspace_border_radius;
-    let normal_gradient = if app.modules_data.workspace_data.current_workspace == *i
-    { app.ron_config.workspace.workspace_button_selected_gradient_color.clone() }
-    else
-    { app.ron_config.workspace.workspace_button_gradient_color.clone() };
+    let border_size = workspace.workspace_border_size;
+    let border_color = workspace.workspace_border_color;
+    let border_radius = workspace.workspace_border_radius;
     set_style(UserStyle {status, hovered, hovered_text, pressed_text, pressed, normal, normal_text, border_color, border_size, border_radius, normal_gradient, hovered_gradient: app.ron_config.workspace.workspace_button_hovered_gradient_color.clone(), pressed_gradient: app.ron_config.workspace.workspace_button_pressed_gradient_color.clone(), shadow_color: app.ron_config.workspace.workspace_button_shadow_color, shadow_x: app.ron_config.workspace.workspace_button_shadow_x, shadow_y: app.ron_config.workspace.workspace_button_shadow_y, shadow_blur: app.ron_config.workspace.workspace_button_shadow_blur})
 }
 
 
 pub fn define_workspaces_text(app: &AppData, id: i32) -> String
 {
-    let string_not_oriented = if id == app.modules_data.workspace_data.current_workspace 
+    let info = find_workspace_info(&app.modules_data.workspace_data, id);
+    let text_index = usize::try_from(info.map_or(id, |workspace| workspace.number) - 1).ok();
+    let fallback = || info.map_or_else(|| id.to_string(), |workspace| workspace.name.clone());
+    let string_not_oriented = if info.is_some_and(|workspace| workspace.is_special)
+    {
+        app.ron_config.workspace.workspace_special_text.clone()
+    }
+    else if is_workspace_selected(&app.modules_data.workspace_data, id) 
     {
         if let Some(selected) = &app.ron_config.workspace.workspace_selected_text 
         {
-            let safe_id = id.saturating_sub(1) as usize;
-            selected.get(safe_id).cloned().unwrap_or_else(|| id.to_string()) 
+            text_index.and_then(|index| selected.get(index)).cloned().unwrap_or_else(fallback) 
         } 
         else 
         {
-            id.to_string() 
+            fallback() 
         }
     } 
     else 
     { 
-        app.ron_config.workspace.workspace_text.get((id - 1) as usize).cloned().unwrap_or_else(|| id.to_string()) 
+        text_index.and_then(|index| app.ron_config.workspace.workspace_text.get(index)).cloned().unwrap_or_else(fallback) 
     };
 
-    orient_text(&string_not_oriented, &app.ron_config.workspace.workspace_text_orientation)
+    orient_text(&fill_workspace_placeholders(&string_not_oriented, id, info), &app.ron_config.workspace.workspace_text_orientation)
 }
 
 
 
 pub fn define_workspaces_size(app: &AppData, id: i32) -> (u32, u32)
 {
-    let width = if let Some(value) = app.ron_config.workspace.workspace_different_selected_width && id == app.modules_data.workspace_data.current_workspace
+    let width = if let Some(value) = app.ron_config.workspace.workspace_different_selected_width && is_workspace_selected(&app.modules_data.workspace_data, id)
     {
         value
     } 
@@ -193,7 +387,7 @@ pub fn define_workspaces_size(app: &AppData, id: i32) -> (u32, u32)
         app.ron_config.workspace.workspace_width
     };
 
-    let height = if let Some(value) = app.ron_config.workspace.workspace_different_selected_height && id == app.modules_data.workspace_data.current_workspace
+    let height = if let Some(value) = app.ron_config.workspace.workspace_different_selected_height && is_workspace_selected(&app.modules_data.workspace_data, id)
     {
         value
     } 
@@ -224,9 +418,11 @@ mod tests
         let mut app = AppData { ..Default::default() };
         app.modules_data.workspace_data = WorkspaceData
         {
-            is_hovering_workspace: false,
             current_workspace: current,
             visible_workspaces: vec![1, 2, 3],
+            workspace_groups: vec![vec![1, 2, 3]],
+            workspaces: Vec::new(),
+            urgent_blink_on: false,
         };
         app.ron_config.workspace.workspace_text = vec!["ws1".into(), "ws2".into(), "ws3".into()];
         app.ron_config.workspace.workspace_selected_text = Some(vec!["[1]".into(), "[2]".into(), "[3]".into()]);
@@ -320,6 +516,19 @@ mod tests
         let style = define_workspaces_style(&make_style_app(1), button::Status::Active, &3);
         assert_eq!(style.background, Some(Background::Color(Color::from_rgb8(0, 0, 200))));
     }
+
+    #[test]
+    fn workspace_style_every_focused_tag_uses_selected_color()
+    {
+        let mut app = make_style_app(1);
+        app.modules_data.workspace_data.workspaces = vec![
+            WorkspaceInfo { id: 1, is_focused: true, ..WorkspaceInfo::placeholder(1) },
+            WorkspaceInfo { id: 3, is_focused: true, ..WorkspaceInfo::placeholder(3) },
+        ];
+        let style = define_workspaces_style(&app, button::Status::Active, &3);
+        assert_eq!(style.background, Some(Background::Color(Color::from_rgb8(255, 0, 0))));
+        assert!(!is_workspace_selected(&app.modules_data.workspace_data, 2));
+    }
  
     #[test]
     fn workspace_style_selected_and_non_selected_differ()
@@ -343,4 +552,140 @@ mod tests
         let style = define_workspaces_style(&make_style_app(1), button::Status::Pressed, &2);
         assert_eq!(style.background, Some(Background::Color(Color::from_rgb8(0, 100, 0))));
     }
+
+    // ---- named and special workspaces ----------------------------------------
+
+    fn app_with(workspaces: Vec<WorkspaceInfo>, current: i32) -> AppData
+    {
+        let mut app = make_app(current);
+        app.modules_data.workspace_data.workspaces = workspaces;
+        app
+    }
+
+    #[test]
+    fn workspace_text_fills_placeholders()
+    {
+        let mut app = app_with(vec![WorkspaceInfo { id: 2, number: 2, name: "2:web".into(), windows: 4, ..Default::default() }], 1);
+        app.ron_config.workspace.workspace_text = vec!["a".into(), "{id}|{name}|{windows}".into()];
+        assert_eq!(define_workspaces_text(&app, 2), "2|2:web|4");
+    }
+
+    #[test]
+    fn workspace_text_without_entry_falls_back_to_name()
+    {
+        let app = app_with(vec![WorkspaceInfo { id: -5, number: -1, name: "music".into(), ..Default::default() }], 1);
+        assert_eq!(define_workspaces_text(&app, -5), "music");
+    }
+
+    #[test]
+    fn special_workspace_uses_special_text()
+    {
+        let mut app = app_with(vec![WorkspaceInfo { id: -98, number: -98, name: "magic".into(), is_special: true, windows: 2, ..Default::default() }], 1);
+        app.ron_config.workspace.workspace_special_text = "* {name} {windows}".into();
+        assert_eq!(define_workspaces_text(&app, -98), "* magic 2");
+    }
+
+    #[test]
+    fn workspace_action_depends_on_kind()
+    {
+        let app = app_with(vec!
+        [
+            WorkspaceInfo { id: -98, name: "magic".into(), is_special: true, ..Default::default() },
+            WorkspaceInfo { id: -5, number: -1, name: "music".into(), ..Default::default() },
+            WorkspaceInfo::placeholder(3),
+        ], 1);
+        let data = &app.modules_data.workspace_data;
+        assert_eq!(workspace_action_for(data, -98), UserWorkspaceAction::ToggleSpecial("magic".into()));
+        assert_eq!(workspace_action_for(data, -5), UserWorkspaceAction::ChangeWithName("music".into()));
+        assert_eq!(workspace_action_for(data, 3), UserWorkspaceAction::ChangeWithIndex(3));
+        assert_eq!(workspace_action_for(data, 9), UserWorkspaceAction::ChangeWithIndex(9));
+    }
+
+    #[test]
+    fn apply_fetched_workspaces_hides_special_unless_enabled()
+    {
+        let special = WorkspaceInfo { id: -98, name: "magic".into(), is_special: true, ..Default::default() };
+        let mut app = make_app(1);
+        apply_fetched_workspaces(&mut app, 1, vec![WorkspaceInfo::placeholder(1), special.clone()], None);
+        assert_eq!(app.modules_data.workspace_data.visible_workspaces, vec![1]);
+        app.ron_config.workspace.workspace_show_special = true;
+        apply_fetched_workspaces(&mut app, 1, vec![WorkspaceInfo::placeholder(1), special], None);
+        assert_eq!(app.modules_data.workspace_data.visible_workspaces, vec![1, -98]);
+    }
+
+    // ---- urgent workspaces ----------------------------------------------------
+
+    fn urgent_app(current: i32) -> AppData
+    {
+        let mut app = app_with(vec![WorkspaceInfo { id: 2, number: 2, name: "2".into(), is_urgent: true, windows: 3, urgent_windows: 1, ..Default::default() }], current);
+        app.ron_config.workspace.workspace_button_urgent_color = ColorType::RGB([250, 10, 10]);
+        app
+    }
+
+    #[test]
+    fn urgent_workspace_uses_urgent_color()
+    {
+        let style = define_workspaces_style(&urgent_app(1), button::Status::Active, &2);
+        assert_eq!(style.background, Some(Background::Color(Color::from_rgb8(250, 10, 10))));
+    }
+
+    #[test]
+    fn focused_urgent_workspace_keeps_selected_style()
+    {
+        let app = urgent_app(2);
+        assert!(!is_workspace_urgent_shown(&app, 2));
+    }
+
+    #[test]
+    fn urgent_blink_alternates_with_normal_style()
+    {
+        let mut app = urgent_app(1);
+        app.ron_config.workspace.workspace_urgent_blink = true;
+        assert!(!is_workspace_urgent_shown(&app, 2));
+        app.modules_data.workspace_data.urgent_blink_on = true;
+        assert!(is_workspace_urgent_shown(&app, 2));
+    }
+
+    #[test]
+    fn urgent_placeholder_counts_urgent_windows()
+    {
+        let app = urgent_app(1);
+        let info = find_workspace_info(&app.modules_data.workspace_data, 2);
+        assert_eq!(fill_workspace_placeholders("{id}:{urgent}/{windows}", 2, info), "2:1/3");
+        assert!(has_urgent_workspace(&app.modules_data.workspace_data));
+    }
+
+    // ---- window icons ---------------------------------------------------------
+
+    #[test]
+    fn window_icons_are_deduplicated_and_capped()
+    {
+        let app_ids: Vec<String> = ["foot", "firefox", "foot", "", "mpv", "gimp"].iter().map(|id| id.to_string()).collect();
+        assert_eq!(select_window_icons(&app_ids, true, 3), vec!["foot", "firefox", "mpv"]);
+        assert_eq!(select_window_icons(&app_ids, false, 3), vec!["foot", "firefox", "foot"]);
+    }
+
+    #[test]
+    fn window_icons_skip_unresolved_apps()
+    {
+        let mut app = app_with(vec![WorkspaceInfo { id: 1, number: 1, app_ids: vec!["foot".into(), "ghost".into()], ..Default::default() }], 1);
+        app.ron_config.workspace.workspace_show_window_icons = true;
+        app.modules_data.app_icons.insert("foot".into(), Some(iced::widget::image::Handle::from_rgba(1, 1, vec![0; 4])));
+        app.modules_data.app_icons.insert("ghost".into(), None);
+        assert_eq!(define_workspace_icons(&app, 1).len(), 1);
+        app.ron_config.workspace.workspace_show_window_icons = false;
+        assert!(define_workspace_icons(&app, 1).is_empty());
+    }
+
+    // ---- scroll_workspace_action ----------------------------------------------
+
+    #[test]
+    fn scroll_direction_maps_to_workspace_moves()
+    {
+        assert_eq!(scroll_workspace_action(-1., false), Some(UserWorkspaceAction::MoveNext));
+        assert_eq!(scroll_workspace_action(1., false), Some(UserWorkspaceAction::MovePrev));
+        assert_eq!(scroll_workspace_action(-1., true), Some(UserWorkspaceAction::MovePrev));
+        assert_eq!(scroll_workspace_action(1., true), Some(UserWorkspaceAction::MoveNext));
+        assert_eq!(scroll_workspace_action(0., false), None);
+    }
 }
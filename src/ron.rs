@@ -1,6 +1,6 @@
 // ============ IMPORTS ============
 use serde::{Deserialize, Serialize};
-use std::{fs, collections::HashSet};
+use std::{fs, collections::{HashMap, HashSet}};
 
 
 
@@ -15,6 +15,8 @@ use crate::modules::
     ram::RamConfig,
     disk::DiskConfig,
     focused_window::FocusedWindowConfig,
+    binding_mode::BindingModeConfig,
+    taskbar::TaskbarConfig,
     tray::TrayConfig,
     network::{NetworkConfig, AltNetworkConfig},
     volume::{VolumeOutputConfig, MutedVolumeOutputConfig, VolumeInputConfig, MutedVolumeInputConfig},
@@ -24,7 +26,8 @@ use crate::modules::
     custom_modules::CustomModuleConfig,
     data::Modules,
 };
-use crate::context_menu::ContextMenuConfig;
+use crate::context_menu::{ContextMenuConfig, UserMenuItem};
+use crate::helpers::{bindings::{InternalAction, ModuleBindings}, condition::Condition};
 use crate::helpers::{string::find_field_colon, color::{ColorType, Gradient}, ron_general::apply_general_settings, style::{SideOption, TextOrientation}};
 
 
@@ -55,8 +58,11 @@ pub enum ActionOnClick
     #[default] Nothing,
     DefaultAction,
     CustomAction(Vec<String>),
+    ShellAction(String),
     CycleClockTimezones,
-    ToggleAltClockAndCycleClockTimezones
+    ToggleAltClockAndCycleClockTimezones,
+    OpenMenu(String),
+    Internal(InternalAction)
 }
 
 
@@ -198,9 +204,14 @@ pub struct BarConfig
     pub ram:                    RamConfig,
     pub disk:                   DiskConfig,
     pub focused_window:         FocusedWindowConfig,
+    pub binding_mode:           BindingModeConfig,
+    pub taskbar:                TaskbarConfig,
     pub context_menu:           ContextMenuConfig,
     pub image:                  ImageConfig,
     pub custom_module:          CustomModuleConfig,
+    pub menus:                  HashMap<String, Vec<UserMenuItem>>,
+    pub bindings:               HashMap<String, ModuleBindings>,
+    pub visible_when:           HashMap<String, Condition>,
 }
 
 
@@ -267,7 +278,7 @@ pub fn read_ron_config() -> RonReturn
     };
 
     let mut active_modules: HashSet<Modules> = HashSet::new();
-    let all_possible_default_modules = [Modules::Disk, Modules::FocusedWindowSway, Modules::FocusedWindowHypr, Modules::FocusedWindowNiri, Modules::CpuTemp, Modules::Ram, Modules::Cpu, Modules::NiriWorkspaces, Modules::MediaPlayerMetaData, Modules::MediaPlayerButtons, Modules::Network, Modules::HyprWorkspaces, Modules::SwayWorkspaces, Modules::VolumeOutput, Modules::VolumeInput, Modules::Clock, Modules::Tray];
+    let all_possible_default_modules = [Modules::Workspaces, Modules::FocusedWindow, Modules::Disk, Modules::FocusedWindowSway, Modules::FocusedWindowHypr, Modules::FocusedWindowNiri, Modules::CpuTemp, Modules::Ram, Modules::Cpu, Modules::NiriWorkspaces, Modules::MediaPlayerMetaData, Modules::MediaPlayerButtons, Modules::Network, Modules::HyprWorkspaces, Modules::SwayWorkspaces, Modules::VolumeOutput, Modules::VolumeInput, Modules::Clock, Modules::Tray, Modules::Taskbar, Modules::BindingMode];
     let all_possible_position = [&bar_config.general.left_modules, &bar_config.general.center_modules, &bar_config.general.right_modules];
     for position in all_possible_position
     {
@@ -7,7 +7,7 @@ use std::time::Duration;
 
 
 // ============ CRATES ============
-use crate::{helpers::config_watcher::config_file_watcher, modules::{data::Modules, hypr::hypr_event_subscription, network::network_subscription, sway::sway_event_subscription, tray::{TraySubscription, tray_stream}, volume::volume_subscription}};
+use crate::{helpers::{config_watcher::config_file_watcher, style::state_blink_interval}, modules::{compositor::compositor_subscriptions, custom_modules::{custom_module_signal_stream, custom_module_signals, custom_module_stream, custom_module_streams, is_polled_custom_module}, data::Modules, image::{image_command_stream, image_command_streams, image_file_watch_stream, image_file_watches}, network::network_subscription, tray::{TraySubscription, tray_stream}, volume::volume_subscription, workspaces::has_urgent_workspace}};
 use crate::update::Message;
 use crate::AppData;
 
@@ -18,7 +18,7 @@ use crate::AppData;
 // ============ FUNCTIONS ============
 pub fn subscription(app: &AppData) -> iced::Subscription<Message>
 {
-    let event_reader = if app.modules_data.active_modules.contains(&Modules::Tray)
+    let event_reader = if app.modules_data.active_modules.contains(&Modules::Tray) || !app.ron_config.menus.is_empty()
     {
         event::listen_with(event_reader_with_tray)
     }
@@ -37,7 +37,7 @@ pub fn subscription(app: &AppData) -> iced::Subscription<Message>
     {
         if let Modules::CustomModule(i) = m 
         {
-            app.ron_config.custom_module.custom_modules.get(*i).is_some_and(|m| !m.continous_command.is_empty())
+            app.ron_config.custom_module.custom_modules.get(*i).is_some_and(is_polled_custom_module)
         } 
         else 
         {
@@ -45,10 +45,12 @@ pub fn subscription(app: &AppData) -> iced::Subscription<Message>
         }
     });
     if has_continuous_modules { subs.push(time::every(Duration::from_millis(225)).map(|_| Message::Tick)); }
+    for stream in custom_module_streams(app) { subs.push(iced::Subscription::run_with(stream, custom_module_stream)); }
+    for signal in custom_module_signals(app) { subs.push(iced::Subscription::run_with(signal, custom_module_signal_stream)); }
+    for stream in image_command_streams(app) { subs.push(iced::Subscription::run_with(stream, image_command_stream)); }
+    for watch in image_file_watches(app) { subs.push(iced::Subscription::run_with(watch, image_file_watch_stream)); }
 
     let mut volume_sub_added = false;
-    let mut hypr_sub_added = false;
-    let mut sway_sub_added = false;
     let mut media_player_sub_added = false;
     for module_name in &app.modules_data.active_modules
     {
@@ -59,9 +61,7 @@ pub fn subscription(app: &AppData) -> iced::Subscription<Message>
             Modules::Cpu =>                 subs.push(time::every(Duration::from_millis(app.ron_config.cpu.cpu_update_interval)).map(|_| Message::UpdateCpu)),
             Modules::CpuTemp =>             subs.push(time::every(Duration::from_millis(app.ron_config.cpu_temp.cpu_temp_update_interval)).map(|_| Message::UpdateCpuTemp)),
             Modules::Ram =>                 subs.push(time::every(Duration::from_millis(app.ron_config.ram.ram_update_interval)).map(|_| Message::UpdateRam)),
-            Modules::FocusedWindowNiri =>   subs.push(time::every(Duration::from_millis(app.ron_config.focused_window.focused_window_update_interval)).map(|_| Message::UpdateFocusedWindowNiri)),
             Modules::Clock =>               subs.push(time::every(Duration::from_millis(app.ron_config.clock.clock_update_interval)).map(|_| Message::UpdateClock)),
-            Modules::NiriWorkspaces =>      subs.push(time::every(Duration::from_millis(app.ron_config.workspace.niri_workspaces_update_interval)).map(|_| Message::UpdateNiriWorkspaces)),
             Modules::MediaPlayerMetaData | Modules::MediaPlayerButtons => 
             {
                 if !media_player_sub_added 
@@ -75,22 +75,6 @@ pub fn subscription(app: &AppData) -> iced::Subscription<Message>
                 subs.push(network_subscription(app.ron_config.network.network_disconnected_text.clone()));
                 subs.push(time::every(Duration::from_secs(1)).map(|_| Message::UpdateNetworkSpeed));
             },
-            Modules::FocusedWindowHypr | Modules::HyprWorkspaces =>
-            {
-                if !hypr_sub_added
-                {
-                    subs.push(iced::Subscription::run(hypr_event_subscription));
-                    hypr_sub_added = true;
-                }
-            }
-            Modules::SwayWorkspaces | Modules::FocusedWindowSway =>
-            {
-                if !sway_sub_added
-                {
-                    subs.push(iced::Subscription::run(sway_event_subscription));
-                    sway_sub_added = true;
-                }
-            }
             Modules::VolumeOutput | Modules::VolumeInput =>
             {
                 if !volume_sub_added
@@ -105,6 +89,19 @@ pub fn subscription(app: &AppData) -> iced::Subscription<Message>
         }
     }
 
+    // Workspaces, focused window and taskbar share one event listener per compositor
+    subs.extend(compositor_subscriptions(&app.modules_data.active_modules));
+
+    if app.ron_config.workspace.workspace_urgent_blink && has_urgent_workspace(&app.modules_data.workspace_data)
+    {
+        subs.push(time::every(Duration::from_millis(app.ron_config.workspace.workspace_urgent_blink_interval.max(50))).map(|_| Message::UrgentBlinkTick));
+    }
+
+    if let Some(blink_interval) = state_blink_interval(app)
+    {
+        subs.push(time::every(Duration::from_millis(blink_interval)).map(|_| Message::StateBlinkTick));
+    }
+
     if let Some(reload_interval) = app.ron_config.general.bar_check_reload_interval_ms 
     {
         subs.push(config_file_watcher(reload_interval));
@@ -115,14 +112,15 @@ pub fn subscription(app: &AppData) -> iced::Subscription<Message>
 
 
 
-fn event_reader_with_tray(event: iced::Event, _status: iced::event::Status, _id: iced::window::Id) -> Option<Message>
+fn event_reader_with_tray(event: iced::Event, _status: iced::event::Status, id: iced::window::Id) -> Option<Message>
 {
     match event 
     {
         iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {key: iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape), .. }) => { Some(Message::CloseContextMenu) }
-        iced::Event::Mouse(mouse::Event::ButtonPressed(_)) => { Some(Message::MouseButtonClicked) }
+        iced::Event::Keyboard(iced::keyboard::Event::KeyPressed { key, .. }) => { Some(Message::ContextMenuKeyPressed(key)) }
+        iced::Event::Mouse(mouse::Event::ButtonPressed(button)) => { Some(Message::MouseButtonClicked(button)) }
         iced::Event::Mouse(mouse::Event::WheelScrolled { delta, .. }) => Some(Message::MouseWheelScrolled(delta)),
-        iced::Event::Mouse(mouse::Event::CursorMoved { position })    => Some(Message::CursorMoved(position)),
+        iced::Event::Mouse(mouse::Event::CursorMoved { position })    => Some(Message::WindowCursorMoved(id, position)),
         _ => None
     }
 }
@@ -133,6 +131,7 @@ fn event_reader_without_tray(event: iced::Event, _status: iced::event::Status, _
 {
     match event 
     {
+        iced::Event::Mouse(mouse::Event::ButtonPressed(button)) => Some(Message::MouseButtonClicked(button)),
         iced::Event::Mouse(mouse::Event::WheelScrolled { delta, .. }) => Some(Message::MouseWheelScrolled(delta)),
         _ => None
     }
@@ -1,7 +1,8 @@
 // ============ IMPORTS ============
-use iced::{Task, mouse::ScrollDelta, widget::image};
+use iced::{Task, mouse::{self, ScrollDelta}, widget::image};
 use std::{sync::Once, time::{Duration, Instant}};
 use iced_layershell::to_layer_message;
+use iced_layershell::reexport::KeyboardInteractivity;
 
 
 
@@ -15,14 +16,18 @@ static WARNING_ONCE: Once = Once::new();
 
 
 // ============ CRATES ============
-use crate::modules::focused_window::{read_focused_window_hypr, read_focused_window_sway, read_focused_window_niri, };
 use crate::helpers::string::{format_input_volume, format_output_volume};
 use crate::modules::cpu_temp::read_cpu_temp;
 use crate::modules::ram::read_ram_data;
-use crate::modules::{image::preload_image, network::{read_rx_tx, PREV_NET}, disk::read_disk_data, clock::cycle_clock_timezones, cpu::{compute_cpu_usage, read_cpu_snapshot}};
-use crate::{helpers::{misc::define_bar_anchor_position, font::build_font, fs::check_if_config_file_exists, monitor::get_monitor_res}, modules::{clock::get_current_time, data::Modules, hypr::{self, change_workspace_hypr}, media_player::{MediaPlayerAction, get_player_data_with_format, media_player_action}, network::NetworkData, niri::{self, change_workspace_niri}, sway::{self, change_workspace_sway}, tray::{load_tray_menu, MenuItem, TrayEvent}, volume, workspaces::UserWorkspaceAction }};
-use crate::helpers::{misc::{is_active_module, validate_bar_data}, workspaces::build_workspace_list };
-use crate::context_menu::{create_context_menu, get_context_menu_size};
+use crate::helpers::bindings::{BindingTrigger, InternalAction, register_left_click, resolve_binding, run_internal_action, scroll_trigger};
+use crate::helpers::command::{CommandOptions, CommandRequest, cancel_running_commands, context_env, merge_env, spawn_command};
+use crate::modules::custom_modules::{get_custom_module_tooltip_size, hide_custom_module_tooltip, is_polled_custom_module, receive_custom_module_exit_code, receive_custom_module_output, refresh_custom_module_tooltip, custom_module_env, run_continuous_command, run_custom_module_command, show_custom_module_tooltip, signaled_custom_modules};
+use crate::modules::{image::{PreloadedImage, configured_image_paths, preload_image, reload_image}, network::{read_rx_tx, PREV_NET}, disk::read_disk_data, clock::cycle_clock_timezones, cpu::{compute_cpu_usage, read_cpu_snapshot}};
+use crate::{helpers::{misc::define_bar_anchor_position, font::build_font, fs::check_if_config_file_exists, icons::set_icon_target_size, monitor::{get_monitor_res, get_monitor_scale}}, modules::{clock::get_current_time, focused_window::{FocusedWindowData, apply_fetched_focused_window}, compositor::{focused_window_backend, workspace_backend}, data::Modules, media_player::{MediaPlayerAction, get_player_data_with_format, media_player_action}, network::NetworkData, tray::{create_tray_drawer, get_tray_drawer_size, load_tray_menu, MenuItem, TrayEvent}, volume, workspaces::{WorkspaceInfo, apply_fetched_workspaces, request_workspace_icons, workspace_action_for} }};
+use crate::helpers::misc::{is_active_module, validate_bar_data};
+use crate::helpers::icons::request_app_icons;
+use crate::modules::taskbar::{TaskbarAction, TaskbarSnapshot, fetch_taskbar_snapshot, taskbar_window_action};
+use crate::context_menu::{ContextMenuData, MenuKeyOutcome, MenuSource, anchored_popup_placement, create_context_menu, cursor_popup_placement, get_context_menu_size, handle_context_menu_key, parse_mnemonic, pick_anchor_bounds, popup_layer_shell, resize_context_menu, user_menu_to_items};
 use crate::ron::read_ron_config;
 use crate::{warning::create_warning, MAIN_ID, AppData, WindowInfo};
 
@@ -37,26 +42,35 @@ pub enum Message
 {
     //CONTEXT MENU
     TrayAction(String, String, i32, String),
-    MouseButtonClicked,
+    MouseButtonClicked(mouse::Button),
     CloseContextMenu,
+    ContextMenuKeyPressed(iced::keyboard::Key),
+    ContextMenuOpenSubmenu(usize),
+    ContextMenuCloseSubmenu,
+    ContextMenuActivate(usize),
+    OpenUserMenu(String),
+    UserMenuResolved(String, Vec<MenuItem>),
+    PopupAnchorResolved(iced::window::Id, Vec<iced::Rectangle>),
+    ToggleTrayDrawer,
     CloseWarning,
 
-    MediaPlayerDataFetched(crate::modules::media_player::MediaPlayerData),
-    CreateCustomModuleCommand((Option<usize>, Vec<String>, String, bool, bool)),
+    MediaPlayerDataFetched(Option<crate::modules::media_player::MediaPlayerData>),
+    CreateCustomModuleCommand(CommandRequest),
     MenuLoaded(String, String, Vec<MenuItem>),
     ContinuousCommandFinished(usize, String),
     ToggleAltClockAndCycleClockTimeZones,
-    IsHoveringMediaPlayerMetaData(bool),
     TrayIconClicked(usize),
     MouseWheelScrolled(ScrollDelta),
     CommandFinished(usize, String),
+    CommandExited(usize, Option<i32>),
     WorkspaceButtonPressed(i32),
-    IsHoveringVolumeOutput(bool),
-    IsHoveringVolumeInput(bool),
     NetworkUpdated(NetworkData),
-    IsHoveringWorkspace(bool),
+    ModuleHovered(Modules, bool),
+    ModuleBindingTriggered(Modules, BindingTrigger),
+    InternalAction(InternalAction),
     MediaPlayerClickPlayPause,
     CursorMoved(iced::Point),
+    WindowCursorMoved(iced::window::Id, iced::Point),
     MuteAudioPressedOutput,
     MuteAudioPressedInput,
     MediaPlayerClickNext,
@@ -70,24 +84,31 @@ pub enum Message
 
     Tick,
     VolumeUpdated(f32, bool, f32, bool),
-    FocusedWindowSwayFetched(Option<String>),
-    FocusedWindowNiriFetched(Option<String>),
-    SwayWorkspacesFetched(i32, Vec<i32>),
-    NiriWorkspacesFetched(i32, Vec<i32>),
-    HyprWorkspacesFetched(i32, Vec<i32>),
+    FocusedWindowFetched(Option<FocusedWindowData>),
+    BindingModeChanged(String),
+    WorkspacesFetched(i32, Vec<WorkspaceInfo>),
+    AppIconsResolved(Vec<(String, Option<(Vec<u8>, u32, u32)>)>),
+    TaskbarFetched(TaskbarSnapshot),
+    TaskbarWindowPressed(String),
+    TaskbarWindowMiddlePressed(String),
+    ShowCustomModuleTooltip(usize, String),
+    HideCustomModuleTooltip,
+    CustomModuleSignal(u8),
+    ImagePathChanged(usize, String, Option<std::time::SystemTime>),
+    ImageFileChanged(usize),
+    ImageLoaded(usize, String, PreloadedImage),
 
     UpdateNetworkSpeed,
     UpdateDisk,
     UpdateRam,
     UpdateCpu,
     UpdateCpuTemp,
-    UpdateFocusedWindowNiri,
-    UpdateFocusedWindowSway,
-    UpdateFocusedWindowHypr,
+    UpdateFocusedWindow,
     UpdateMediaPlayerMetadata,
-    UpdateNiriWorkspaces,
-    UpdateSwayWorkspaces,
-    UpdateHyprWorkspaces,
+    UpdateWorkspaces,
+    UrgentBlinkTick,
+    StateBlinkTick,
+    UpdateTaskbar,
     UpdateClock
 }
 
@@ -144,23 +165,127 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
             }
         }
 
-        Message::MouseButtonClicked =>
+        Message::WindowCursorMoved(id, position) =>
         {
+            // Module bounds are relative to the bar surface, so only its own cursor position can pick the popup anchor
+            if MAIN_ID.get() == Some(&id) { app.context_menu_data.bar_mouse_position = (position.x as i32, position.y as i32); }
+            return update(app, Message::CursorMoved(position));
+        }
+
+        Message::MouseButtonClicked(button) =>
+        {
+            let double_click = if button == mouse::Button::Left { register_left_click(&mut app.modules_data.binding_data, Instant::now()) } else { None };
+            let binding_task = match double_click
+            {
+                Some(module) => resolve_binding(app, &module, BindingTrigger::DoubleClick),
+                None => Task::none(),
+            };
+
             let has_context_menu = app.ids.values().any(|v| *v == WindowInfo::ContextMenu);
-            if !has_context_menu { return Task::none(); }  // add this guard
+            if !has_context_menu { return binding_task; }  // add this guard
             app.context_menu_data.context_menu_is_open = false;
             if !app.context_menu_data.cursor_is_inside_menu
             {
                 let window_ids_to_close: Vec<iced::window::Id> = app.ids.iter().filter(|(_, info)| **info == WindowInfo::ContextMenu).map(|(id, _)| *id).collect();
                 for id in &window_ids_to_close { app.ids.remove(id);  }
-                return Task::batch(window_ids_to_close.into_iter().map(|id| Task::done(Message::RemoveWindow(id))));
+                return Task::batch(window_ids_to_close.into_iter().map(|id| Task::done(Message::RemoveWindow(id))).chain([binding_task]));
             }
+            return binding_task;
         }
 
         Message::CloseContextMenu =>
         {
             app.context_menu_data.context_menu_is_open = false;
+            let window_ids_to_close: Vec<iced::window::Id> = app.ids.iter().filter(|(_, info)| **info == WindowInfo::ContextMenu || **info == WindowInfo::TrayDrawer).map(|(id, _)| *id).collect();
+            for id in &window_ids_to_close { app.ids.remove(id);  }
+            return Task::batch(window_ids_to_close.into_iter().map(|id| Task::done(Message::RemoveWindow(id))));
+        }
+
+        Message::ContextMenuKeyPressed(key) =>
+        {
+            if !app.ids.values().any(|info| *info == WindowInfo::ContextMenu) { return Task::none(); }
+            match handle_context_menu_key(&mut app.context_menu_data, &key, Instant::now())
+            {
+                MenuKeyOutcome::Nothing => {}
+                MenuKeyOutcome::Resize => return resize_context_menu(app),
+                MenuKeyOutcome::Activate(index) => return Task::done(Message::ContextMenuActivate(index)),
+            }
+        }
+
+        Message::ContextMenuActivate(index) =>
+        {
+            let Some(item) = app.context_menu_data.current_items().get(index) else { return Task::none(); };
+            let label = parse_mnemonic(&item.label).0;
+            match &app.context_menu_data.source
+            {
+                MenuSource::Tray => return Task::done(Message::TrayAction(app.context_menu_data.service.clone(), app.context_menu_data.path.clone(), item.id, label)),
+                MenuSource::User(menu_name) =>
+                {
+                    let command = item.command.clone();
+                    let custom_name = format!("{menu_name} -> {label}");
+                    if command.is_empty() { return Task::none(); }
+                    return Task::batch([Task::done(Message::CloseContextMenu), Task::done(Message::CreateCustomModuleCommand(CommandRequest { command, name: custom_name, module: "Menu".into(), is_left_click: true, ..Default::default() }))]);
+                }
+            }
+        }
+
+        Message::OpenUserMenu(menu_name) =>
+        {
+            let Some(menu) = app.ron_config.menus.get(&menu_name).cloned() else { eprintln!("No menu named '{menu_name}' in the 'menus' section of your config!!!"); return Task::none(); };
+            let icon_size = app.ron_config.context_menu.context_menu_icon_size;
+            // Icons may hit the disk and the icon theme, so the items are built off the UI thread
+            return Task::perform(tokio::task::spawn_blocking(move || user_menu_to_items(&menu, icon_size, &mut 0)), move |result| match result
+            {
+                Ok(items) => Message::UserMenuResolved(menu_name, items),
+                Err(err) => { eprintln!("[icebar] Menu '{menu_name}' couldn't be built: {err}"); Message::Nothing }
+            });
+        }
+
+        Message::UserMenuResolved(menu_name, items) =>
+        {
             let window_ids_to_close: Vec<iced::window::Id> = app.ids.iter().filter(|(_, info)| **info == WindowInfo::ContextMenu).map(|(id, _)| *id).collect();
+            for id in &window_ids_to_close { app.ids.remove(id); }
+            let close_tasks = Task::batch(window_ids_to_close.into_iter().map(|id| Task::done(Message::RemoveWindow(id))));
+
+            app.context_menu_data = ContextMenuData
+            {
+                mouse_position: app.context_menu_data.mouse_position,
+                bar_mouse_position: app.context_menu_data.bar_mouse_position,
+                default_font: app.default_font,
+                context_menu_is_open: true,
+                source: MenuSource::User(menu_name),
+                items,
+                ..Default::default()
+            };
+            return Task::batch([close_tasks, create_context_menu(app)]);
+        }
+
+        Message::ContextMenuOpenSubmenu(index) => { if app.context_menu_data.open_submenu(index) { return resize_context_menu(app); } }
+        Message::ContextMenuCloseSubmenu => { if app.context_menu_data.close_submenu() { return resize_context_menu(app); } }
+
+        Message::PopupAnchorResolved(id, bounds) =>
+        {
+            // The popup may have been closed again before its anchor was resolved
+            let (size, widget, keyboard_interactivity) = match app.ids.get(&id)
+            {
+                Some(WindowInfo::ContextMenu) => (get_context_menu_size(&app.context_menu_data, &app.ron_config), pick_anchor_bounds(&bounds, app.context_menu_data.bar_mouse_position), KeyboardInteractivity::Exclusive),
+                Some(WindowInfo::TrayDrawer) => (get_tray_drawer_size(app), pick_anchor_bounds(&bounds, app.context_menu_data.bar_mouse_position), KeyboardInteractivity::Exclusive),
+                // Tooltips only ever resolve the hovered module, and must not steal the keyboard
+                Some(WindowInfo::Tooltip) => (get_custom_module_tooltip_size(app), bounds.first().copied(), KeyboardInteractivity::None),
+                _ => return Task::none(),
+            };
+            let placement = match widget
+            {
+                Some(widget) => anchored_popup_placement(&app.ron_config.general.bar_position, widget, size, app.monitor_size, app.ron_config.general.floating_space),
+                None => cursor_popup_placement(app, size),
+            };
+            return popup_layer_shell(id, size, placement, keyboard_interactivity);
+        }
+
+        Message::ToggleTrayDrawer =>
+        {
+            let window_ids_to_close: Vec<iced::window::Id> = app.ids.iter().filter(|(_, info)| **info == WindowInfo::TrayDrawer).map(|(id, _)| *id).collect();
+            if window_ids_to_close.is_empty() { return create_tray_drawer(app); }
             for id in &window_ids_to_close { app.ids.remove(id);  }
             return Task::batch(window_ids_to_close.into_iter().map(|id| Task::done(Message::RemoveWindow(id))));
         }
@@ -176,15 +301,63 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
             for id in &window_ids_to_close { app.ids.remove(id);  }
             return Task::batch(window_ids_to_close.into_iter().map(|id| Task::done(Message::RemoveWindow(id))));
         }
-        Message::IsHoveringVolumeOutput(bool) => { app.modules_data.volume_data.is_hovering_volume_output = bool; }
-        Message::IsHoveringVolumeInput(bool) => { app.modules_data.volume_data.is_hovering_volume_input = bool; }
-        Message::IsHoveringWorkspace(bool) => { app.modules_data.workspace_data.is_hovering_workspace = bool; }
-        Message::IsHoveringMediaPlayerMetaData(bool) => { app.modules_data.media_player_data.is_hovering_media_player_meta_data = bool; }
+        Message::ModuleHovered(module, is_hovering) =>
+        {
+            let binding_data = &mut app.modules_data.binding_data;
+            if is_hovering { binding_data.hovered_module = Some(module.clone()); }
+            // The next module may be entered before this one is left
+            else if binding_data.hovered_module.as_ref() == Some(&module) { binding_data.hovered_module = None; }
+            return resolve_binding(app, &module, if is_hovering { BindingTrigger::HoverEnter } else { BindingTrigger::HoverLeave });
+        }
+        Message::ModuleBindingTriggered(module, trigger) => return resolve_binding(app, &module, trigger),
+        Message::InternalAction(action) => return run_internal_action(app, action),
+        Message::ImagePathChanged(index, path, modified) =>
+        {
+            let image_data = &mut app.modules_data.image_data;
+            let Some(current_path) = image_data.image_paths.get_mut(index) else { return Task::none(); };
+            let is_loaded = image_data.preloaded_images_handle.get(index).is_some_and(Option::is_some);
+            // The command may keep printing the same path while the file behind it is rewritten, e.g: album art
+            let is_same_file = *current_path == path && image_data.image_modified.get(&index).copied() == modified;
+            if is_same_file && is_loaded { return Task::none(); }
+            *current_path = path.clone();
+            match modified
+            {
+                Some(modified) => { image_data.image_modified.insert(index, modified); }
+                None => { image_data.image_modified.remove(&index); }
+            }
+            return reload_image(app, index, path);
+        }
+        Message::ImageFileChanged(index) =>
+        {
+            let Some(path) = app.modules_data.image_data.image_paths.get(index).cloned() else { return Task::none(); };
+            if let Ok(modified) = std::fs::metadata(&path).and_then(|metadata| metadata.modified()) { app.modules_data.image_data.image_modified.insert(index, modified); }
+            return reload_image(app, index, path);
+        }
+        Message::ImageLoaded(index, path, image) =>
+        {
+            // The command may have moved on to another path while this one was loading
+            if app.modules_data.image_data.image_paths.get(index) != Some(&path) { return Task::none(); }
+            if let Some(slot) = app.modules_data.image_data.preloaded_images_handle.get_mut(index) { *slot = Some((image, index)); }
+        }
         Message::MuteAudioPressedOutput => { return volume::volume( volume::VolumeAction::MuteOutput); }
         Message::MuteAudioPressedInput => { return volume::volume( volume::VolumeAction::MuteInput); }
         Message::ToggleAltClock => { app.modules_data.clock_data.is_showing_alt_clock = !app.modules_data.clock_data.is_showing_alt_clock; }
-        Message::CommandFinished(index, text) => { if app.modules_data.custom_module_data.cached_command_outputs.len() <= index { app.modules_data.custom_module_data.cached_command_outputs.resize(index + 1, String::new()); } app.modules_data.custom_module_data.cached_command_outputs[index] = text; }
-        Message::ContinuousCommandFinished(index, text) => { if app.modules_data.custom_module_data.cached_continuous_outputs.len() <= index { app.modules_data.custom_module_data.cached_continuous_outputs.resize(index + 1, String::new()); } app.modules_data.custom_module_data.cached_continuous_outputs[index] = text; }
+        Message::CommandFinished(index, text) => { receive_custom_module_output(app, index, text, false); return refresh_custom_module_tooltip(app, index); }
+        Message::ContinuousCommandFinished(index, text) => { receive_custom_module_output(app, index, text, true); return refresh_custom_module_tooltip(app, index); }
+        Message::CommandExited(index, exit_code) => receive_custom_module_exit_code(app, index, exit_code),
+        Message::ShowCustomModuleTooltip(index, anchor) => return show_custom_module_tooltip(app, index, anchor),
+        Message::HideCustomModuleTooltip => return hide_custom_module_tooltip(app),
+        Message::CustomModuleSignal(signal) =>
+        {
+            let mut tasks = Vec::new();
+            for index in signaled_custom_modules(app, signal)
+            {
+                if let Some(last_run) = app.modules_data.custom_module_data.custom_module_last_run.get_mut(index) { *last_run = Instant::now(); }
+                let env = custom_module_env(app, index, "");
+                tasks.push(run_continuous_command(index, &app.ron_config.custom_module.custom_modules[index], env));
+            }
+            return Task::batch(tasks);
+        }
         Message::MediaPlayerClickNext => return media_player_action(&app.ron_config.media_player_metadata.player, MediaPlayerAction::Next),
         Message::MediaPlayerClickPlayPause => return media_player_action(&app.ron_config.media_player_metadata.player, MediaPlayerAction::PlayPause),
         Message::MediaPlayerClickPrev => return media_player_action(&app.ron_config.media_player_metadata.player, MediaPlayerAction::Prev),
@@ -192,55 +365,76 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
         Message::ToggleAltClockAndCycleClockTimeZones => { app.modules_data.clock_data.is_showing_alt_clock = !app.modules_data.clock_data.is_showing_alt_clock; cycle_clock_timezones(app); },
         Message::UpdateCpuTemp => if let Some(temp) = read_cpu_temp() { app.modules_data.cpu_temp_data.temp_celsius = temp; }
         Message::UpdateRam => { if let Some(data) = read_ram_data() { app.modules_data.ram_data = data; }},
-        Message::FocusedWindowNiriFetched(title) => { app.modules_data.focused_window_data.title = title.unwrap_or_default(); }
-        Message::FocusedWindowSwayFetched(title) => { app.modules_data.focused_window_data.title = title.unwrap_or_default(); }
-        Message::UpdateFocusedWindowNiri => { return Task::perform(tokio::task::spawn_blocking(read_focused_window_niri), |result| Message::FocusedWindowNiriFetched(result.ok().flatten())); }
-        Message::UpdateFocusedWindowSway => { return Task::perform(tokio::task::spawn_blocking(read_focused_window_sway), |result| Message::FocusedWindowSwayFetched(result.ok().flatten())); }
-        Message::UpdateFocusedWindowHypr => { app.modules_data.focused_window_data.title = read_focused_window_hypr().unwrap_or_default(); },
-        Message::SwayWorkspacesFetched(current, list) => { app.modules_data.workspace_data.current_workspace  = current; app.modules_data.workspace_data.visible_workspaces = list; }
-        Message::NiriWorkspacesFetched(current, list) => { app.modules_data.workspace_data.current_workspace  = current; app.modules_data.workspace_data.visible_workspaces = list; }
-        Message::HyprWorkspacesFetched(current, list) => { app.modules_data.workspace_data.current_workspace  = current; app.modules_data.workspace_data.visible_workspaces = list; }
-        Message::MediaPlayerDataFetched(data) => { app.modules_data.media_player_data = data; }
-
-        Message::WorkspaceButtonPressed(id) =>
+        Message::FocusedWindowFetched(window) => return apply_fetched_focused_window(app, window),
+        Message::BindingModeChanged(mode) => { app.modules_data.binding_mode_data.mode = mode; }
+        Message::UpdateFocusedWindow =>
         {
-            if is_active_module(&app.modules_data.active_modules, Modules::HyprWorkspaces)
-            {
-                change_workspace_hypr(UserWorkspaceAction::ChangeWithIndex(id)); // hypr is fine as-is
-            }
-            else if is_active_module(&app.modules_data.active_modules, Modules::SwayWorkspaces)
+            let Some(backend) = focused_window_backend(&app.modules_data.active_modules) else { return Task::none(); };
+            return Task::perform(tokio::task::spawn_blocking(move || backend.focused_window()), |result| Message::FocusedWindowFetched(result.ok().flatten()));
+        }
+        Message::WorkspacesFetched(current, list) => 
+        { 
+            let persistent = workspace_backend(&app.modules_data.active_modules).filter(|backend| backend.supports_persistent_workspaces()).and(app.ron_config.workspace.persistent_workspaces);
+            apply_fetched_workspaces(app, current, list, persistent); 
+            return request_workspace_icons(app); 
+        }
+        Message::UpdateTaskbar =>
+        {
+            if !is_active_module(&app.modules_data.active_modules, Modules::Taskbar) { return Task::none(); }
+            return Task::perform(tokio::task::spawn_blocking(fetch_taskbar_snapshot), |result| Message::TaskbarFetched(result.ok().flatten().unwrap_or_default()));
+        }
+        Message::TaskbarFetched(snapshot) =>
+        {
+            app.modules_data.taskbar_data.snapshot = snapshot;
+            if app.ron_config.taskbar.taskbar_show_icons
             {
-                return Task::perform(
-                    tokio::task::spawn_blocking(move || change_workspace_sway(UserWorkspaceAction::ChangeWithIndex(id))),
-                    |_| Message::Nothing,
-                );
+                let app_ids: Vec<String> = app.modules_data.taskbar_data.snapshot.windows.iter().map(|window| window.app_id.clone()).collect();
+                let size = app.ron_config.taskbar.taskbar_icon_size;
+                return request_app_icons(app, app_ids, size);
             }
-            else if is_active_module(&app.modules_data.active_modules, Modules::NiriWorkspaces)
+        }
+        Message::TaskbarWindowPressed(id) => { return Task::perform(tokio::task::spawn_blocking(move || taskbar_window_action(id, TaskbarAction::Focus)), |_| Message::Nothing); }
+        Message::TaskbarWindowMiddlePressed(id) => { return Task::perform(tokio::task::spawn_blocking(move || taskbar_window_action(id, TaskbarAction::Close)), |_| Message::Nothing); }
+        Message::AppIconsResolved(icons) =>
+        {
+            for (app_id, icon) in icons
             {
-                return Task::perform(
-                    tokio::task::spawn_blocking(move || change_workspace_niri(UserWorkspaceAction::ChangeWithIndex(id))),
-                    |_| Message::Nothing,
-                );
+                app.modules_data.app_icons.insert(app_id, icon.map(|(data, width, height)| image::Handle::from_rgba(width, height, data)));
             }
         }
+        Message::UrgentBlinkTick => { app.modules_data.workspace_data.urgent_blink_on = !app.modules_data.workspace_data.urgent_blink_on; }
+        Message::StateBlinkTick => { app.modules_data.state_blink_off = !app.modules_data.state_blink_off; }
+        // A skipped run keeps the last data instead of blanking the module
+        Message::MediaPlayerDataFetched(data) => { if let Some(data) = data { app.modules_data.media_player_data = data; } }
 
-        Message::UpdateHyprWorkspaces =>
+        Message::WorkspaceButtonPressed(id) =>
         {
-            let persistent = app.ron_config.workspace.persistent_workspaces;
-            return Task::perform(tokio::task::spawn_blocking(move || { (hypr::current_workspace(), hypr::workspace_count()) }), move |result| 
-            {
-                let (current, counts) = result.unwrap_or((0, vec![]));
-                Message::HyprWorkspacesFetched(current, build_workspace_list(&counts, persistent))
-            },);
+            let Some(backend) = workspace_backend(&app.modules_data.active_modules) else { return Task::none(); };
+            let action = workspace_action_for(&app.modules_data.workspace_data, id);
+            return Task::perform(tokio::task::spawn_blocking(move || backend.change_workspace(action)), |_| Message::Nothing);
         }
 
-        Message::UpdateSwayWorkspaces =>
+        Message::UpdateWorkspaces =>
         {
-            let persistent = app.ron_config.workspace.persistent_workspaces;
-            return Task::perform(tokio::task::spawn_blocking(move || { (sway::current_workspace(), sway::workspace_count()) }), move |result| 
+            let Some(backend) = workspace_backend(&app.modules_data.active_modules) else { return Task::none(); };
+            WARNING_ONCE.call_once(|| 
+            {
+                if !backend.supports_persistent_workspaces() && app.ron_config.workspace.persistent_workspaces.is_some()
+                {
+                    let compositor = backend.compositor();
+                    println!("\n=== {compositor:?} Workspaces Warning ===");
+                    for _ in 0..3
+                    {
+                        println!("Warning!!!: Persistent Elements Defined But {compositor:?} Doesn't Support Persistent Workspaces.");
+                    }
+                    println!("\n");
+                }
+            });
+
+            return Task::perform(tokio::task::spawn_blocking(move || backend.workspaces()), |result| 
             {
                 let (current, counts) = result.unwrap_or((0, vec![]));
-                Message::SwayWorkspacesFetched(current, build_workspace_list(&counts, persistent))
+                Message::WorkspacesFetched(current, counts)
             },);
         }
 
@@ -326,28 +520,6 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
             }
         }
 
-        Message::UpdateNiriWorkspaces =>
-        { 
-            WARNING_ONCE.call_once(|| 
-            {
-                if app.ron_config.workspace.persistent_workspaces.is_some()
-                {
-                    println!("\n=== Niri Workspaces Warning ===");
-                    for _ in 0..3
-                    {
-                        println!("Warning!!!: Persistent Elements Defined But Niri Doesn't Support Persistent Workspaces.");
-                    }
-                    println!("\n");
-                }
-            });
-
-            return Task::perform(tokio::task::spawn_blocking(|| { (niri::current_workspace(), niri::workspace_count()) }), |result| 
-            {
-                let (current, counts) = result.unwrap_or((0, vec![]));
-                Message::NiriWorkspacesFetched(current, build_workspace_list(&counts, None))
-            },);
-        }
-
         Message::UpdateClock =>
         {
             let format_to_send = if app.modules_data.clock_data.is_showing_alt_clock 
@@ -386,6 +558,7 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
             let preloaded_images = preload_image(&mut warning_err, &mut config_parsed_failed, &new_config.image.images);
             let new_anchor = define_bar_anchor_position(&new_config.general.bar_position);
             let monitor_res = get_monitor_res(new_config.general.display.clone());
+            set_icon_target_size((new_config.tray.tray_icon_size as f32 * get_monitor_scale(new_config.general.display.clone())).round() as u32);
             let font_name = new_config.general.font_family.clone();
             let new_font = if font_name != app.ron_config.general.font_family { build_font(&font_name, &new_config.general.font_style) } else { app.default_font };
             let mut modules_data = app.modules_data.clone();
@@ -395,7 +568,13 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
             modules_data.network_data.connection_type_icons = new_config.network.network_connection_type_icons.clone();
             modules_data.network_data.network_icons = new_config.network.network_level_format.clone();
             modules_data.custom_module_data.custom_module_last_run = vec![Instant::now() - Duration::from_secs(3600); new_config.custom_module.custom_modules.len()];
+            modules_data.custom_module_data.config_generation += 1;
+            // The reload resizes the bar back to its configured size
+            modules_data.binding_data.bar_hidden = false;
+            cancel_running_commands();
             modules_data.image_data.preloaded_images_handle = preloaded_images;
+            modules_data.image_data.image_paths = configured_image_paths(&new_config.image.images);
+            modules_data.image_data.image_modified.clear();
 
             let old_config_parse_status = app.config_parsed_failed;
 
@@ -445,58 +624,11 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
             return Task::batch(task_vec);
         }
 
-        Message::MouseWheelScrolled(ScrollDelta::Pixels { x: _, y }) =>
+        Message::MouseWheelScrolled(delta) =>
         {
-            if app.modules_data.media_player_data.is_hovering_media_player_meta_data
-            {
-                if y > 0. { return media_player_action(&app.ron_config.media_player_metadata.player, MediaPlayerAction::VolumeUp); }
-                if y < 0. { return media_player_action(&app.ron_config.media_player_metadata.player, MediaPlayerAction::VolumeDown); }
-            }
-
-            if app.modules_data.volume_data.is_hovering_volume_output
-            {
-                if y > 0. { return volume::volume(volume::VolumeAction::IncreaseOutput(app.ron_config.volume_output.incremental_steps_output)); }
-                if y < 0. { return volume::volume(volume::VolumeAction::DecreaseOutput(app.ron_config.volume_output.incremental_steps_output)); }
-            }
-
-            if app.modules_data.volume_data.is_hovering_volume_input
+            if let Some(module) = app.modules_data.binding_data.hovered_module.clone() && let Some(trigger) = scroll_trigger(delta)
             {
-                if y > 0. { return volume::volume(volume::VolumeAction::IncreaseInput(app.ron_config.volume_input.incremental_steps_input)); }
-                if y < 0. { return volume::volume(volume::VolumeAction::DecreaseInput(app.ron_config.volume_input.incremental_steps_input)); }
-            }
-
-            if app.modules_data.workspace_data.is_hovering_workspace
-            {
-                let hypr_active = is_active_module(&app.modules_data.active_modules, Modules::HyprWorkspaces);
-                let sway_active = is_active_module(&app.modules_data.active_modules, Modules::SwayWorkspaces);
-                let niri_active = is_active_module(&app.modules_data.active_modules, Modules::NiriWorkspaces);
-                // === SCROLL UP ===
-                if y > 0.
-                {
-                    if app.ron_config.workspace.reverse_scroll_on_workspace
-                    {
-                        if hypr_active { change_workspace_hypr(UserWorkspaceAction::MoveNext); }
-                        else if sway_active { return Task::perform(tokio::task::spawn_blocking(|| change_workspace_sway(UserWorkspaceAction::MoveNext)), |_| Message::Nothing); }
-                        else if niri_active { return Task::perform(tokio::task::spawn_blocking(|| change_workspace_niri(UserWorkspaceAction::MoveNext)), |_| Message::Nothing); }
-                    }
-                    else if hypr_active { change_workspace_hypr(UserWorkspaceAction::MovePrev); }
-                    else if sway_active { return Task::perform(tokio::task::spawn_blocking(|| change_workspace_sway(UserWorkspaceAction::MovePrev)), |_| Message::Nothing); }
-                    else if niri_active { return Task::perform(tokio::task::spawn_blocking(|| change_workspace_niri(UserWorkspaceAction::MovePrev)), |_| Message::Nothing); }
-                }
-                
-                // === SCROLL DOWN ===
-                if y < 0.
-                {
-                    if app.ron_config.workspace.reverse_scroll_on_workspace
-                    {
-                        if hypr_active { change_workspace_hypr(UserWorkspaceAction::MovePrev); }
-                        else if sway_active { return Task::perform(tokio::task::spawn_blocking(|| change_workspace_sway(UserWorkspaceAction::MovePrev)), |_| Message::Nothing); }
-                        else if niri_active { return Task::perform(tokio::task::spawn_blocking(|| change_workspace_niri(UserWorkspaceAction::MovePrev)), |_| Message::Nothing); }
-                    }
-                    else if hypr_active { change_workspace_hypr(UserWorkspaceAction::MoveNext); }
-                    else if sway_active { return Task::perform(tokio::task::spawn_blocking(|| change_workspace_sway(UserWorkspaceAction::MoveNext)), |_| Message::Nothing); }
-                    else if niri_active { return Task::perform(tokio::task::spawn_blocking(|| change_workspace_niri(UserWorkspaceAction::MoveNext)), |_| Message::Nothing); }
-                }
+                return resolve_binding(app, &module, trigger);
             }
         }
 
@@ -514,36 +646,10 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
                 {
                     let index  = *index;
                     let Some(module) = app.ron_config.custom_module.custom_modules.get(index) else { continue; };
-                    if module.continous_command.is_empty() { continue; }
+                    if !is_polled_custom_module(module) { continue; }
                     if app.modules_data.custom_module_data.custom_module_last_run[index].elapsed() < Duration::from_millis(module.continous_command_interval) { continue; }
                     app.modules_data.custom_module_data.custom_module_last_run[index] = Instant::now();
-        
-                    if let Some((program, args)) = module.continous_command.split_first()
-                    {
-                        let program     = program.clone();
-                        let args        = args.to_vec();
-                        let display_err = module.display_err_output_if_failed;
-        
-                        tasks.push(Task::perform
-                        (
-                            async move 
-                            {
-                                let out = tokio::process::Command::new(program).args(args).output().await.ok();
-                                out.map
-                                (|o| { 
-                                    if o.stdout.is_empty() && display_err 
-                                    { 
-                                        String::from_utf8_lossy(&o.stderr).into() 
-                                    } 
-                                    else
-                                    { 
-                                        String::from_utf8_lossy(&o.stdout).into() 
-                                    }
-                                }).unwrap_or_default()
-                            },
-                            move |text| Message::ContinuousCommandFinished(index, text),
-                        ));
-                    }
+                    tasks.push(run_continuous_command(index, module, custom_module_env(app, index, "")));
                 }
             }
             return Task::batch(tasks);
@@ -556,42 +662,37 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
 
 
 
-        Message::CreateCustomModuleCommand((output_index, command_vec, custom_name, is_left_click, output_as_text)) =>
+        Message::CreateCustomModuleCommand(request) =>
         {
-            if let Some((program, args)) = command_vec.split_first()
+            if let Some((program, args)) = request.command.split_first()
             {
                 let program = program.clone();
                 let args = args.to_vec();
+                let custom_name = &request.name;
         
                 println!("\n=== Custom Module ===");
-                if custom_name.is_empty() {if is_left_click { println!("Custom Module Button Was *Left* Clicked!!"); } else { println!("Custom Module Button Was *Right* Clicked!!"); } } else if is_left_click { println!("Your '{custom_name}' Button Was *Left* Clicked!!"); } else { println!("Your '{custom_name}' Button Was *Right* Clicked!!"); }
+                if let Some(button) = &request.button { println!("Your '{custom_name}' Binding Was Triggered By *{button}*!!"); }
+                else if custom_name.is_empty() {if request.is_left_click { println!("Custom Module Button Was *Left* Clicked!!"); } else { println!("Custom Module Button Was *Right* Clicked!!"); } } else if request.is_left_click { println!("Your '{custom_name}' Button Was *Left* Clicked!!"); } else { println!("Your '{custom_name}' Button Was *Right* Clicked!!"); }
+                let label = if custom_name.is_empty() { "Custom Module command".to_string() } else { format!("'{custom_name}' command") };
+                let button = request.button.as_deref().unwrap_or(if request.is_left_click { "left" } else { "right" });
+                let env = merge_env(context_env(app, &request.module, button), &request.env);
         
 
                 // ==============================
                 // OUTPUT USED → async + message
                 // ==============================
-                if output_as_text 
+                if request.output_as_text 
                 {
-                    return Task::perform
-                    (async move 
-                        {
-                                let output = tokio::process::Command::new(program).args(args).output().await.ok();
-                                if custom_name.is_empty() { println!("Custom Module Output:\n{:?}", output); } else { println!( "'{custom_name}' Command Was Running!!!, The Output Was:\n{:?}", output); }
-                                output.map(|o| String::from_utf8_lossy(&o.stdout).to_string()).unwrap_or_default()
-                        },
-                        move |text| { Message::CommandFinished(output_index.unwrap_or(0), text) },
-                    );
+                    let index = request.output_index.unwrap_or(0);
+                    let module = app.ron_config.custom_module.custom_modules.get(index).cloned().unwrap_or_default();
+                    return run_custom_module_command(index, &module, request.command.clone(), false, label, env);
                 }
         
 
                 // ==============================
                 // FIRE & FORGET → no message
                 // ==============================
-                tokio::spawn(async move 
-                {
-                    let output = tokio::process::Command::new(program).args(args).output().await;
-                    if custom_name.is_empty() { println!("Custom Module Output:\n{:?}", output); } else { println!( "'{custom_name}' Command executed (no output capture):\n{:?}", output); }
-                });
+                spawn_command(program, args, CommandOptions { label, shell: request.shell, env, ..Default::default() });
         
                 return Task::none();
             }
@@ -633,13 +734,16 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
         Message::TrayIconClicked(idx) =>
         {
             println!("TrayIcon Clicked");
+            let drawer_ids_to_close: Vec<iced::window::Id> = app.ids.iter().filter(|(_, info)| **info == WindowInfo::TrayDrawer).map(|(id, _)| *id).collect();
+            for id in &drawer_ids_to_close { app.ids.remove(id); }
+            let close_tasks = Task::batch(drawer_ids_to_close.into_iter().map(|id| Task::done(Message::RemoveWindow(id))));
             if let Some((_, combined)) = app.modules_data.tray_icons.get(idx)
             {
                 let parts: Vec<&str> = combined.split('|').collect();
-                if parts.len() != 2 { return Task::none(); }
+                if parts.len() != 2 { return close_tasks; }
                 let service = parts[0].to_string();
                 let path = parts[1].to_string();
-                return Task::perform(async move { load_tray_menu(service, path).await }, |result| match result 
+                let load_task = Task::perform(async move { load_tray_menu(service, path).await }, |result| match result 
                 {
                         Ok((s, p, i)) => Message::MenuLoaded(s, p, i),
                         Err(e) => 
@@ -649,8 +753,9 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
                         }
                     }
                 );
+                return Task::batch([close_tasks, load_task]);
             }
-            return Task::none();
+            return close_tasks;
         }
 
 
@@ -660,15 +765,17 @@ pub fn update(app: &mut AppData, message: Message) -> Task<Message>
             println!("Service: {service}");
             println!("Menu Path: {path}");
             println!("Id: {:?}\n", items);
-            let context_menu_data = crate::context_menu::ContextMenuData 
+            let context_menu_data = ContextMenuData 
             {
                 mouse_position: app.context_menu_data.mouse_position,
+                bar_mouse_position: app.context_menu_data.bar_mouse_position,
                 default_font: app.default_font,
                 cursor_is_inside_menu: false, 
                 context_menu_is_open: true,
                 service,
                 items,
                 path,
+                ..Default::default()
             };
             app.context_menu_data = context_menu_data;
             
@@ -701,47 +808,37 @@ mod tests
         }
     }
  
-    // ---- IsHovering* flags --------------------------------------------------
+    // ---- ModuleHovered ------------------------------------------------------
  
     #[test]
-    fn message_is_hovering_volume_output_sets_flag_true()
+    fn module_hovered_tracks_the_module_under_the_cursor()
     {
         let mut app = make_app();
-        let _ = update(&mut app, Message::IsHoveringVolumeOutput(true));
-        assert!(app.modules_data.volume_data.is_hovering_volume_output);
+        let _ = update(&mut app, Message::ModuleHovered(Modules::VolumeOutput, true));
+        assert_eq!(app.modules_data.binding_data.hovered_module, Some(Modules::VolumeOutput));
+        let _ = update(&mut app, Message::ModuleHovered(Modules::VolumeOutput, false));
+        assert_eq!(app.modules_data.binding_data.hovered_module, None);
     }
  
     #[test]
-    fn message_is_hovering_volume_output_sets_flag_false()
+    fn leaving_a_module_after_entering_the_next_keeps_the_next_one()
     {
         let mut app = make_app();
-        app.modules_data.volume_data.is_hovering_volume_output = true;
-        let _ = update(&mut app, Message::IsHoveringVolumeOutput(false));
-        assert!(!app.modules_data.volume_data.is_hovering_volume_output);
+        let _ = update(&mut app, Message::ModuleHovered(Modules::Workspaces, true));
+        let _ = update(&mut app, Message::ModuleHovered(Modules::MediaPlayerMetaData, true));
+        let _ = update(&mut app, Message::ModuleHovered(Modules::Workspaces, false));
+        assert_eq!(app.modules_data.binding_data.hovered_module, Some(Modules::MediaPlayerMetaData));
     }
  
     #[test]
-    fn message_is_hovering_volume_input_sets_flag()
+    fn left_clicks_on_the_hovered_module_are_recorded_for_double_clicks()
     {
         let mut app = make_app();
-        let _ = update(&mut app, Message::IsHoveringVolumeInput(true));
-        assert!(app.modules_data.volume_data.is_hovering_volume_input);
-    }
- 
-    #[test]
-    fn message_is_hovering_workspace_sets_flag()
-    {
-        let mut app = make_app();
-        let _ = update(&mut app, Message::IsHoveringWorkspace(true));
-        assert!(app.modules_data.workspace_data.is_hovering_workspace);
-    }
- 
-    #[test]
-    fn message_is_hovering_media_player_sets_flag()
-    {
-        let mut app = make_app();
-        let _ = update(&mut app, Message::IsHoveringMediaPlayerMetaData(true));
-        assert!(app.modules_data.media_player_data.is_hovering_media_player_meta_data);
+        let _ = update(&mut app, Message::ModuleHovered(Modules::VolumeInput, true));
+        let _ = update(&mut app, Message::MouseButtonClicked(mouse::Button::Left));
+        assert!(matches!(&app.modules_data.binding_data.last_left_click, Some((Modules::VolumeInput, _))));
+        let _ = update(&mut app, Message::MouseButtonClicked(mouse::Button::Left));
+        assert!(app.modules_data.binding_data.last_left_click.is_none());
     }
  
     // ---- ToggleAltClock -----------------------------------------------------
@@ -821,6 +918,45 @@ mod tests
         let _ = update(&mut app, Message::CursorMoved(iced::Point { x: 99.9, y: 99.9 }));
         assert_eq!(app.context_menu_data.mouse_position, (99, 99));
     }
+
+    #[test]
+    fn window_cursor_moved_tracks_bar_position_only_on_the_bar()
+    {
+        let mut app = make_app();
+        let bar = *MAIN_ID.get_or_init(iced::window::Id::unique);
+        let _ = update(&mut app, Message::WindowCursorMoved(bar, iced::Point { x: 140.0, y: 12.0 }));
+        let _ = update(&mut app, Message::WindowCursorMoved(iced::window::Id::unique(), iced::Point { x: 5.0, y: 7.0 }));
+        assert_eq!(app.context_menu_data.bar_mouse_position, (140, 12));
+        assert_eq!(app.context_menu_data.mouse_position, (5, 7));
+    }
+ 
+    // ---- ImagePathChanged ---------------------------------------------------
+
+    #[test]
+    fn image_path_changed_tracks_the_file_modification_time()
+    {
+        let mut app = make_app();
+        app.modules_data.image_data.image_paths = vec!["/tmp/cover.png".into()];
+        let modified = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(10);
+        let _ = update(&mut app, Message::ImagePathChanged(0, "/tmp/cover.png".into(), Some(modified)));
+        assert_eq!(app.modules_data.image_data.image_modified.get(&0), Some(&modified));
+        let _ = update(&mut app, Message::ImagePathChanged(0, "/tmp/other.png".into(), None));
+        assert_eq!(app.modules_data.image_data.image_paths[0], "/tmp/other.png");
+        assert!(app.modules_data.image_data.image_modified.is_empty());
+    }
+
+    // ---- MediaPlayerDataFetched ---------------------------------------------
+
+    #[test]
+    fn skipped_media_player_run_keeps_previous_data()
+    {
+        let mut app = make_app();
+        let data = crate::modules::media_player::MediaPlayerData { metadata: "Song".into(), status: "Playing".into() };
+        let _ = update(&mut app, Message::MediaPlayerDataFetched(Some(data)));
+        let _ = update(&mut app, Message::MediaPlayerDataFetched(None));
+        assert_eq!(app.modules_data.media_player_data.metadata, "Song");
+        assert_eq!(app.modules_data.media_player_data.status, "Playing");
+    }
  
     // ---- CommandFinished ----------------------------------------------------
  
@@ -1177,4 +1313,52 @@ mod tests
         assert!(app.modules_data.tray_icons[1].0.is_some()); // assigned
         assert!(app.modules_data.tray_icons[2].0.is_none()); // untouched
     }
+
+    // ---- Tray drawer ----------------------------------------------------------
+
+    #[test]
+    fn toggle_tray_drawer_opens_drawer_window()
+    {
+        let mut app = make_app();
+        let _ = update(&mut app, Message::ToggleTrayDrawer);
+        assert_eq!(app.ids.values().filter(|info| **info == WindowInfo::TrayDrawer).count(), 1);
+    }
+
+    #[test]
+    fn toggle_tray_drawer_twice_closes_drawer_window()
+    {
+        let mut app = make_app();
+        let _ = update(&mut app, Message::ToggleTrayDrawer);
+        let _ = update(&mut app, Message::ToggleTrayDrawer);
+        assert!(!app.ids.values().any(|info| *info == WindowInfo::TrayDrawer));
+    }
+
+    #[test]
+    fn close_context_menu_also_closes_tray_drawer()
+    {
+        let mut app = make_app();
+        let _ = update(&mut app, Message::ToggleTrayDrawer);
+        let _ = update(&mut app, Message::CloseContextMenu);
+        assert!(app.ids.is_empty());
+    }
+
+    #[test]
+    fn tray_icon_clicked_closes_tray_drawer()
+    {
+        let mut app = make_app();
+        let _ = update(&mut app, Message::ToggleTrayDrawer);
+        let _ = update(&mut app, Message::TrayIconClicked(0));
+        assert!(!app.ids.values().any(|info| *info == WindowInfo::TrayDrawer));
+    }
+
+    #[test]
+    fn popup_anchor_resolved_after_close_is_ignored()
+    {
+        let mut app = make_app();
+        let _ = update(&mut app, Message::ToggleTrayDrawer);
+        let id = *app.ids.keys().next().unwrap();
+        let _ = update(&mut app, Message::CloseContextMenu);
+        let _ = update(&mut app, Message::PopupAnchorResolved(id, vec![]));
+        assert!(app.ids.is_empty());
+    }
 }
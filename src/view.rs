@@ -7,10 +7,11 @@ use iced_gif::gif;
 
 
 // ============ CRATES ============
-use crate::{helpers::{misc::{create_button_container, create_button_container_without_hover_message}, string::{convert_text_to_rich_text, convert_text_to_rich_text_ellipsized}, style::{apply_separator, bar_style, orient_text}}, modules::{cpu::define_cpu_text, cpu_temp::{define_cpu_temp_style, define_cpu_temp_text}, focused_window::{define_focused_window_style, define_focused_window_text}, ram::{define_ram_style, define_ram_text}, volume::define_volume_text}};
-use crate::modules::{image::{PreloadedImage, define_image_style}, disk::{define_disk_style, define_disk_text},cpu::define_cpu_style, clock::define_clock_style, custom_modules::{define_custom_module_style, define_custom_module_text}, data::Modules, media_player::{create_media_button, define_button_data, define_media_player_buttons_text, define_media_player_metadata_style, define_media_player_metadata_text}, network::{define_network_style, define_network_text}, tray::{define_tray_icon, define_tray_style}, volume::{define_volume_input_style, define_volume_output_style}, workspaces::{define_workspaces_size, define_workspaces_style, define_workspaces_text}};
+use crate::{helpers::{bindings::{BindingTrigger, InternalAction, bind_module_events, bound_click, module_key}, misc::{create_button_container_without_hover_message, create_icon_button_container_without_hover_message}, string::{convert_text_to_rich_text, convert_text_to_rich_text_ellipsized}, style::{apply_separator, bar_style, module_state, orient_text}}, modules::{cpu::define_cpu_text, taskbar::{define_taskbar_style, define_taskbar_text, visible_taskbar_windows}, cpu_temp::{define_cpu_temp_style, define_cpu_temp_text}, focused_window::{define_focused_window_icon, define_focused_window_style, define_focused_window_text}, binding_mode::{define_binding_mode_style, define_binding_mode_text}, ram::{define_ram_style, define_ram_text}, volume::{define_volume_level, define_volume_text}}};
+use crate::modules::{image::{PreloadedImage, define_image_style, image_click_request}, disk::{define_disk_style, define_disk_text},cpu::define_cpu_style, clock::define_clock_style, custom_modules::{CustomModuleOutputFormat, apply_custom_module_classes, custom_module_click_request, custom_module_json_output, custom_module_tooltip_view, define_custom_module_style, define_custom_module_text}, data::Modules, media_player::{create_media_button, define_button_data, define_media_player_buttons_text, define_media_player_metadata_style, define_media_player_metadata_text}, network::{define_network_style, define_network_text}, tray::{define_tray_icon, define_tray_style, split_tray_items, tray_drawer_view}, volume::{define_volume_input_style, define_volume_output_style}, workspaces::{define_workspace_icons, define_workspaces_size, define_workspaces_style, define_workspaces_text}};
 use crate::ron::{ActionOnClick, BarPosition};
-use crate::context_menu::context_menu_view;
+use crate::helpers::command::CommandRequest;
+use crate::context_menu::{context_menu_view, module_anchor_id};
 use crate::update::Message;
 use crate::{warning::warning_view, MAIN_ID, AppData, WindowInfo, id_info};
 
@@ -36,6 +37,8 @@ pub fn view(app: &AppData, id: iced::window::Id) -> Element<'_, Message>
     match id_info(app, id) 
     {
         Some(WindowInfo::ContextMenu) => return context_menu_view(&app.context_menu_data, &app.ron_config),
+        Some(WindowInfo::TrayDrawer) => return tray_drawer_view(app),
+        Some(WindowInfo::Tooltip) => return custom_module_tooltip_view(app),
         Some(WindowInfo::Warning) => 
         {
             if app.config_parsed_failed
@@ -54,15 +57,20 @@ pub fn view(app: &AppData, id: iced::window::Id) -> Element<'_, Message>
 
 fn main_bar_view(app: &AppData) -> Element<'_, Message>
 {
+    if app.modules_data.binding_data.bar_hidden
+    {
+        return mouse_area(container(Space::new()).width(Length::Fill).height(Length::Fill)).on_press(Message::InternalAction(InternalAction::ToggleBar)).into();
+    }
+
     let axis = match app.ron_config.general.bar_position 
     {
         BarPosition::Left | BarPosition::Right => Axis::Vertical,
         _ => Axis::Horizontal,
     };
 
-    let start  = build_modules(&app.ron_config.general.left_modules, app, axis);
-    let center = build_modules(&app.ron_config.general.center_modules, app, axis);
-    let end    = build_modules(&app.ron_config.general.right_modules, app, axis);
+    let start  = build_modules(&app.ron_config.general.left_modules, "left", app, axis);
+    let center = build_modules(&app.ron_config.general.center_modules, "center", app, axis);
+    let end    = build_modules(&app.ron_config.general.right_modules, "right", app, axis);
     let content = axis_layout(app.ron_config.general.bar_side_spaces_size, axis, start, center, end);
     let fixed_bar_size_y = if app.ron_config.general.bar_size[1] == 0 { app.monitor_size.1 } else { app.ron_config.general.bar_size[1] };
     let fixed_bar_size_x = if app.ron_config.general.bar_size[0] == 0 { app.monitor_size.0 } else { app.ron_config.general.bar_size[0] };
@@ -75,20 +83,31 @@ fn main_bar_view(app: &AppData) -> Element<'_, Message>
 
 
 
-fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis: Axis) -> Element<'a, Message> 
+fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, side: &str, app: &'a AppData, axis: Axis) -> Element<'a, Message> 
 {
     let mut children = Vec::new();
-    for item in list_of_modules
+    for (module_index, item) in list_of_modules.iter().enumerate()
     {
+        let key = module_key(item);
+        if app.modules_data.binding_data.hidden_modules.contains(&key) { continue; }
+        if let Some(condition) = app.ron_config.visible_when.get(&key) && !condition.is_met(app, item) { continue; }
+        let left_click = |default: Message| bound_click(app, item, BindingTrigger::LeftClick, default);
+        let right_click = |default: Message| bound_click(app, item, BindingTrigger::RightClick, default);
         let element: Element<_> = match item
         {   
             Modules::Tray =>
             {
-                let children: Vec<Element<_>> = app.modules_data.tray_icons.iter().enumerate().map(|(i, (icon, _))|
+                let (inline_count, has_overflow) = split_tray_items(app.modules_data.tray_icons.len(), app.ron_config.tray.tray_pinned_items);
+                let mut children: Vec<Element<_>> = app.modules_data.tray_icons.iter().take(inline_count).enumerate().map(|(i, (icon, _))|
                 {
                     let button_content = define_tray_icon(app, icon);
-                    button(button_content).style(|_: &Theme, status: button::Status| define_tray_style(app, status)).padding(app.ron_config.tray.tray_button_size).on_press(Message::TrayIconClicked(i)).into()
+                    button(button_content).style(|_: &Theme, status: button::Status| define_tray_style(app, status)).padding(app.ron_config.tray.tray_button_size).on_press(left_click(Message::TrayIconClicked(i))).into()
                 }).collect();
+                if has_overflow
+                {
+                    let expander_text = text(&app.ron_config.tray.tray_expander_text).font(app.default_font).size(app.ron_config.tray.tray_expander_text_size).center();
+                    children.push(button(expander_text).style(|_: &Theme, status: button::Status| define_tray_style(app, status)).padding(app.ron_config.tray.tray_button_size).on_press(Message::ToggleTrayDrawer).into());
+                }
              
                 let inner: Element<_> = match axis
                 {
@@ -107,33 +126,62 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
             },
              
              
-            // ── HyprWorkspaces / SwayWorkspaces / NiriWorkspaces ─────────────
-            Modules::HyprWorkspaces | Modules::SwayWorkspaces | Modules::NiriWorkspaces =>
+            // ── Workspaces (HyprWorkspaces / SwayWorkspaces / NiriWorkspaces) ─
+            Modules::Workspaces | Modules::HyprWorkspaces | Modules::SwayWorkspaces | Modules::NiriWorkspaces =>
             {
-                let workspace_buttons = app.modules_data.workspace_data.visible_workspaces.iter().map(|i|
+                let workspace_button = |i: &'a i32| -> Element<'a, Message>
                 {
                     let non_color_workspace_text = define_workspaces_text(app, *i);
                     let size = define_workspaces_size(app, *i);
                     let workspace_text = convert_text_to_rich_text(&non_color_workspace_text);
-                    button(workspace_text.wrapping(iced::widget::text::Wrapping::Word).font(app.default_font).size(app.ron_config.workspace.workspace_text_size).center())
+                    let workspace_label: Element<_> = workspace_text.wrapping(iced::widget::text::Wrapping::Word).font(app.default_font).size(app.ron_config.workspace.workspace_text_size).center().into();
+                    let icons = define_workspace_icons(app, *i);
+                    let workspace_content: Element<_> = if icons.is_empty()
+                    {
+                        workspace_label
+                    }
+                    else
+                    {
+                        let icon_size = app.ron_config.workspace.workspace_window_icon_size;
+                        let spacing = app.ron_config.workspace.workspace_window_icon_spacing;
+                        let mut items = vec![workspace_label];
+                        items.extend(icons.into_iter().map(|handle| -> Element<_> { image(handle).width(icon_size).height(icon_size).into() }));
+                        match axis
+                        {
+                            Axis::Horizontal => row(items).spacing(spacing).align_y(Alignment::Center).into(),
+                            Axis::Vertical => column(items).spacing(spacing).align_x(Alignment::Center).into(),
+                        }
+                    };
+                    button(workspace_content)
                     .padding(app.ron_config.workspace.workspace_padding)
                     .style(move |_: &Theme, status: button::Status| define_workspaces_style(app, status, i))
                     .width(size.0)
                     .height(size.1)
-                    .on_press(Message::WorkspaceButtonPressed(*i))
+                    .on_press(left_click(Message::WorkspaceButtonPressed(*i)))
                     .into()
-                });
+                };
+
+                // Groups only differ from a single list with `GroupedByOutput`, a divider then marks where each output starts
+                let (divider_width, divider_height) = match axis
+                {
+                    Axis::Horizontal => (app.ron_config.workspace.workspace_output_divider_width, app.ron_config.workspace.workspace_output_divider_height),
+                    Axis::Vertical => (app.ron_config.workspace.workspace_output_divider_height, app.ron_config.workspace.workspace_output_divider_width),
+                };
+                let divider_color = app.ron_config.workspace.workspace_output_divider_color.to_iced_color();
+                let mut workspace_buttons: Vec<Element<_>> = Vec::new();
+                for (group_index, group) in app.modules_data.workspace_data.workspace_groups.iter().enumerate()
+                {
+                    if group_index > 0
+                    {
+                        workspace_buttons.push(container(Space::new()).width(divider_width).height(divider_height).style(move |_: &Theme| container::Style { background: Some(divider_color.into()), ..Default::default() }).into());
+                    }
+                    workspace_buttons.extend(group.iter().map(&workspace_button));
+                }
              
                 let inner: Element<_> = match axis
                 {
-                    Axis::Horizontal => mouse_area(row(workspace_buttons).align_y(Alignment::Center).spacing(app.ron_config.workspace.workspace_spacing))
-                        .on_enter(Message::IsHoveringWorkspace(true))
-                        .on_exit(Message::IsHoveringWorkspace(false))
-                        .into(),
-                    Axis::Vertical => mouse_area(column(workspace_buttons).align_x(Alignment::Center).spacing(app.ron_config.workspace.workspace_spacing))
-                        .on_enter(Message::IsHoveringWorkspace(true))
-                        .on_exit(Message::IsHoveringWorkspace(false))
-                        .into(),
+                    Axis::Horizontal => row(workspace_buttons).align_y(Alignment::Center).spacing(app.ron_config.workspace.workspace_spacing).into(),
+                    Axis::Vertical => column(workspace_buttons).align_x(Alignment::Center).spacing(app.ron_config.workspace.workspace_spacing).into(),
                 };
              
                 apply_separator
@@ -147,6 +195,52 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
             },
              
              
+            // ── Taskbar ──────────────────────────────────────────────────────
+            Modules::Taskbar =>
+            {
+                let config = &app.ron_config.taskbar;
+                let window_buttons: Vec<Element<_>> = visible_taskbar_windows(app).into_iter().map(|window| -> Element<_>
+                {
+                    let mut items: Vec<Element<_>> = Vec::new();
+                    if config.taskbar_show_icons && let Some(Some(handle)) = app.modules_data.app_icons.get(&window.app_id)
+                    {
+                        items.push(image(handle.clone()).width(config.taskbar_icon_size).height(config.taskbar_icon_size).into());
+                    }
+                    let title = define_taskbar_text(app, window);
+                    if !title.is_empty()
+                    {
+                        items.push(convert_text_to_rich_text_ellipsized(&title, &app.ron_config.general.ellipsis_text, config.taskbar_title_limit_len).font(app.default_font).size(config.taskbar_text_size).into());
+                    }
+                    let content: Element<_> = match axis
+                    {
+                        Axis::Horizontal => row(items).spacing(config.taskbar_icon_spacing).align_y(Alignment::Center).into(),
+                        Axis::Vertical   => column(items).spacing(config.taskbar_icon_spacing).align_x(Alignment::Center).into(),
+                    };
+                    let window_button = button(content)
+                        .padding(config.taskbar_padding)
+                        .style(move |_: &Theme, status: button::Status| define_taskbar_style(app, status, window))
+                        .on_press(left_click(Message::TaskbarWindowPressed(window.id.clone())));
+                    mouse_area(window_button).on_middle_press(Message::TaskbarWindowMiddlePressed(window.id.clone())).into()
+                }).collect();
+                if window_buttons.is_empty() { continue; }
+             
+                let inner: Element<_> = match axis
+                {
+                    Axis::Horizontal => row(window_buttons).spacing(config.taskbar_spacing).align_y(Alignment::Center).into(),
+                    Axis::Vertical   => column(window_buttons).spacing(config.taskbar_spacing).align_x(Alignment::Center).into(),
+                };
+             
+                apply_separator
+                (
+                    inner,
+                    config.taskbar_side_separator,
+                    config.taskbar_side_separator_color.to_iced_color(),
+                    config.taskbar_side_separator_width,
+                    config.taskbar_side_separator_height,
+                )
+            },
+             
+             
             // ── MediaPlayerMetaData ──────────────────────────────────────────
             Modules::MediaPlayerMetaData =>
             {
@@ -156,10 +250,10 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
                 }
              
                 let text_to_send = define_media_player_metadata_text(app);
-                let left_click_metadata_message: Message  = match &app.ron_config.media_player_metadata.action_on_left_click_media_player_metadata  { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Media Player Custom Action".to_string(), true, false)) };
-                let right_click_metadata_message: Message = match &app.ron_config.media_player_metadata.action_on_right_click_media_player_metadata { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Media Player Custom Action".to_string(), false, false)) };
+                let left_click_metadata_message: Message  = match &app.ron_config.media_player_metadata.action_on_left_click_media_player_metadata  { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("MediaPlayerMetaData", custom_action, true)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("MediaPlayerMetaData", script, true)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
+                let right_click_metadata_message: Message = match &app.ron_config.media_player_metadata.action_on_right_click_media_player_metadata { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("MediaPlayerMetaData", custom_action, false)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("MediaPlayerMetaData", script, false)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
                 let colored_formated_metadata = convert_text_to_rich_text_ellipsized::<Message>(&text_to_send, &app.ron_config.general.ellipsis_text, app.ron_config.media_player_metadata.media_player_metadata_text_limit_len);
-                let inner = create_button_container(app, app.ron_config.media_player_metadata.media_player_metadata_padding, (colored_formated_metadata, app.ron_config.media_player_metadata.media_player_metadata_text_size), (Message::IsHoveringMediaPlayerMetaData(true), Message::IsHoveringMediaPlayerMetaData(false)), left_click_metadata_message, right_click_metadata_message, define_media_player_metadata_style);
+                let inner = create_button_container_without_hover_message(app, app.ron_config.media_player_metadata.media_player_metadata_padding, (colored_formated_metadata, app.ron_config.media_player_metadata.media_player_metadata_text_size), left_click(left_click_metadata_message), right_click(right_click_metadata_message), define_media_player_metadata_style);
              
                 apply_separator
                 (
@@ -187,7 +281,7 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
                 let (previous_text, play_pause_text, next_text) = define_media_player_buttons_text(app);
                 let button_data = define_button_data(previous_text, play_pause_text, next_text);
                 let padding = app.ron_config.media_player_button.media_player_button_padding;
-                let media_buttons: Vec<Element<Message>> = button_data.into_iter().map(|(label, message)| { create_media_button(app, padding, label, message) }).collect();
+                let media_buttons: Vec<Element<Message>> = button_data.into_iter().map(|(label, message)| { create_media_button(app, padding, label, left_click(message)) }).collect();
              
                 let inner: Element<_> = match axis
                 {
@@ -207,7 +301,7 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
              
              
             // ── FocusedWindow ────────────────────────────────────────────────
-            Modules::FocusedWindowHypr | Modules::FocusedWindowNiri | Modules::FocusedWindowSway =>
+            Modules::FocusedWindow | Modules::FocusedWindowHypr | Modules::FocusedWindowNiri | Modules::FocusedWindowSway =>
             {
                 let text_to_send = &define_focused_window_text(app);
                 if app.ron_config.focused_window.dont_show_focused_window_if_empty && text_to_send.is_empty() { continue; };
@@ -221,7 +315,9 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
                     ),
                     app.ron_config.focused_window.focused_window_text_size,
                 );
-                let inner = create_button_container_without_hover_message(app, app.ron_config.focused_window.focused_window_padding, text_data, Message::Nothing, Message::Nothing, define_focused_window_style);
+                let config = &app.ron_config.focused_window;
+                let icon = define_focused_window_icon(app).map(|handle| (Element::from(image(handle).width(config.focused_window_icon_size).height(config.focused_window_icon_size)), config.focused_window_icon_spacing, axis));
+                let inner = create_icon_button_container_without_hover_message(app, config.focused_window_padding, icon, text_data, left_click(Message::Nothing), right_click(Message::Nothing), define_focused_window_style);
              
                 apply_separator
                 (
@@ -234,11 +330,39 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
             },
 
 
+            // ── BindingMode ──────────────────────────────────────────────────
+            Modules::BindingMode =>
+            {
+                let text_to_send = &define_binding_mode_text(app);
+                if text_to_send.is_empty() { continue; };
+                let text_data =
+                (
+                    convert_text_to_rich_text_ellipsized
+                    (
+                        text_to_send,
+                        &app.ron_config.general.ellipsis_text,
+                        app.ron_config.binding_mode.binding_mode_text_limit_len,
+                    ),
+                    app.ron_config.binding_mode.binding_mode_text_size,
+                );
+                let inner = create_button_container_without_hover_message(app, app.ron_config.binding_mode.binding_mode_padding, text_data, left_click(Message::Nothing), right_click(Message::Nothing), define_binding_mode_style);
+             
+                apply_separator
+                (
+                    inner,
+                    app.ron_config.binding_mode.binding_mode_side_separator,
+                    app.ron_config.binding_mode.binding_mode_side_separator_color.to_iced_color(),
+                    app.ron_config.binding_mode.binding_mode_side_separator_width,
+                    app.ron_config.binding_mode.binding_mode_side_separator_height,
+                )
+            },
+
+
             // ── Disk ──────────────────────────────────────────────────────────
             Modules::Disk =>
             {
                 let text_data = (convert_text_to_rich_text::<Message>(&define_disk_text(app)), app.ron_config.disk.disk_text_size);
-                let inner = create_button_container_without_hover_message(app, app.ron_config.disk.disk_padding, text_data, Message::Nothing, Message::Nothing, define_disk_style);
+                let inner = create_button_container_without_hover_message(app, app.ron_config.disk.disk_padding, text_data, left_click(Message::Nothing), right_click(Message::Nothing), define_disk_style);
              
                 apply_separator
                 (
@@ -255,7 +379,7 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
             Modules::Ram =>
             {
                 let text_data = (convert_text_to_rich_text::<Message>(&define_ram_text(app)), app.ron_config.ram.ram_text_size);
-                let inner = create_button_container_without_hover_message(app, app.ron_config.ram.ram_padding, text_data, Message::Nothing, Message::Nothing, define_ram_style);
+                let inner = create_button_container_without_hover_message(app, app.ron_config.ram.ram_padding, text_data, left_click(Message::Nothing), right_click(Message::Nothing), define_ram_style);
              
                 apply_separator
                 (
@@ -272,10 +396,10 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
             Modules::Cpu =>
             {
                 let text_to_send = define_cpu_text(app);
-                let left_click_metadata_message: Message  = match &app.ron_config.cpu.action_on_left_click_cpu  { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Cpu Custom Action".to_string(), true, false)) };
-                let right_click_metadata_message: Message = match &app.ron_config.cpu.action_on_right_click_cpu { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Cpu Custom Action".to_string(), false, false)) };
+                let left_click_metadata_message: Message  = match &app.ron_config.cpu.action_on_left_click_cpu  { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("Cpu", custom_action, true)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("Cpu", script, true)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
+                let right_click_metadata_message: Message = match &app.ron_config.cpu.action_on_right_click_cpu { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("Cpu", custom_action, false)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("Cpu", script, false)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
                 let colored_formated_metadata = convert_text_to_rich_text::<Message>(&text_to_send);
-                let inner = create_button_container_without_hover_message(app, app.ron_config.cpu.cpu_padding, (colored_formated_metadata, app.ron_config.cpu.cpu_text_size), left_click_metadata_message, right_click_metadata_message, define_cpu_style);
+                let inner = create_button_container_without_hover_message(app, app.ron_config.cpu.cpu_padding, (colored_formated_metadata, app.ron_config.cpu.cpu_text_size), left_click(left_click_metadata_message), right_click(right_click_metadata_message), define_cpu_style);
              
                 apply_separator
                 (
@@ -295,11 +419,11 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
             // ── CpuTemp ──────────────────────────────────────────────────────
             Modules::CpuTemp =>
             {
-                let left_click_metadata_message: Message  = match &app.ron_config.cpu_temp.action_on_left_click_cpu_temp  { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Cpu Temp Custom Action".to_string(), true, false)) };
-                let right_click_metadata_message: Message = match &app.ron_config.cpu_temp.action_on_right_click_cpu_temp { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Cpu Temp Custom Action".to_string(), false, false)) };
+                let left_click_metadata_message: Message  = match &app.ron_config.cpu_temp.action_on_left_click_cpu_temp  { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("CpuTemp", custom_action, true)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("CpuTemp", script, true)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
+                let right_click_metadata_message: Message = match &app.ron_config.cpu_temp.action_on_right_click_cpu_temp { ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("CpuTemp", custom_action, false)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("CpuTemp", script, false)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
                 let text_to_send = define_cpu_temp_text(app);
                 let colored_cpu_temp = convert_text_to_rich_text::<Message>(&text_to_send);
-                let inner = create_button_container_without_hover_message(app, app.ron_config.cpu_temp.cpu_temp_padding, (colored_cpu_temp, app.ron_config.cpu_temp.cpu_temp_text_size), left_click_metadata_message, right_click_metadata_message, define_cpu_temp_style);
+                let inner = create_button_container_without_hover_message(app, app.ron_config.cpu_temp.cpu_temp_padding, (colored_cpu_temp, app.ron_config.cpu_temp.cpu_temp_text_size), left_click(left_click_metadata_message), right_click(right_click_metadata_message), define_cpu_temp_style);
              
                 apply_separator
                 (
@@ -319,8 +443,8 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
             // ── Network ──────────────────────────────────────────────────────
             Modules::Network =>
             {
-                let left_click_message: Message  = match &app.ron_config.network.action_on_left_click_network  { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::ToggleAltNetwork, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Network Custom Action".to_string(), true, false)) };
-                let right_click_message: Message = match &app.ron_config.network.action_on_right_click_network { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Network Custom Action".to_string(), false, false)) };
+                let left_click_message: Message  = match &app.ron_config.network.action_on_left_click_network  { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::ToggleAltNetwork, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("Network", custom_action, true)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("Network", script, true)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
+                let right_click_message: Message = match &app.ron_config.network.action_on_right_click_network { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("Network", custom_action, false)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("Network", script, false)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
              
                 let (text_size, padding, side_separator, side_separator_color, side_separator_width, side_separator_height) = if app.modules_data.network_data.is_showing_alt_network_module
                 {
@@ -335,7 +459,7 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
              
                 let text_to_send = define_network_text(app);
                 let colored_network_string = convert_text_to_rich_text::<Message>(&text_to_send);
-                let inner = create_button_container_without_hover_message(app, padding, (colored_network_string, text_size), left_click_message, right_click_message, define_network_style);
+                let inner = create_button_container_without_hover_message(app, padding, (colored_network_string, text_size), left_click(left_click_message), right_click(right_click_message), define_network_style);
              
                 apply_separator
                 (
@@ -355,8 +479,8 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
             // ── VolumeOutput ─────────────────────────────────────────────────
             Modules::VolumeOutput =>
             {
-                let left_click_message: Message  = match &app.ron_config.volume_output.action_on_left_click_volume_output  { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::MuteAudioPressedOutput, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Volume Output Custom Action".to_string(), true, false)) };
-                let right_click_message: Message = match &app.ron_config.volume_output.action_on_right_click_volume_output { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Volume Output Custom Action".to_string(), false, false)) };
+                let left_click_message: Message  = match &app.ron_config.volume_output.action_on_left_click_volume_output  { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::MuteAudioPressedOutput, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("VolumeOutput", custom_action, true)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("VolumeOutput", script, true)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
+                let right_click_message: Message = match &app.ron_config.volume_output.action_on_right_click_volume_output { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("VolumeOutput", custom_action, false)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("VolumeOutput", script, false)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
              
                 let (text_orientation, text_size, padding, side_separator, side_separator_color, side_separator_width, side_separator_height) = if app.modules_data.volume_data.volume_output_is_muted
                 {
@@ -375,9 +499,9 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
                     )
                 };
              
-                let text_to_send = define_volume_text(&app.modules_data.volume_data.output_volume_level, text_orientation);
+                let text_to_send = define_volume_text(&define_volume_level(&app.modules_data.volume_data.output_volume_level, app.modules_data.volume_data.volume_output_raw, module_state(app, &Modules::VolumeOutput)), text_orientation);
                 let colored_volume_output_string = convert_text_to_rich_text::<Message>(&text_to_send);
-                let inner = create_button_container(app, padding, (colored_volume_output_string, *text_size), (Message::IsHoveringVolumeOutput(true), Message::IsHoveringVolumeOutput(false)), left_click_message, right_click_message, define_volume_output_style);
+                let inner = create_button_container_without_hover_message(app, padding, (colored_volume_output_string, *text_size), left_click(left_click_message), right_click(right_click_message), define_volume_output_style);
              
                 apply_separator
                 (
@@ -397,8 +521,8 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
             // ── VolumeInput ──────────────────────────────────────────────────
             Modules::VolumeInput =>
             {
-                let left_click_message: Message  = match &app.ron_config.volume_input.action_on_left_click_volume_input  { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::MuteAudioPressedInput, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Volume Input Custom Action".to_string(), true, false)) };
-                let right_click_message: Message = match &app.ron_config.volume_input.action_on_right_click_volume_input { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones, ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Volume Input Custom Action".to_string(), false, false)) };
+                let left_click_message: Message  = match &app.ron_config.volume_input.action_on_left_click_volume_input  { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::MuteAudioPressedInput, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("VolumeInput", custom_action, true)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("VolumeInput", script, true)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
+                let right_click_message: Message = match &app.ron_config.volume_input.action_on_right_click_volume_input { ActionOnClick::Nothing => Message::Nothing, ActionOnClick::DefaultAction => Message::Nothing, ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones), ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones, ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("VolumeInput", custom_action, false)), ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("VolumeInput", script, false)), ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())), ActionOnClick::Internal(action) => Message::InternalAction(action.clone()) };
              
                 let (text_orientation, text_size, padding, side_separator, side_separator_color, side_separator_width, side_separator_height) = if app.modules_data.volume_data.volume_input_is_muted
                 {
@@ -417,9 +541,9 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
                     )
                 };
              
-                let text_to_send = define_volume_text(&app.modules_data.volume_data.input_volume_level, text_orientation);
+                let text_to_send = define_volume_text(&define_volume_level(&app.modules_data.volume_data.input_volume_level, app.modules_data.volume_data.volume_input_raw, module_state(app, &Modules::VolumeInput)), text_orientation);
                 let colored_volume_input_string = convert_text_to_rich_text::<Message>(&text_to_send);
-                let inner = create_button_container(app, padding, (colored_volume_input_string, *text_size), (Message::IsHoveringVolumeInput(true), Message::IsHoveringVolumeInput(false)), left_click_message, right_click_message, define_volume_input_style);
+                let inner = create_button_container_without_hover_message(app, padding, (colored_volume_input_string, *text_size), left_click(left_click_message), right_click(right_click_message), define_volume_input_style);
              
                 apply_separator
                 (
@@ -443,17 +567,23 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
                 {
                     ActionOnClick::Nothing => Message::Nothing,
                     ActionOnClick::DefaultAction => Message::ToggleAltClock,
-                    ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones,
+                    ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones),
                     ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones,
-                    ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Clock Custom Action".to_string(), true, false))
+                    ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("Clock", custom_action, true)),
+                    ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("Clock", script, true)),
+                    ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())),
+                    ActionOnClick::Internal(action) => Message::InternalAction(action.clone())
                 };
                 let right_click_message: Message = match &app.ron_config.clock.action_on_right_click_clock
                 {
                     ActionOnClick::Nothing => Message::Nothing,
                     ActionOnClick::DefaultAction => Message::ToggleAltClock,
                     ActionOnClick::ToggleAltClockAndCycleClockTimezones => Message::ToggleAltClockAndCycleClockTimeZones,
-                    ActionOnClick::CycleClockTimezones => Message::CycleClockTimeZones,
-                    ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand((None, custom_action.to_vec(), "Clock Custom Action".to_string(), false, false))
+                    ActionOnClick::CycleClockTimezones => Message::InternalAction(InternalAction::CycleClockTimezones),
+                    ActionOnClick::CustomAction(custom_action) => Message::CreateCustomModuleCommand(CommandRequest::custom_action("Clock", custom_action, false)),
+                    ActionOnClick::ShellAction(script) => Message::CreateCustomModuleCommand(CommandRequest::shell_action("Clock", script, false)),
+                    ActionOnClick::OpenMenu(menu) => Message::InternalAction(InternalAction::OpenMenu(menu.clone())),
+                    ActionOnClick::Internal(action) => Message::InternalAction(action.clone())
                 };
 
                 let (text_orientation, text_size, padding, separator_flags, separator_color, separator_width, separator_height) = if app.modules_data.clock_data.is_showing_alt_clock
@@ -486,7 +616,7 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
                 };
                 let text_string = orient_text(&app.modules_data.clock_data.current_time, text_orientation);
                 let colored_clock_string = convert_text_to_rich_text::<Message>(&text_string);
-                let inner = create_button_container_without_hover_message(app, padding, (colored_clock_string, text_size), left_click_message, right_click_message, define_clock_style);
+                let inner = create_button_container_without_hover_message(app, padding, (colored_clock_string, text_size), left_click(left_click_message), right_click(right_click_message), define_clock_style);
 
                 apply_separator
                 (
@@ -513,6 +643,8 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
                 {
                     Some((PreloadedImage::Static(handle), _)) => image(handle).content_fit(received_image.content_fit.into()).width(received_image.width).height(received_image.height).into(),
                     Some((PreloadedImage::Gif(frames), _)) => gif(frames.as_ref()).width(received_image.width).height(received_image.height).into(),
+                    // Still waiting for the first `image_command` output
+                    None if !received_image.image_command.is_empty() => container(Space::new()).width(received_image.width).height(received_image.height).into(),
                     None => text("Warning!!!: Parsed Image Doesn't Exists!!!").height(Length::Fill).width(Length::Fill).size(15).center().into()
                 };
 
@@ -524,12 +656,12 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
                             (
                                 element
                             )
-                            .on_right_press(Message::CreateCustomModuleCommand((Some(index), received_image.command_to_exec_on_right_click.clone(), "Image Command".to_string(), false, false)))
+                            .on_right_press(right_click(Message::CreateCustomModuleCommand(image_click_request(index, received_image, false))))
                         )
                         .width(Length::Shrink)
                         .height(Length::Shrink)
                         .padding(received_image.padding)
-                        .on_press(Message::CreateCustomModuleCommand((Some(index), received_image.command_to_exec_on_left_click.clone(), "Image Command".to_string(), true, false)))
+                        .on_press(left_click(Message::CreateCustomModuleCommand(image_click_request(index, received_image, true))))
                         .style(|_, status| {define_image_style(received_image, status)})  
                 ).width(Length::Shrink).height(Length::Shrink).align_y(Alignment::Center);
                 
@@ -562,25 +694,38 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
                 };
                 let text_to_send = orient_text(&text_to_render, &custom_module.text_orientation);
                 let colored_custom_module_string = convert_text_to_rich_text::<Message>(&text_to_send);
+                let classes = custom_module_json_output(app, index).map(|output| output.class.as_slice()).unwrap_or_default();
+                let styled_custom_module = apply_custom_module_classes(custom_module, classes);
 
-                let element = container
+                let custom_module_button = button
                 (
-                        button
-                        (
-                            mouse_area
-                            (
-                                colored_custom_module_string
-                                .align_y(Alignment::Center)
-                                .wrapping(iced::widget::text::Wrapping::Word)
-                                .font(app.default_font)
-                                .size(custom_module.text_size)
-                                .center()
-                            )
-                            .on_right_press(Message::CreateCustomModuleCommand((Some(index), custom_module.command_to_exec_on_right_click.clone(), custom_module.name.clone(), false, custom_module.use_output_as_text)))
-                        )
-                        .on_press(Message::CreateCustomModuleCommand((Some(index), custom_module.command_to_exec_on_left_click.clone(), custom_module.name.clone(), true, custom_module.use_output_as_text)))
-                        .style(|_, status| {define_custom_module_style(custom_module, status)})  
-                ).padding(custom_module.padding).align_y(Alignment::Center);
+                    mouse_area
+                    (
+                        colored_custom_module_string
+                        .align_y(Alignment::Center)
+                        .wrapping(iced::widget::text::Wrapping::Word)
+                        .font(app.default_font)
+                        .size(custom_module.text_size)
+                        .center()
+                    )
+                    .on_right_press(right_click(Message::CreateCustomModuleCommand(custom_module_click_request(index, custom_module, false))))
+                )
+                .on_press(left_click(Message::CreateCustomModuleCommand(custom_module_click_request(index, custom_module, true))))
+                .style(move |_, status| {define_custom_module_style(&styled_custom_module, status)});
+
+                let element = if custom_module.output_format == CustomModuleOutputFormat::Json && custom_module.show_tooltip
+                {
+                    container
+                    (
+                        mouse_area(custom_module_button)
+                        .on_enter(Message::ShowCustomModuleTooltip(index, module_anchor_id(side, module_index)))
+                        .on_exit(Message::HideCustomModuleTooltip)
+                    ).padding(custom_module.padding).align_y(Alignment::Center)
+                }
+                else
+                {
+                    container(custom_module_button).padding(custom_module.padding).align_y(Alignment::Center)
+                };
                 
                 apply_separator
                 (
@@ -596,7 +741,9 @@ fn build_modules<'a>(list_of_modules: &'a Vec<Modules>, app: &'a AppData, axis:
                 )
             }
         };
-        children.push(element);
+        let element = bind_module_events(app, item, element);
+        // Tagged so popups opened from this module can be anchored to its bounds
+        children.push(container(element).id(module_anchor_id(side, module_index)).into());
     }
 
     match axis 